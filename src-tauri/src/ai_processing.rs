@@ -93,6 +93,49 @@ fn verify_sha256(path: &Path, expected_hash: &str) -> Result<bool> {
     Ok(hex_hash == expected_hash)
 }
 
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AiModelStatus {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// All of the model's files are already downloaded. Missing models are fetched on
+    /// first use, so this only says whether that will need a network connection.
+    pub installed: bool,
+}
+
+/// Reports which AI models are on disk, without loading or hash-checking them.
+pub fn installed_models(app_handle: &tauri::AppHandle) -> Vec<AiModelStatus> {
+    let models_dir = get_models_dir(app_handle).ok();
+    let installed = |files: &[&str]| {
+        models_dir
+            .as_ref()
+            .map_or(false, |dir| files.iter().all(|file| dir.join(file).is_file()))
+    };
+
+    vec![
+        AiModelStatus {
+            id: "sam",
+            name: "Subject Selection",
+            installed: installed(&[ENCODER_FILENAME, DECODER_FILENAME]),
+        },
+        AiModelStatus {
+            id: "foreground",
+            name: "Foreground Mask",
+            installed: installed(&[U2NETP_FILENAME]),
+        },
+        AiModelStatus {
+            id: "sky",
+            name: "Sky Mask",
+            installed: installed(&[SKYSEG_FILENAME]),
+        },
+        AiModelStatus {
+            id: "clip",
+            name: "AI Tagging",
+            installed: installed(&[CLIP_MODEL_FILENAME, CLIP_TOKENIZER_FILENAME]),
+        },
+    ]
+}
+
 async fn download_and_verify_model(
     app_handle: &tauri::AppHandle,
     models_dir: &Path,
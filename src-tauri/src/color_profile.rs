@@ -0,0 +1,415 @@
+use glam::{Mat3, Vec3};
+use image::Rgb32FImage;
+use rayon::prelude::*;
+
+const TRC_LUT_SIZE: usize = 4096;
+
+// Bradford-adapted XYZ (D50 PCS) to linear sRGB, row-major.
+const XYZ_D50_TO_LINEAR_SRGB: [f32; 9] = [
+    3.133856, -1.6168667, -0.4906146, -0.9787684, 1.9161415, 0.0334540, 0.0719453, -0.2289914,
+    1.4052427,
+];
+
+enum ToneCurve {
+    Gamma(f32),
+    Table(Vec<f32>),
+    Parametric { kind: u16, params: [f32; 7] },
+}
+
+impl ToneCurve {
+    fn eval(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            ToneCurve::Gamma(g) => x.powf(*g),
+            ToneCurve::Table(table) => {
+                if table.len() == 1 {
+                    return table[0];
+                }
+                let pos = x * (table.len() - 1) as f32;
+                let i = (pos.floor() as usize).min(table.len() - 2);
+                let t = pos - i as f32;
+                table[i] + (table[i + 1] - table[i]) * t
+            }
+            ToneCurve::Parametric { kind, params: p } => {
+                let (g, a, b, c, d, e, f) = (p[0], p[1], p[2], p[3], p[4], p[5], p[6]);
+                match kind {
+                    0 => x.powf(g),
+                    1 => {
+                        if a != 0.0 && x >= -b / a {
+                            (a * x + b).max(0.0).powf(g)
+                        } else {
+                            0.0
+                        }
+                    }
+                    2 => {
+                        if a != 0.0 && x >= -b / a {
+                            (a * x + b).max(0.0).powf(g) + c
+                        } else {
+                            c
+                        }
+                    }
+                    3 => {
+                        if x >= d {
+                            (a * x + b).max(0.0).powf(g)
+                        } else {
+                            c * x
+                        }
+                    }
+                    _ => {
+                        if x >= d {
+                            (a * x + b).max(0.0).powf(g) + e
+                        } else {
+                            c * x + f
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An RGB matrix/TRC ICC profile, which covers the common display and editing
+/// spaces (sRGB, Display P3, Adobe RGB, ProPhoto). LUT-based profiles are not supported.
+pub struct IccProfile {
+    to_linear_srgb: Mat3,
+    trc: [ToneCurve; 3],
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Option<f32> {
+    read_u32(data, offset).map(|v| v as i32 as f32 / 65536.0)
+}
+
+fn find_tag<'a>(data: &'a [u8], signature: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = read_u32(data, 128)? as usize;
+    (0..count).find_map(|i| {
+        let entry = 132 + i * 12;
+        if data.get(entry..entry + 4)? != signature {
+            return None;
+        }
+        let offset = read_u32(data, entry + 4)? as usize;
+        let size = read_u32(data, entry + 8)? as usize;
+        data.get(offset..offset.checked_add(size)?)
+    })
+}
+
+fn parse_xyz(tag: &[u8]) -> Option<Vec3> {
+    if tag.get(0..4)? != b"XYZ " {
+        return None;
+    }
+    Some(Vec3::new(
+        read_s15_fixed16(tag, 8)?,
+        read_s15_fixed16(tag, 12)?,
+        read_s15_fixed16(tag, 16)?,
+    ))
+}
+
+fn parse_curve(tag: &[u8]) -> Option<ToneCurve> {
+    match tag.get(0..4)? {
+        b"curv" => {
+            let count = read_u32(tag, 8)? as usize;
+            match count {
+                0 => Some(ToneCurve::Gamma(1.0)),
+                1 => Some(ToneCurve::Gamma(read_u16(tag, 12)? as f32 / 256.0)),
+                _ => (0..count)
+                    .map(|i| read_u16(tag, 12 + i * 2).map(|v| v as f32 / 65535.0))
+                    .collect::<Option<Vec<f32>>>()
+                    .map(ToneCurve::Table),
+            }
+        }
+        b"para" => {
+            let kind = read_u16(tag, 8)?;
+            let param_count = match kind {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return None,
+            };
+            let mut params = [0.0; 7];
+            for (i, param) in params.iter_mut().take(param_count).enumerate() {
+                *param = read_s15_fixed16(tag, 12 + i * 4)?;
+            }
+            Some(ToneCurve::Parametric { kind, params })
+        }
+        _ => None,
+    }
+}
+
+fn parse_description(tag: &[u8]) -> Option<String> {
+    let text = match tag.get(0..4)? {
+        b"desc" => {
+            let len = read_u32(tag, 8)? as usize;
+            let bytes = tag.get(12..12 + len)?;
+            String::from_utf8_lossy(bytes).to_string()
+        }
+        b"mluc" => {
+            let len = read_u32(tag, 20)? as usize;
+            let offset = read_u32(tag, 24)? as usize;
+            let units: Vec<u16> = tag
+                .get(offset..offset + len)?
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => return None,
+    };
+    let text = text.trim_matches(char::from(0)).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Reads the name of an ICC profile without requiring it to be a supported type.
+pub fn profile_description(data: &[u8]) -> Option<String> {
+    find_tag(data, b"desc").and_then(parse_description)
+}
+
+impl IccProfile {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.get(16..20)? != b"RGB " || data.get(20..24)? != b"XYZ " {
+            return None;
+        }
+
+        let red = parse_xyz(find_tag(data, b"rXYZ")?)?;
+        let green = parse_xyz(find_tag(data, b"gXYZ")?)?;
+        let blue = parse_xyz(find_tag(data, b"bXYZ")?)?;
+        let trc = [
+            parse_curve(find_tag(data, b"rTRC")?)?,
+            parse_curve(find_tag(data, b"gTRC")?)?,
+            parse_curve(find_tag(data, b"bTRC")?)?,
+        ];
+
+        let to_xyz = Mat3::from_cols(red, green, blue);
+        let xyz_to_srgb = Mat3::from_cols_array(&XYZ_D50_TO_LINEAR_SRGB).transpose();
+
+        Some(Self {
+            to_linear_srgb: xyz_to_srgb * to_xyz,
+            trc,
+        })
+    }
+
+    /// True when the profile is sRGB within rounding, so conversion can be skipped.
+    pub fn is_srgb(&self) -> bool {
+        let matrix_matches = self.to_linear_srgb.abs_diff_eq(Mat3::IDENTITY, 0.02);
+        let curves_match = self.trc.iter().all(|curve| {
+            [0.1f32, 0.25, 0.5, 0.75, 0.9]
+                .iter()
+                .all(|&x| (curve.eval(x) - srgb_to_linear(x)).abs() < 0.01)
+        });
+        matrix_matches && curves_match
+    }
+
+    /// Converts pixels encoded in this profile to the sRGB-encoded values the rest
+    /// of the non-RAW pipeline expects. Colors outside sRGB keep values above 1.0 and
+    /// below 0.0, which the linear segment of the sRGB curve carries through to the
+    /// shader unchanged.
+    pub fn convert_to_srgb(&self, image: &mut Rgb32FImage) {
+        let luts: Vec<Vec<f32>> = self
+            .trc
+            .iter()
+            .map(|curve| {
+                (0..TRC_LUT_SIZE)
+                    .map(|i| curve.eval(i as f32 / (TRC_LUT_SIZE - 1) as f32))
+                    .collect()
+            })
+            .collect();
+        let to_linear = |channel: usize, v: f32| -> f32 {
+            let idx = (v.clamp(0.0, 1.0) * (TRC_LUT_SIZE - 1) as f32).round() as usize;
+            luts[channel][idx]
+        };
+        let matrix = self.to_linear_srgb;
+
+        image.par_chunks_mut(3).for_each(|pixel| {
+            let linear = Vec3::new(
+                to_linear(0, pixel[0]),
+                to_linear(1, pixel[1]),
+                to_linear(2, pixel[2]),
+            );
+            let srgb = matrix * linear;
+            pixel[0] = linear_to_srgb(srgb.x);
+            pixel[1] = linear_to_srgb(srgb.y);
+            pixel[2] = linear_to_srgb(srgb.z);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // D50-adapted colorants as published in the sRGB and Display P3 ICC profiles
+    const SRGB_COLORANTS: [[f32; 3]; 3] = [
+        [0.4361, 0.2225, 0.0139],
+        [0.3851, 0.7169, 0.0971],
+        [0.1431, 0.0606, 0.7141],
+    ];
+    const DISPLAY_P3_COLORANTS: [[f32; 3]; 3] = [
+        [0.5151, 0.2412, -0.0011],
+        [0.2920, 0.6922, 0.0419],
+        [0.1571, 0.0666, 0.7841],
+    ];
+
+    fn s15_fixed16(v: f32) -> [u8; 4] {
+        ((v * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    fn xyz_tag(xyz: [f32; 3]) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        xyz.iter().for_each(|&v| tag.extend(s15_fixed16(v)));
+        tag
+    }
+
+    /// The sRGB transfer function as a type 3 parametric curve.
+    fn srgb_trc_tag() -> Vec<u8> {
+        let mut tag = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045]
+            .iter()
+            .for_each(|&v| tag.extend(s15_fixed16(v)));
+        tag
+    }
+
+    fn gamma_trc_tag(gamma: f32) -> Vec<u8> {
+        let mut tag = b"curv\0\0\0\0\0\0\0\x01".to_vec();
+        tag.extend(((gamma * 256.0).round() as u16).to_be_bytes());
+        tag
+    }
+
+    fn desc_tag(name: &str) -> Vec<u8> {
+        let mut tag = b"desc\0\0\0\0".to_vec();
+        tag.extend((name.len() as u32).to_be_bytes());
+        tag.extend(name.as_bytes());
+        tag
+    }
+
+    /// Assembles a matrix/TRC profile: a 128 byte header, the tag table, then tag data.
+    fn build_profile(color_space: &[u8; 4], tags: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+        let mut header = vec![0u8; 128];
+        header[16..20].copy_from_slice(color_space);
+        header[20..24].copy_from_slice(b"XYZ ");
+
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        let data_start = 128 + 4 + tags.len() * 12;
+        for (signature, tag) in &tags {
+            table.extend(*signature);
+            table.extend(((data_start + data.len()) as u32).to_be_bytes());
+            table.extend((tag.len() as u32).to_be_bytes());
+            data.extend(tag);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+        [header, table, data].concat()
+    }
+
+    fn rgb_profile(colorants: [[f32; 3]; 3], trc: Vec<u8>, name: &str) -> Vec<u8> {
+        build_profile(
+            b"RGB ",
+            vec![
+                (b"desc", desc_tag(name)),
+                (b"rXYZ", xyz_tag(colorants[0])),
+                (b"gXYZ", xyz_tag(colorants[1])),
+                (b"bXYZ", xyz_tag(colorants[2])),
+                (b"rTRC", trc.clone()),
+                (b"gTRC", trc.clone()),
+                (b"bTRC", trc),
+            ],
+        )
+    }
+
+    fn convert(profile: &IccProfile, rgb: [f32; 3]) -> [f32; 3] {
+        let mut image = Rgb32FImage::from_raw(1, 1, rgb.to_vec()).unwrap();
+        profile.convert_to_srgb(&mut image);
+        image.get_pixel(0, 0).0
+    }
+
+    #[test]
+    fn srgb_profile_is_recognised_and_named() {
+        let data = rgb_profile(SRGB_COLORANTS, srgb_trc_tag(), "sRGB IEC61966-2.1");
+        let profile = IccProfile::parse(&data).unwrap();
+        assert!(profile.is_srgb());
+        assert_eq!(
+            profile_description(&data).as_deref(),
+            Some("sRGB IEC61966-2.1")
+        );
+
+        let converted = convert(&profile, [0.2, 0.5, 0.8]);
+        for (actual, expected) in converted.iter().zip([0.2, 0.5, 0.8]) {
+            assert!((actual - expected).abs() < 0.005, "{:?}", converted);
+        }
+    }
+
+    #[test]
+    fn display_p3_red_maps_outside_srgb_without_clipping() {
+        let data = rgb_profile(DISPLAY_P3_COLORANTS, srgb_trc_tag(), "Display P3");
+        let profile = IccProfile::parse(&data).unwrap();
+        assert!(!profile.is_srgb());
+
+        // P3 red is about (1.225, -0.042, -0.020) in linear sRGB
+        let [r, g, b] = convert(&profile, [1.0, 0.0, 0.0]);
+        assert!((srgb_to_linear(r) - 1.225).abs() < 0.01, "r = {}", r);
+        assert!((srgb_to_linear(g) + 0.042).abs() < 0.005, "g = {}", g);
+        assert!((srgb_to_linear(b) + 0.020).abs() < 0.005, "b = {}", b);
+
+        // White stays white
+        let white = convert(&profile, [1.0, 1.0, 1.0]);
+        assert!(white.iter().all(|c| (c - 1.0).abs() < 0.01), "{:?}", white);
+    }
+
+    #[test]
+    fn gamma_curve_profile_decodes_through_its_trc() {
+        let data = rgb_profile(SRGB_COLORANTS, gamma_trc_tag(2.2), "Gamma 2.2");
+        let profile = IccProfile::parse(&data).unwrap();
+
+        let [r, _, _] = convert(&profile, [0.5, 0.5, 0.5]);
+        assert!(
+            (srgb_to_linear(r) - 0.5f32.powf(2.2)).abs() < 0.005,
+            "r = {}",
+            r
+        );
+    }
+
+    #[test]
+    fn non_rgb_or_incomplete_profiles_are_rejected() {
+        let gray = build_profile(
+            b"GRAY",
+            vec![
+                (b"kTRC", gamma_trc_tag(2.2)),
+                (b"desc", desc_tag("Gray Gamma 2.2")),
+            ],
+        );
+        assert!(IccProfile::parse(&gray).is_none());
+        assert_eq!(
+            profile_description(&gray).as_deref(),
+            Some("Gray Gamma 2.2")
+        );
+
+        let mut data = rgb_profile(SRGB_COLORANTS, srgb_trc_tag(), "Truncated");
+        data.truncate(200);
+        assert!(IccProfile::parse(&data).is_none());
+    }
+}
@@ -0,0 +1,200 @@
+use std::io::Cursor;
+
+use base64::{Engine as _, engine::general_purpose};
+use image::{ImageFormat, Rgba, RgbaImage};
+use imageproc::drawing::draw_line_segment_mut;
+use serde::Deserialize;
+
+use crate::image_processing::Crop;
+
+// The editor scales the overlay over the crop box, so there's no point rendering it at
+// the full resolution of a large crop
+const MAX_OVERLAY_DIM: f64 = 2048.0;
+const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+const SPIRAL_STEPS: usize = 10;
+const ARC_SEGMENTS: usize = 24;
+
+const GUIDE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 200]);
+const GUIDE_SHADOW_COLOR: Rgba<u8> = Rgba([0, 0, 0, 110]);
+
+/// Composition guide to draw. The spiral variants name the corner the spiral winds into.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CropGridType {
+    Thirds,
+    GoldenRatio,
+    GoldenSpiralTopLeft,
+    GoldenSpiralTopRight,
+    GoldenSpiralBottomLeft,
+    GoldenSpiralBottomRight,
+    Diagonals,
+}
+
+/// A guide line as a polyline in unit coordinates of the crop, (0, 0) top left.
+type Guide = Vec<(f64, f64)>;
+
+fn grid_lines(fractions: &[f64]) -> Vec<Guide> {
+    fractions
+        .iter()
+        .flat_map(|&f| [vec![(f, 0.0), (f, 1.0)], vec![(0.0, f), (1.0, f)]])
+        .collect()
+}
+
+/// 45° lines from each corner, meeting the opposite side of a square-ish crop. The
+/// lengths are in unit coordinates, so they are corrected for the crop's aspect ratio.
+fn diagonal_guides(aspect: f64) -> Vec<Guide> {
+    let (dx, dy) = if aspect >= 1.0 {
+        (1.0 / aspect, 1.0)
+    } else {
+        (1.0, aspect)
+    };
+    vec![
+        vec![(0.0, 0.0), (dx, dy)],
+        vec![(1.0, 0.0), (1.0 - dx, dy)],
+        vec![(0.0, 1.0), (dx, 1.0 - dy)],
+        vec![(1.0, 1.0), (1.0 - dx, 1.0 - dy)],
+    ]
+}
+
+/// Golden spiral laid out in a golden rectangle `[0, φ] x [0, 1]` that winds into the
+/// bottom-right, together with the square divisions it is built from. Each step cuts a
+/// square off the left, top, right and bottom of what remains, in turn, and draws a
+/// quarter arc inside it.
+fn canonical_golden_spiral() -> Vec<Guide> {
+    let (mut x, mut y, mut w, mut h) = (0.0, 0.0, GOLDEN_RATIO, 1.0);
+    let mut guides = Vec::new();
+    let mut spiral: Guide = Vec::new();
+
+    for step in 0..SPIRAL_STEPS {
+        let (side, center, start_angle, divider) = match step % 4 {
+            0 => {
+                let s = h;
+                let cut = (s, (x + s, y + s), 180.0f64, vec![(x + s, y), (x + s, y + h)]);
+                x += s;
+                w -= s;
+                cut
+            }
+            1 => {
+                let s = w;
+                let cut = (s, (x, y + s), 270.0, vec![(x, y + s), (x + w, y + s)]);
+                y += s;
+                h -= s;
+                cut
+            }
+            2 => {
+                let s = h;
+                let cut = (s, (x + w - s, y), 0.0, vec![(x + w - s, y), (x + w - s, y + h)]);
+                w -= s;
+                cut
+            }
+            _ => {
+                let s = w;
+                let cut = (s, (x + s, y + h - s), 90.0, vec![(x, y + h - s), (x + w, y + h - s)]);
+                h -= s;
+                cut
+            }
+        };
+
+        for i in 0..=ARC_SEGMENTS {
+            let angle = (start_angle + 90.0 * i as f64 / ARC_SEGMENTS as f64).to_radians();
+            spiral.push((center.0 + side * angle.cos(), center.1 + side * angle.sin()));
+        }
+        guides.push(divider);
+    }
+
+    guides.push(spiral);
+    guides
+}
+
+fn golden_spiral_guides(grid_type: CropGridType, aspect: f64) -> Vec<Guide> {
+    let (flip_x, flip_y) = match grid_type {
+        CropGridType::GoldenSpiralTopLeft => (true, true),
+        CropGridType::GoldenSpiralTopRight => (false, true),
+        CropGridType::GoldenSpiralBottomLeft => (true, false),
+        _ => (false, false),
+    };
+
+    canonical_golden_spiral()
+        .into_iter()
+        .map(|guide| {
+            guide
+                .into_iter()
+                .map(|(gx, gy)| {
+                    // Portrait crops get the spiral transposed rather than squashed
+                    let (u, v) = if aspect >= 1.0 {
+                        (gx / GOLDEN_RATIO, gy)
+                    } else {
+                        (gy, gx / GOLDEN_RATIO)
+                    };
+                    (
+                        if flip_x { 1.0 - u } else { u },
+                        if flip_y { 1.0 - v } else { v },
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn guides_for(grid_type: CropGridType, aspect: f64) -> Vec<Guide> {
+    match grid_type {
+        CropGridType::Thirds => grid_lines(&[1.0 / 3.0, 2.0 / 3.0]),
+        CropGridType::GoldenRatio => grid_lines(&[1.0 - 1.0 / GOLDEN_RATIO, 1.0 / GOLDEN_RATIO]),
+        CropGridType::Diagonals => diagonal_guides(aspect),
+        _ => golden_spiral_guides(grid_type, aspect),
+    }
+}
+
+fn draw_guides(guides: &[Guide], width: u32, height: u32) -> RgbaImage {
+    let mut overlay = RgbaImage::new(width, height);
+    let max_x = (width - 1) as f64;
+    let max_y = (height - 1) as f64;
+    let to_pixel = |(u, v): (f64, f64)| ((u * max_x) as f32, (v * max_y) as f32);
+
+    // A faint dark outline keeps the guides visible over bright skies
+    for (color, offset) in [(GUIDE_SHADOW_COLOR, 1.0f32), (GUIDE_COLOR, 0.0)] {
+        for guide in guides {
+            for segment in guide.windows(2) {
+                let (x0, y0) = to_pixel(segment[0]);
+                let (x1, y1) = to_pixel(segment[1]);
+                draw_line_segment_mut(
+                    &mut overlay,
+                    (x0 + offset, y0 + offset),
+                    (x1 + offset, y1 + offset),
+                    color,
+                );
+            }
+        }
+    }
+    overlay
+}
+
+/// Renders composition guides for the crop rectangle as a transparent PNG data URL, at
+/// the crop's aspect ratio so it can be stretched over the crop box.
+#[tauri::command]
+pub fn generate_crop_overlay(
+    crop: Crop,
+    image_dims: (u32, u32),
+    grid_type: CropGridType,
+) -> Result<String, String> {
+    let (image_width, image_height) = (image_dims.0 as f64, image_dims.1 as f64);
+    let crop_width = crop.width.min(image_width - crop.x.max(0.0));
+    let crop_height = crop.height.min(image_height - crop.y.max(0.0));
+    if crop_width < 1.0 || crop_height < 1.0 {
+        return Err("Crop rectangle is outside the image".to_string());
+    }
+
+    let scale = (MAX_OVERLAY_DIM / crop_width.max(crop_height)).min(1.0);
+    let width = ((crop_width * scale).round() as u32).max(2);
+    let height = ((crop_height * scale).round() as u32).max(2);
+
+    let guides = guides_for(grid_type, crop_width / crop_height);
+    let overlay = draw_guides(&guides, width, height);
+
+    let mut buf = Cursor::new(Vec::new());
+    overlay
+        .write_to(&mut buf, ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
+    Ok(format!("data:image/png;base64,{}", base64_str))
+}
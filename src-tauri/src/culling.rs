@@ -8,6 +8,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::{AppHandle, Emitter};
 
 use crate::image_loader;
+use crate::raw_processing::RawDevelopParams;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -124,8 +125,13 @@ fn analyze_image(path: &str, hasher: &image_hasher::Hasher) -> Result<ImageAnaly
     const ANALYSIS_DIM: u32 = 512;
     let file_bytes = std::fs::read(path).map_err(|e| e.to_string())?;
 
-    let img = image_loader::load_base_image_from_bytes(&file_bytes, path, false, 2.5)
-        .map_err(|e| e.to_string())?;
+    let img = image_loader::load_base_image_from_bytes(
+        &file_bytes,
+        path,
+        false,
+        RawDevelopParams::default(),
+    )
+    .map_err(|e| e.to_string())?;
 
     let (width, height) = img.dimensions();
     let thumbnail = img.thumbnail(ANALYSIS_DIM, ANALYSIS_DIM);
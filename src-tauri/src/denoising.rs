@@ -1,6 +1,7 @@
 use crate::formats::is_raw_file;
 use crate::image_loader::load_base_image_from_bytes;
 use crate::image_processing::apply_cpu_default_raw_processing;
+use crate::raw_processing::RawDevelopParams;
 use base64::{engine::general_purpose, Engine as _};
 use image::{DynamicImage, GenericImageView, ImageFormat, Rgb, Rgb32FImage};
 use rayon::prelude::*;
@@ -57,8 +58,9 @@ pub fn denoise_image(
 
     let file_bytes = fs::read(path).map_err(|e| e.to_string())?;
 
-    let mut dynamic_img = load_base_image_from_bytes(&file_bytes, &path_str, false, 2.5)
-        .map_err(|e| e.to_string())?;
+    let mut dynamic_img =
+        load_base_image_from_bytes(&file_bytes, &path_str, false, RawDevelopParams::default())
+            .map_err(|e| e.to_string())?;
 
     if is_raw {
         let _ = app_handle.emit("denoise-progress", "Preparing RAW data...");
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::file_management::ReadFileError;
+
+#[derive(Debug, Clone)]
+pub enum AppError {
+    NotFound(String),
+    FileLocked(String),
+    Io(String),
+    Decode(String),
+    Encode(String),
+    Gpu(String),
+    NoImageLoaded,
+    Busy(String),
+    Cancelled,
+    Other(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "notFound",
+            AppError::FileLocked(_) => "fileLocked",
+            AppError::Io(_) => "io",
+            AppError::Decode(_) => "decode",
+            AppError::Encode(_) => "encode",
+            AppError::Gpu(_) => "gpu",
+            AppError::NoImageLoaded => "noImageLoaded",
+            AppError::Busy(_) => "busy",
+            AppError::Cancelled => "cancelled",
+            AppError::Other(_) => "other",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(msg) => write!(f, "File not found: {}", msg),
+            AppError::FileLocked(msg) => write!(f, "File is locked: {}", msg),
+            AppError::Io(msg) => write!(f, "IO error: {}", msg),
+            AppError::Decode(msg) => write!(f, "Failed to decode image: {}", msg),
+            AppError::Encode(msg) => write!(f, "Failed to encode image: {}", msg),
+            AppError::Gpu(msg) => write!(f, "GPU error: {}", msg),
+            AppError::NoImageLoaded => write!(f, "No original image loaded"),
+            AppError::Busy(msg) => write!(f, "{}", msg),
+            AppError::Cancelled => write!(f, "Operation cancelled"),
+            AppError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<ReadFileError> for AppError {
+    fn from(err: ReadFileError) -> Self {
+        match err {
+            ReadFileError::NotFound => AppError::NotFound(err.to_string()),
+            ReadFileError::Locked => AppError::FileLocked(err.to_string()),
+            ReadFileError::Io(e) => AppError::from(e),
+            ReadFileError::Empty | ReadFileError::Invalid => AppError::Decode(err.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(err.to_string()),
+            _ => AppError::Io(err.to_string()),
+        }
+    }
+}
+
+impl From<image::ImageError> for AppError {
+    fn from(err: image::ImageError) -> Self {
+        match err {
+            image::ImageError::IoError(e) => AppError::from(e),
+            image::ImageError::Encoding(_) => AppError::Encode(err.to_string()),
+            _ => AppError::Decode(err.to_string()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        if let Some(app_err) = err.downcast_ref::<AppError>() {
+            return app_err.clone();
+        }
+        match err.downcast::<std::io::Error>() {
+            Ok(io_err) => AppError::from(io_err),
+            Err(err) => AppError::Other(err.to_string()),
+        }
+    }
+}
+
+impl From<String> for AppError {
+    fn from(msg: String) -> Self {
+        AppError::Other(msg)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(msg: &str) -> Self {
+        AppError::Other(msg.to_string())
+    }
+}
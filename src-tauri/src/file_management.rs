@@ -5,16 +5,18 @@ use std::fmt;
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io::BufReader;
+use std::io::{Read, Write};
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::thread;
 
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::{DateTime, Utc};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use image::codecs::jpeg::JpegEncoder;
 use image::{DynamicImage, GenericImageView, ImageBuffer, Luma};
 use little_exif::exif_tag::ExifTag;
@@ -30,6 +32,7 @@ use uuid::Uuid;
 use walkdir::WalkDir;
 
 use crate::AppState;
+use crate::errors::AppError;
 use crate::formats::{is_raw_file, is_supported_image_file};
 use crate::gpu_processing;
 use crate::image_loader;
@@ -37,12 +40,20 @@ use crate::image_processing::GpuContext;
 use crate::image_processing::{
     Crop, ImageMetadata, apply_coarse_rotation, apply_crop, apply_flip, apply_rotation,
     auto_results_to_json, get_all_adjustments_from_json, perform_auto_analysis, apply_cpu_default_raw_processing,
+    grain_seed,
 };
 use crate::mask_generation::MaskDefinition;
 use crate::preset_converter;
-use crate::tagging::COLOR_TAG_PREFIX;
+use crate::raw_processing::{self, RawDemosaic, RawDevelopParams};
+use crate::xmp_sidecar;
+use crate::tagging::{
+    COLOR_TAG_PREFIX, DEFAULT_TAGGING_CONFIDENCE_THRESHOLD, DEFAULT_TAGGING_MAX_TAGS,
+    color_label_to_xmp_label, invalidate_tag_usage_cache, xmp_label_to_color_label,
+};
 
 const THUMBNAIL_WIDTH: u32 = 640;
+const DEFAULT_PREVIEW_CACHE_SIZE_MB: u32 = 512;
+const DEFAULT_THUMBNAIL_CACHE_SIZE_MB: u32 = 2048;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Preset {
@@ -130,6 +141,22 @@ pub struct LastFolderState {
     pub expanded_folders: Vec<String>,
 }
 
+/// Main window placement in physical pixels. While the window is maximized only
+/// `maximized` and `monitor` are updated, so the bounds are where it restores to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    /// The monitor's name as reported by the OS, used to notice when it's disconnected.
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum PasteMode {
@@ -142,10 +169,11 @@ fn default_included_adjustments() -> HashSet<String> {
         "blacks", "brightness", "clarity", "centré", "chromaticAberrationBlueYellow",
         "chromaticAberrationRedCyan", "colorCalibration", "colorGrading", "colorNoiseReduction",
         "contrast", "curves", "dehaze", "enableNegativeConversion", "exposure", "filmBaseColor",
-        "grainAmount", "grainRoughness", "grainSize", "highlights", "hsl", "lutIntensity",
+        "grainAmount", "grainRoughness", "grainSeed", "grainSize", "highlights", "hsl", "lutIntensity",
         "lutName", "lutPath", "lutSize", "lumaNoiseReduction", "negativeBlueBalance",
         "negativeGreenBalance", "negativeRedBalance", "saturation", "sectionVisibility",
-        "shadows", "sharpness", "showClipping", "structure", "temperature", "tint",
+        "shadows", "sharpeningMode", "sharpeningRadius", "sharpeningThreshold", "sharpness",
+        "showClipping", "structure", "temperature", "tint",
         "toneMapper", "vibrance", "vignetteAmount", "vignetteFeather", "vignetteMidpoint",
         "vignetteRoundness", "whites",
     ]
@@ -203,6 +231,8 @@ pub struct AppSettings {
     pub theme: Option<String>,
     pub transparent: Option<bool>,
     pub decorations: Option<bool>,
+    #[serde(default)]
+    pub window_bounds: Option<WindowBounds>,
     #[serde(alias = "comfyuiAddress")]
     pub ai_connector_address: Option<String>,
     pub last_folder_state: Option<LastFolderState>,
@@ -210,6 +240,10 @@ pub struct AppSettings {
     pub ui_visibility: Option<Value>,
     pub enable_ai_tagging: Option<bool>,
     pub tagging_thread_count: Option<u32>,
+    #[serde(default)]
+    pub tagging_confidence_threshold: Option<f32>,
+    #[serde(default)]
+    pub tagging_max_tags: Option<u32>,
     #[serde(default = "default_tagging_shortcuts_option")]
     pub tagging_shortcuts: Option<Vec<String>>,
     pub thumbnail_size: Option<String>,
@@ -228,6 +262,55 @@ pub struct AppSettings {
     pub processing_backend: Option<String>,
     #[serde(default)]
     pub linux_gpu_optimization: Option<bool>,
+    #[serde(default)]
+    pub enable_preview_disk_cache: Option<bool>,
+    #[serde(default)]
+    pub preview_disk_cache_size_mb: Option<u32>,
+    #[serde(default)]
+    pub thumbnail_cache_size_mb: Option<u32>,
+    #[serde(default)]
+    pub preview_downscale_quality: Option<String>,
+    #[serde(default)]
+    pub enable_perf_metrics: Option<bool>,
+    #[serde(default)]
+    pub export_thread_count: Option<u32>,
+    #[serde(default = "default_export_quality_by_format_option")]
+    pub export_quality_by_format: Option<HashMap<String, u8>>,
+    #[serde(default)]
+    pub camera_highlight_compression: Option<HashMap<String, f32>>,
+    #[serde(default)]
+    pub raw_exposure_bias: Option<f32>,
+    #[serde(default)]
+    pub raw_demosaic: Option<RawDemosaic>,
+    #[serde(default)]
+    pub camera_raw_overrides: Option<HashMap<String, CameraRawOverrides>>,
+    #[serde(default)]
+    pub file_lock_retries: Option<u32>,
+    #[serde(default)]
+    pub show_folder_image_counts: Option<bool>,
+    #[serde(default)]
+    pub hide_empty_folders: Option<bool>,
+}
+
+/// Per-camera corrections for cameras whose reported levels are off, e.g. a black point
+/// that is too low and leaves the shadows muddy. Unset fields keep the decoder's values.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraRawOverrides {
+    #[serde(default)]
+    pub black_level: Option<u32>,
+    #[serde(default)]
+    pub white_level: Option<u32>,
+    #[serde(default)]
+    pub exposure_bias: Option<f32>,
+}
+
+const DEFAULT_EXPORT_JPEG_QUALITY: u8 = 90;
+
+fn default_export_quality_by_format_option() -> Option<HashMap<String, u8>> {
+    let mut map = HashMap::new();
+    map.insert("jpeg".to_string(), DEFAULT_EXPORT_JPEG_QUALITY);
+    Some(map)
 }
 
 fn default_adjustment_visibility() -> HashMap<String, bool> {
@@ -260,12 +343,15 @@ impl Default for AppSettings {
             decorations: Some(true),
             #[cfg(any(target_os = "windows", target_os = "macos"))]
             decorations: Some(false),
+            window_bounds: None,
             ai_connector_address: None,
             last_folder_state: None,
             adaptive_editor_theme: Some(false),
             ui_visibility: None,
             enable_ai_tagging: Some(false),
             tagging_thread_count: Some(3),
+            tagging_confidence_threshold: Some(DEFAULT_TAGGING_CONFIDENCE_THRESHOLD),
+            tagging_max_tags: Some(DEFAULT_TAGGING_MAX_TAGS),
             tagging_shortcuts: default_tagging_shortcuts_option(),
             thumbnail_size: Some("medium".to_string()),
             thumbnail_aspect_ratio: Some("cover".to_string()),
@@ -280,7 +366,77 @@ impl Default for AppSettings {
             linux_gpu_optimization: Some(true),
             #[cfg(not(target_os = "linux"))]
             linux_gpu_optimization: Some(false),
+            enable_preview_disk_cache: Some(false),
+            preview_disk_cache_size_mb: Some(DEFAULT_PREVIEW_CACHE_SIZE_MB),
+            thumbnail_cache_size_mb: Some(DEFAULT_THUMBNAIL_CACHE_SIZE_MB),
+            preview_downscale_quality: Some("box".to_string()),
+            enable_perf_metrics: Some(false),
+            export_thread_count: Some(0),
+            export_quality_by_format: default_export_quality_by_format_option(),
+            camera_highlight_compression: Some(HashMap::new()),
+            raw_exposure_bias: Some(0.0),
+            raw_demosaic: Some(RawDemosaic::Quality),
+            camera_raw_overrides: Some(HashMap::new()),
+            file_lock_retries: Some(DEFAULT_FILE_LOCK_RETRIES),
+            show_folder_image_counts: Some(false),
+            hide_empty_folders: Some(false),
+        }
+    }
+}
+
+impl AppSettings {
+    /// The global RAW development parameters, ignoring any per-camera overrides.
+    pub fn raw_develop_params(&self) -> RawDevelopParams {
+        RawDevelopParams {
+            highlight_compression: self.raw_highlight_compression.unwrap_or(2.5),
+            exposure_bias: self.raw_exposure_bias.unwrap_or(0.0),
+            demosaic: self.raw_demosaic.unwrap_or_default(),
+            ..RawDevelopParams::default()
+        }
+    }
+
+    /// RAW development parameters for a file: the global settings with the overrides for
+    /// its camera from `camera_highlight_compression` and `camera_raw_overrides` applied.
+    /// Both are keyed like `raw_processing::camera_model_key`, case-insensitively.
+    pub fn raw_develop_params_for(&self, source_path: &Path) -> RawDevelopParams {
+        let mut params = self.raw_develop_params();
+        let highlight_overrides = self
+            .camera_highlight_compression
+            .as_ref()
+            .filter(|map| !map.is_empty());
+        let raw_overrides = self.camera_raw_overrides.as_ref().filter(|map| !map.is_empty());
+        if highlight_overrides.is_none() && raw_overrides.is_none() {
+            return params;
+        }
+        if !is_raw_file(&source_path.to_string_lossy()) {
+            return params;
+        }
+
+        let Some(camera_key) = read_file_mapped(source_path).ok().and_then(|mmap| {
+            let bytes: &[u8] = &mmap;
+            std::panic::catch_unwind(|| raw_processing::camera_model_key(bytes))
+                .ok()
+                .flatten()
+        }) else {
+            return params;
+        };
+        fn find<'a, T>(map: Option<&'a HashMap<String, T>>, key: &str) -> Option<&'a T> {
+            map?.iter()
+                .find(|(model, _)| model.trim().eq_ignore_ascii_case(key))
+                .map(|(_, value)| value)
+        }
+
+        if let Some(value) = find(highlight_overrides, &camera_key) {
+            params.highlight_compression = *value;
+        }
+        if let Some(overrides) = find(raw_overrides, &camera_key) {
+            params.black_level = overrides.black_level;
+            params.white_level = overrides.white_level;
+            if let Some(bias) = overrides.exposure_bias {
+                params.exposure_bias = bias;
+            }
         }
+        params
     }
 }
 
@@ -303,6 +459,37 @@ pub struct ImportSettings {
     pub delete_after_import: bool,
 }
 
+/// An image counts as edited once its adjustments hold anything besides the rating.
+pub fn is_adjustments_edited(adjustments: &Value) -> bool {
+    adjustments.as_object().map_or(false, |a| {
+        a.keys().len() > 1 || (a.keys().len() == 1 && !a.contains_key("rating"))
+    })
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ImageEditState {
+    path: String,
+    is_edited: bool,
+    rating: u8,
+}
+
+/// Lets the grid refresh an image's edited badge and rating after its sidecar is rewritten.
+fn emit_edit_state_changed(app_handle: &AppHandle, path: &str, metadata: &ImageMetadata) {
+    let _ = app_handle.emit(
+        "image-edit-state-changed",
+        ImageEditState {
+            path: path.to_string(),
+            is_edited: is_adjustments_edited(&metadata.adjustments),
+            rating: metadata.rating,
+        },
+    );
+}
+
+/// Matches virtual copy sidecars, `<source file name>.<6 hex copy id>.rrdata`.
+static SIDECAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.*)\.([a-f0-9]{6})\.rrdata$").unwrap());
+
 pub fn parse_virtual_path(virtual_path: &str) -> (PathBuf, PathBuf) {
     let (source_path_str, copy_id) =
         if let Some((base, id)) = virtual_path.rsplit_once("?vc=") {
@@ -372,24 +559,87 @@ pub async fn read_exif_for_paths(
     Ok(exif_data)
 }
 
+const LIBRARY_IGNORE_FILE: &str = ".rapidrawignore";
+
+/// Paths excluded from library listings: gitignore-style patterns from a `.rapidrawignore`
+/// at the library root (relative to that file), plus the app's own thumbnail cache.
+pub struct LibraryIgnore {
+    matcher: Option<Gitignore>,
+    excluded_dirs: Vec<PathBuf>,
+}
+
+impl LibraryIgnore {
+    pub fn load(root: &Path, app_handle: &AppHandle) -> Self {
+        let ignore_file = root.join(LIBRARY_IGNORE_FILE);
+        let matcher = if ignore_file.is_file() {
+            let mut builder = GitignoreBuilder::new(root);
+            if let Some(e) = builder.add(&ignore_file) {
+                log::warn!("Problem reading '{}': {}", ignore_file.display(), e);
+            }
+            match builder.build() {
+                Ok(gitignore) => Some(gitignore),
+                Err(e) => {
+                    log::warn!("Invalid patterns in '{}': {}", ignore_file.display(), e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let excluded_dirs = app_handle
+            .path()
+            .app_cache_dir()
+            .map(|dir| dir.join("thumbnails"))
+            .into_iter()
+            .collect();
+
+        Self {
+            matcher,
+            excluded_dirs,
+        }
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.excluded_dirs.iter().any(|dir| path.starts_with(dir)) {
+            return true;
+        }
+        match &self.matcher {
+            Some(matcher) if path != matcher.path() && path.starts_with(matcher.path()) => {
+                matcher.matched_path_or_any_parents(path, is_dir).is_ignore()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `root_path` is the library root whose `.rapidrawignore` applies; defaults to `path`.
 #[tauri::command]
-pub fn list_images_in_dir(path: String) -> Result<Vec<ImageFile>, String> {
+pub fn list_images_in_dir(
+    path: String,
+    root_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Vec<ImageFile>, String> {
+    let ignore_root = root_path.unwrap_or_else(|| path.clone());
+    let library_ignore = LibraryIgnore::load(Path::new(&ignore_root), &app_handle);
     let entries = fs::read_dir(&path).map_err(|e| e.to_string())?;
     let mut image_files = HashMap::new();
     let mut sidecars_by_source = HashMap::new();
 
-    let sidecar_re = Regex::new(r"^(.*)\.([a-f0-9]{6})\.rrdata$").unwrap();
     let original_sidecar_re = Regex::new(r"^(.*)\.rrdata$").unwrap();
 
     for entry in entries.filter_map(Result::ok) {
         let entry_path = entry.path();
+        if library_ignore.is_ignored(&entry_path, entry_path.is_dir()) {
+            continue;
+        }
         let file_name = entry_path.file_name().unwrap_or_default().to_string_lossy();
 
         if is_supported_image_file(&entry_path.to_string_lossy()) {
             let path_str = entry_path.to_string_lossy().into_owned();
             image_files.insert(path_str, entry_path.clone());
         } else if file_name.ends_with(".rrdata") {
-            if let Some(caps) = sidecar_re.captures(&file_name) {
+            if let Some(caps) = SIDECAR_RE.captures(&file_name) {
                 let source_filename = caps.get(1).map_or("", |m| m.as_str());
                 let copy_id = caps.get(2).map_or("", |m| m.as_str());
                 let source_path = Path::new(&path).join(source_filename);
@@ -435,9 +685,7 @@ pub fn list_images_in_dir(path: String) -> Result<Vec<ImageFile>, String> {
             let (is_edited, tags) = if sidecar_path.exists() {
                 if let Ok(content) = fs::read_to_string(sidecar_path) {
                     if let Ok(metadata) = serde_json::from_str::<ImageMetadata>(&content) {
-                        let edited = metadata.adjustments.as_object().map_or(false, |a| {
-                            a.keys().len() > 1 || (a.keys().len() == 1 && !a.contains_key("rating"))
-                        });
+                        let edited = is_adjustments_edited(&metadata.adjustments);
                         (edited, metadata.tags)
                     } else { (false, None) }
                 } else { (false, None) }
@@ -457,16 +705,26 @@ pub fn list_images_in_dir(path: String) -> Result<Vec<ImageFile>, String> {
     Ok(result_list)
 }
 
+/// `root_path` is the library root whose `.rapidrawignore` applies; defaults to `path`.
 #[tauri::command]
-pub fn list_images_recursive(path: String) -> Result<Vec<ImageFile>, String> {
+pub fn list_images_recursive(
+    path: String,
+    root_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Vec<ImageFile>, String> {
+    let ignore_root = root_path.unwrap_or_else(|| path.clone());
+    let library_ignore = LibraryIgnore::load(Path::new(&ignore_root), &app_handle);
     let root_path = Path::new(&path);
     let mut image_files = HashMap::new();
     let mut sidecars_by_source = HashMap::new();
 
-    let sidecar_re = Regex::new(r"^(.*)\.([a-f0-9]{6})\.rrdata$").unwrap();
     let original_sidecar_re = Regex::new(r"^(.*)\.rrdata$").unwrap();
 
-    for entry in WalkDir::new(root_path).into_iter().filter_map(Result::ok) {
+    for entry in WalkDir::new(root_path)
+        .into_iter()
+        .filter_entry(|e| !library_ignore.is_ignored(e.path(), e.file_type().is_dir()))
+        .filter_map(Result::ok)
+    {
         let entry_path = entry.path();
         if !entry_path.is_file() {
             continue;
@@ -478,7 +736,7 @@ pub fn list_images_recursive(path: String) -> Result<Vec<ImageFile>, String> {
             let path_str = entry_path.to_string_lossy().into_owned();
             image_files.insert(path_str, entry_path.to_path_buf());
         } else if file_name.ends_with(".rrdata") {
-            if let Some(caps) = sidecar_re.captures(&file_name) {
+            if let Some(caps) = SIDECAR_RE.captures(&file_name) {
                 let source_filename = caps.get(1).map_or("", |m| m.as_str());
                 let copy_id = caps.get(2).map_or("", |m| m.as_str());
                 if let Some(parent) = entry_path.parent() {
@@ -528,9 +786,7 @@ pub fn list_images_recursive(path: String) -> Result<Vec<ImageFile>, String> {
             let (is_edited, tags) = if sidecar_path.exists() {
                 if let Ok(content) = fs::read_to_string(sidecar_path) {
                     if let Ok(metadata) = serde_json::from_str::<ImageMetadata>(&content) {
-                        let edited = metadata.adjustments.as_object().map_or(false, |a| {
-                            a.keys().len() > 1 || (a.keys().len() == 1 && !a.contains_key("rating"))
-                        });
+                        let edited = is_adjustments_edited(&metadata.adjustments);
                         (edited, metadata.tags)
                     } else { (false, None) }
                 } else { (false, None) }
@@ -556,16 +812,141 @@ pub struct FolderNode {
     pub path: String,
     pub children: Vec<FolderNode>,
     pub is_dir: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_image_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FolderTreeOptions {
+    count_images: bool,
+    hide_empty: bool,
+}
+
+impl FolderTreeOptions {
+    fn new(count_images: Option<bool>, hide_empty: Option<bool>) -> Self {
+        let hide_empty = hide_empty.unwrap_or(false);
+        Self {
+            count_images: count_images.unwrap_or(false) || hide_empty,
+            hide_empty,
+        }
+    }
+}
+
+fn count_images_in_dir(path: &Path, library_ignore: &LibraryIgnore) -> usize {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(std::result::Result::ok)
+                .filter(|entry| {
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    !name.starts_with('.')
+                        && is_supported_image_file(&name)
+                        && entry.file_type().map_or(false, |t| t.is_file())
+                        && !library_ignore.is_ignored(&entry.path(), false)
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Whether `path` or any folder below it holds an image, stopping at the first one found.
+fn subtree_has_images(path: &Path, library_ignore: &LibraryIgnore) -> bool {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| {
+            (e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+                && !library_ignore.is_ignored(e.path(), e.file_type().is_dir())
+        })
+        .filter_map(std::result::Result::ok)
+        .any(|e| e.file_type().is_file() && is_supported_image_file(&e.path().to_string_lossy()))
+}
+
+fn scan_subfolder(
+    folder_path: &Path,
+    options: FolderTreeOptions,
+    depth: Option<usize>,
+    library_ignore: &LibraryIgnore,
+) -> Result<Option<FolderNode>, std::io::Error> {
+    let (sub_children, sub_image_count, total_image_count) = if depth == Some(0) {
+        let count = if options.count_images {
+            count_images_in_dir(folder_path, library_ignore)
+        } else {
+            0
+        };
+        (Vec::new(), count, None)
+    } else {
+        let (sub_children, sub_image_count) =
+            scan_dir_recursive(
+                folder_path,
+                options,
+                depth.map(|d| d - 1),
+                false,
+                library_ignore,
+            )?;
+        let total_image_count = sub_image_count
+            + sub_children
+                .iter()
+                .filter_map(|c| c.total_image_count)
+                .sum::<usize>();
+        (
+            sub_children,
+            sub_image_count,
+            depth.is_none().then_some(total_image_count),
+        )
+    };
+    if options.hide_empty {
+        let is_empty = match total_image_count {
+            Some(total) => total == 0,
+            // Below the depth limit the total isn't known; empty children were already
+            // pruned, so only a leaf's unscanned subtree is left to check
+            None => {
+                sub_image_count == 0
+                    && sub_children.is_empty()
+                    && (depth != Some(0) || !subtree_has_images(folder_path, library_ignore))
+            }
+        };
+        if is_empty {
+            return Ok(None);
+        }
+    }
+    Ok(Some(FolderNode {
+        name: folder_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        path: folder_path.to_string_lossy().into_owned(),
+        children: sub_children,
+        is_dir: folder_path.is_dir(),
+        image_count: options.count_images.then_some(sub_image_count),
+        total_image_count: total_image_count.filter(|_| options.count_images),
+    }))
 }
 
-fn scan_dir_recursive(path: &Path) -> Result<Vec<FolderNode>, std::io::Error> {
-    let mut children = Vec::new();
+/// Returns the sub-folders of `path` and the number of images directly inside it.
+/// Images are only counted (by extension) when `options.count_images` is set.
+/// With `depth` set, sub-folders at the last level are returned without children;
+/// subtree totals are then unknown, so `total_image_count` is omitted, though
+/// `options.hide_empty` still prunes folders with no images at any depth.
+/// `parallel` scans the immediate sub-folders on the rayon pool.
+fn scan_dir_recursive(
+    path: &Path,
+    options: FolderTreeOptions,
+    depth: Option<usize>,
+    parallel: bool,
+    library_ignore: &LibraryIgnore,
+) -> Result<(Vec<FolderNode>, usize), std::io::Error> {
+    let mut subfolders = Vec::new();
+    let mut image_count = 0;
 
     let entries = match fs::read_dir(path) {
         Ok(entries) => entries,
         Err(e) => {
             log::warn!("Could not scan directory '{}': {}", path.display(), e);
-            return Ok(Vec::new());
+            return Ok((Vec::new(), 0));
         }
     };
 
@@ -575,28 +956,45 @@ fn scan_dir_recursive(path: &Path) -> Result<Vec<FolderNode>, std::io::Error> {
             .file_name()
             .and_then(|s| s.to_str())
             .map_or(false, |s| s.starts_with('.'));
+        let is_dir = current_path.is_dir();
+        if is_hidden || library_ignore.is_ignored(&current_path, is_dir) {
+            continue;
+        }
 
-        if current_path.is_dir() && !is_hidden {
-            let sub_children = scan_dir_recursive(&current_path)?;
-            children.push(FolderNode {
-                name: current_path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .into_owned(),
-                path: current_path.to_string_lossy().into_owned(),
-                children: sub_children,
-                is_dir: current_path.is_dir(),
-            });
+        if is_dir {
+            subfolders.push(current_path);
+        } else if options.count_images
+            && is_supported_image_file(&current_path.to_string_lossy())
+            && entry.file_type().map_or(false, |t| t.is_file())
+        {
+            image_count += 1;
         }
     }
 
+    let scanned: Vec<Option<FolderNode>> = if parallel {
+        subfolders
+            .par_iter()
+            .map(|folder| scan_subfolder(folder, options, depth, library_ignore))
+            .collect::<Result<_, _>>()?
+    } else {
+        subfolders
+            .iter()
+            .map(|folder| scan_subfolder(folder, options, depth, library_ignore))
+            .collect::<Result<_, _>>()?
+    };
+    let mut children: Vec<FolderNode> = scanned.into_iter().flatten().collect();
+
     children.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
 
-    Ok(children)
+    Ok((children, image_count))
 }
 
-fn get_folder_tree_sync(path: String) -> Result<FolderNode, String> {
+fn get_folder_tree_sync(
+    path: String,
+    options: FolderTreeOptions,
+    depth: Option<usize>,
+    library_ignore: &LibraryIgnore,
+) -> Result<FolderNode, String> {
     let root_path = Path::new(&path);
     if !root_path.is_dir() {
         return Err(format!(
@@ -609,29 +1007,88 @@ fn get_folder_tree_sync(path: String) -> Result<FolderNode, String> {
         .unwrap_or_default()
         .to_string_lossy()
         .into_owned();
-    let children = scan_dir_recursive(root_path).map_err(|e| e.to_string())?;
+    let (children, image_count) =
+        scan_dir_recursive(root_path, options, depth, true, library_ignore)
+            .map_err(|e| e.to_string())?;
+    let total_image_count = image_count
+        + children
+            .iter()
+            .filter_map(|c| c.total_image_count)
+            .sum::<usize>();
     Ok(FolderNode {
         name,
         path: path.clone(),
         children,
         is_dir: root_path.is_dir(),
+        image_count: options.count_images.then_some(image_count),
+        total_image_count: (options.count_images && depth.is_none()).then_some(total_image_count),
     })
 }
 
+/// `root_path` is the library root whose `.rapidrawignore` applies; defaults to `path`.
 #[tauri::command]
-pub async fn get_folder_tree(path: String) -> Result<FolderNode, String> {
-    match tauri::async_runtime::spawn_blocking(move || get_folder_tree_sync(path)).await {
+pub async fn get_folder_tree(
+    path: String,
+    count_images: Option<bool>,
+    hide_empty: Option<bool>,
+    root_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<FolderNode, String> {
+    let options = FolderTreeOptions::new(count_images, hide_empty);
+    match tauri::async_runtime::spawn_blocking(move || {
+        let ignore_root = root_path.unwrap_or_else(|| path.clone());
+        let library_ignore = LibraryIgnore::load(Path::new(&ignore_root), &app_handle);
+        get_folder_tree_sync(path, options, None, &library_ignore)
+    })
+    .await
+    {
         Ok(Ok(folder_node)) => Ok(folder_node),
         Ok(Err(e)) => Err(e),
         Err(e) => Err(format!("Failed to execute folder tree task: {}", e)),
     }
 }
 
+/// Rescans a single directory level, returning `path` with its immediate sub-folders
+/// (each with empty `children`) so the frontend can merge it into a cached tree.
+/// `root_path` is the tree's root, whose `.rapidrawignore` applies.
+#[tauri::command]
+pub async fn refresh_folder_node(
+    path: String,
+    count_images: Option<bool>,
+    root_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<FolderNode, String> {
+    let options = FolderTreeOptions::new(count_images, None);
+    match tauri::async_runtime::spawn_blocking(move || {
+        let ignore_root = root_path.unwrap_or_else(|| path.clone());
+        let library_ignore = LibraryIgnore::load(Path::new(&ignore_root), &app_handle);
+        get_folder_tree_sync(path, options, Some(0), &library_ignore)
+    })
+    .await
+    {
+        Ok(Ok(folder_node)) => Ok(folder_node),
+        Ok(Err(e)) => Err(e),
+        Err(e) => Err(format!("Failed to execute folder refresh task: {}", e)),
+    }
+}
+
+/// `max_depth` limits how many folder levels below each pinned root are scanned.
 #[tauri::command]
-pub async fn get_pinned_folder_trees(paths: Vec<String>) -> Result<Vec<FolderNode>, String> {
+pub async fn get_pinned_folder_trees(
+    paths: Vec<String>,
+    count_images: Option<bool>,
+    hide_empty: Option<bool>,
+    max_depth: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<FolderNode>, String> {
+    let options = FolderTreeOptions::new(count_images, hide_empty);
+    let depth = max_depth.map(|d| d.max(1) - 1);
     let results: Vec<Result<FolderNode, String>> = paths
         .par_iter()
-        .map(|path| get_folder_tree_sync(path.clone()))
+        .map(|path| {
+            let library_ignore = LibraryIgnore::load(Path::new(path), &app_handle);
+            get_folder_tree_sync(path.clone(), options, depth, &library_ignore)
+        })
         .collect();
 
     let mut folder_nodes = Vec::new();
@@ -644,20 +1101,51 @@ pub async fn get_pinned_folder_trees(paths: Vec<String>) -> Result<Vec<FolderNod
     Ok(folder_nodes)
 }
 
-pub fn read_file_mapped(path: &Path) -> Result<Mmap, ReadFileError> {
-    if !path.is_file() {
-        return Err(ReadFileError::Invalid);
+pub const DEFAULT_FILE_LOCK_RETRIES: u32 = 3;
+const MAX_FILE_LOCK_RETRIES: u32 = 10;
+// Doubles after each attempt, so the default three retries wait 50 + 100 + 200 ms
+const FILE_LOCK_RETRY_BASE_DELAY_MS: u64 = 50;
+
+static FILE_LOCK_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_FILE_LOCK_RETRIES);
+
+/// Sets how often `read_file_mapped` retries a file another process still has locked.
+pub fn configure_file_lock_retries(retries: u32) {
+    FILE_LOCK_RETRIES.store(retries.min(MAX_FILE_LOCK_RETRIES), Ordering::Relaxed);
+}
+
+/// Opens `path` with a shared lock. A file that is still being written (a tethered
+/// capture, a cloud sync in progress) is retried with backoff before giving up;
+/// missing and empty files fail immediately.
+fn open_with_shared_lock(path: &Path) -> Result<fs::File, ReadFileError> {
+    let retries = FILE_LOCK_RETRIES.load(Ordering::Relaxed);
+    let mut attempt = 0;
+    loop {
+        let file = fs::File::open(path).map_err(ReadFileError::Io)?;
+        if file.try_lock_shared().is_ok() {
+            return Ok(file);
+        }
+        if attempt >= retries {
+            return Err(ReadFileError::Locked);
+        }
+        drop(file);
+        thread::sleep(std::time::Duration::from_millis(
+            FILE_LOCK_RETRY_BASE_DELAY_MS << attempt,
+        ));
+        attempt += 1;
     }
+}
+
+pub fn read_file_mapped(path: &Path) -> Result<Mmap, ReadFileError> {
     if !path.exists() {
         return Err(ReadFileError::NotFound);
     }
+    if !path.is_file() {
+        return Err(ReadFileError::Invalid);
+    }
     if path.metadata().map_err(ReadFileError::Io)?.len() == 0 {
         return Err(ReadFileError::Empty);
     }
-    let file = fs::File::open(path).map_err(ReadFileError::Io)?;
-    if file.try_lock_shared().is_err() {
-        return Err(ReadFileError::Locked);
-    }
+    let file = open_with_shared_lock(path)?;
     let mmap = unsafe {
         MmapOptions::new()
             .len(file.metadata().map_err(ReadFileError::Io)?.len() as usize)
@@ -686,19 +1174,23 @@ pub fn generate_thumbnail_data(
         .map_or(serde_json::Value::Null, |m| m.adjustments.clone());
 
     let settings = crate::file_management::load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
 
-    let composite_image = if let Some(img) = preloaded_image {
-        image_loader::composite_patches_on_image(img, &adjustments)?
+    let (composite_image, is_embedded_preview) = if let Some(img) = preloaded_image {
+        (image_loader::composite_patches_on_image(img, &adjustments)?, false)
     } else {
-        match read_file_mapped(&source_path) {
+        let raw_params = settings.raw_develop_params_for(&source_path);
+        // Decode failures are tagged as such so callers can tell a bad file from an I/O error
+        let as_decode_error =
+            |e: anyhow::Error| anyhow::Error::new(AppError::Decode(e.to_string()));
+        let decoded = match read_file_mapped(&source_path) {
             Ok(mmap) => image_loader::load_and_composite(
                 &mmap,
                 &source_path_str,
                 &adjustments,
                 true,
-                highlight_compression,
-            )?,
+                raw_params,
+            )
+            .map_err(as_decode_error)?,
             Err(e) => {
                 log::warn!(
                     "Failed to memory-map file '{}': {}. Falling back to standard read.",
@@ -713,11 +1205,14 @@ pub fn generate_thumbnail_data(
                     &source_path_str,
                     &adjustments,
                     true,
-                    highlight_compression,
-                )?
+                    raw_params,
+                )
+                .map_err(as_decode_error)?
             }
-        }
+        };
+        (decoded.image, decoded.is_embedded_preview)
     };
+    let is_raw = is_raw && !is_embedded_preview;
 
     if let (Some(context), Some(meta)) = (gpu_context, metadata) {
         if !meta.adjustments.is_null() {
@@ -792,11 +1287,17 @@ pub fn generate_thumbnail_data(
                             unscaled_crop_offset.0 * scale_for_gpu,
                             unscaled_crop_offset.1 * scale_for_gpu,
                         ),
+                        crate::mask_generation::RangeSource {
+                            image: &cropped_preview,
+                            is_linear: is_raw,
+                        },
                     )
                 })
                 .collect();
 
-            let gpu_adjustments = get_all_adjustments_from_json(&meta.adjustments, is_raw);
+            let mut gpu_adjustments = get_all_adjustments_from_json(&meta.adjustments, is_raw);
+            gpu_adjustments.global.grain_scale = scale_for_gpu;
+            gpu_adjustments.global.grain_seed = grain_seed(path_str, &meta.adjustments);
             let lut_path = meta.adjustments["lutPath"].as_str();
             let lut = lut_path.and_then(|p| {
                 let mut cache = state.lut_cache.lock().unwrap();
@@ -819,6 +1320,7 @@ pub fn generate_thumbnail_data(
             if let Ok(processed_image) = gpu_processing::process_and_get_dynamic_image(
                 context,
                 &state,
+                app_handle,
                 &cropped_preview,
                 unique_hash,
                 gpu_adjustments,
@@ -846,6 +1348,115 @@ pub fn generate_thumbnail_data(
     ))
 }
 
+/// Whether a file in a listing can be decoded at all, judged without decoding it.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileHealth {
+    Ok,
+    /// Still being written (e.g. a copy in progress), held open by another program or
+    /// failing to read. Worth trying again later.
+    Locked,
+    /// Empty, truncated or not an image. Stays that way until the file changes.
+    Corrupt,
+}
+
+// A file modified this recently is assumed to still be copying
+const IN_PROGRESS_WRITE_WINDOW: std::time::Duration = std::time::Duration::from_secs(2);
+// How many times a locked file's thumbnail is retried, one write window apart
+const LOCKED_THUMBNAIL_RETRIES: usize = 5;
+const MIN_RAW_FILE_SIZE: u64 = 1024;
+const FILE_TAIL_CHECK_LEN: u64 = 64;
+
+/// Files whose thumbnail failed for good, with the size and modification time they had,
+/// so scrolling past them doesn't decode them again until they change.
+static CORRUPT_FILES: LazyLock<Mutex<HashMap<PathBuf, (u64, u64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn read_file_tail(file: &mut fs::File, len: u64) -> Option<Vec<u8>> {
+    use std::io::{Seek, SeekFrom};
+    let size = file.metadata().ok()?.len();
+    let tail_len = len.min(size);
+    file.seek(SeekFrom::End(-(tail_len as i64))).ok()?;
+    let mut tail = vec![0u8; tail_len as usize];
+    file.read_exact(&mut tail).ok()?;
+    Some(tail)
+}
+
+/// Cheap integrity check: size, lock state, a recognizable header and, for PNG, the end
+/// marker, which catches the common case of a truncated copy. JPEGs aren't checked for
+/// their end marker because phones and cameras append data after it (motion photos, maker
+/// trailers); a truncated JPEG is caught when its data fails to decode instead.
+pub fn check_file_health(path: &Path) -> FileHealth {
+    let Ok(metadata) = fs::metadata(path) else {
+        return FileHealth::Corrupt;
+    };
+    let recently_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map_or(false, |age| age < IN_PROGRESS_WRITE_WINDOW);
+    if recently_modified {
+        return FileHealth::Locked;
+    }
+    if metadata.len() == 0 {
+        return FileHealth::Corrupt;
+    }
+
+    let Ok(mut file) = fs::File::open(path) else {
+        return FileHealth::Locked;
+    };
+    if file.try_lock_shared().is_err() {
+        return FileHealth::Locked;
+    }
+
+    let path_str = path.to_string_lossy();
+    if is_raw_file(&path_str) {
+        return if metadata.len() < MIN_RAW_FILE_SIZE {
+            FileHealth::Corrupt
+        } else {
+            FileHealth::Ok
+        };
+    }
+
+    let mut header = [0u8; 16];
+    let Ok(header_len) = file.read(&mut header) else {
+        return FileHealth::Locked;
+    };
+    let Ok(format) = image::guess_format(&header[..header_len]) else {
+        return FileHealth::Corrupt;
+    };
+    let complete = match format {
+        image::ImageFormat::Png => match read_file_tail(&mut file, FILE_TAIL_CHECK_LEN) {
+            Some(tail) => tail.windows(4).any(|w| w == b"IEND"),
+            None => return FileHealth::Locked,
+        },
+        _ => true,
+    };
+    if complete {
+        FileHealth::Ok
+    } else {
+        FileHealth::Corrupt
+    }
+}
+
+fn file_signature(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((metadata.len(), modified))
+}
+
+fn emit_thumbnail_failed(app_handle: &AppHandle, path_str: &str, health: FileHealth) {
+    let _ = app_handle.emit(
+        "thumbnail-failed",
+        serde_json::json!({ "path": path_str, "status": health }),
+    );
+}
+
 fn encode_thumbnail(image: &DynamicImage) -> Result<Vec<u8>> {
     let thumbnail =
         crate::image_processing::downscale_f32_image(image, THUMBNAIL_WIDTH, THUMBNAIL_WIDTH);
@@ -865,7 +1476,7 @@ fn generate_single_thumbnail_and_cache(
 ) -> Option<(String, u8)> {
     let (source_path, sidecar_path) = parse_virtual_path(path_str);
 
-    let img_mod_time = fs::metadata(source_path)
+    let img_mod_time = fs::metadata(&source_path)
         .ok()?
         .modified()
         .ok()?
@@ -899,23 +1510,98 @@ fn generate_single_thumbnail_and_cache(
 
     if !force_regenerate && cache_path.exists() {
         if let Ok(data) = fs::read(&cache_path) {
+            if let Ok(file) = fs::File::options().write(true).open(&cache_path) {
+                let _ = file.set_modified(std::time::SystemTime::now());
+            }
             let base64_str = general_purpose::STANDARD.encode(&data);
             return Some((format!("data:image/jpeg;base64,{}", base64_str), rating));
         }
     }
 
-    if let Ok(thumb_image) =
-        generate_thumbnail_data(path_str, gpu_context, preloaded_image, app_handle)
-    {
-        if let Ok(thumb_data) = encode_thumbnail(&thumb_image) {
-            let _ = fs::write(&cache_path, &thumb_data);
-            let base64_str = general_purpose::STANDARD.encode(&thumb_data);
-            return Some((format!("data:image/jpeg;base64,{}", base64_str), rating));
+    let signature = file_signature(&source_path);
+    if preloaded_image.is_none() {
+        let known_corrupt = signature.is_some()
+            && CORRUPT_FILES.lock().unwrap().get(&source_path) == signature.as_ref();
+        let health = if known_corrupt {
+            FileHealth::Corrupt
+        } else {
+            check_file_health(&source_path)
+        };
+        if health != FileHealth::Ok {
+            if health == FileHealth::Corrupt && !known_corrupt {
+                log::warn!("Skipping thumbnail for unreadable file '{}'", source_path.display());
+                if let Some(signature) = signature {
+                    CORRUPT_FILES.lock().unwrap().insert(source_path, signature);
+                }
+            }
+            emit_thumbnail_failed(app_handle, path_str, health);
+            return None;
+        }
+    }
+
+    match generate_thumbnail_data(path_str, gpu_context, preloaded_image, app_handle) {
+        Ok(thumb_image) => {
+            if let Ok(thumb_data) = encode_thumbnail(&thumb_image) {
+                let _ = fs::write(&cache_path, &thumb_data);
+                let base64_str = general_purpose::STANDARD.encode(&thumb_data);
+                return Some((format!("data:image/jpeg;base64,{}", base64_str), rating));
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to generate thumbnail for '{}': {}", path_str, e);
+            // The header looked fine but the data didn't decode, so treat it as corrupt too.
+            // Anything else (a read error, a bad AI patch) may well succeed next time.
+            if matches!(e.downcast_ref::<AppError>(), Some(AppError::Decode(_))) {
+                if let Some(signature) = signature {
+                    CORRUPT_FILES.lock().unwrap().insert(source_path, signature);
+                }
+                emit_thumbnail_failed(app_handle, path_str, FileHealth::Corrupt);
+            }
         }
     }
     None
 }
 
+// Number of interactive thumbnail batches in flight; pre-generation waits while any are running
+static INTERACTIVE_THUMBNAIL_JOBS: AtomicUsize = AtomicUsize::new(0);
+
+struct InteractiveThumbnailJob;
+
+impl InteractiveThumbnailJob {
+    fn start() -> Self {
+        INTERACTIVE_THUMBNAIL_JOBS.fetch_add(1, Ordering::SeqCst);
+        InteractiveThumbnailJob
+    }
+}
+
+impl Drop for InteractiveThumbnailJob {
+    fn drop(&mut self) {
+        INTERACTIVE_THUMBNAIL_JOBS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn thumbnail_cache_budget(app_handle: &AppHandle) -> u64 {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    settings
+        .thumbnail_cache_size_mb
+        .unwrap_or(DEFAULT_THUMBNAIL_CACHE_SIZE_MB) as u64
+        * 1024
+        * 1024
+}
+
+fn cache_dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
 #[tauri::command]
 pub async fn generate_thumbnails(
     paths: Vec<String>,
@@ -923,6 +1609,7 @@ pub async fn generate_thumbnails(
 ) -> Result<HashMap<String, String>, String> {
     let app_handle_clone = app_handle.clone();
     tauri::async_runtime::spawn_blocking(move || {
+        let _job = InteractiveThumbnailJob::start();
         let cache_dir = app_handle_clone
             .path()
             .app_cache_dir()
@@ -950,6 +1637,7 @@ pub async fn generate_thumbnails(
             })
             .collect();
 
+        evict_cache_dir(&thumb_cache_dir, thumbnail_cache_budget(&app_handle_clone));
         Ok(thumbnails)
     })
     .await
@@ -990,8 +1678,13 @@ pub fn generate_thumbnails_progressive(
     let completed_count = Arc::new(AtomicUsize::new(0));
 
     pool.spawn(move || {
+        let job = InteractiveThumbnailJob::start();
         let state = app_handle_clone.state::<AppState>();
         let gpu_context = gpu_processing::get_or_init_gpu_context(&state).ok();
+        let locked_paths = Mutex::new(Vec::new());
+        let is_locked = |path_str: &str| {
+            check_file_health(&parse_virtual_path(path_str).0) == FileHealth::Locked
+        };
 
         let _ = paths.par_iter().try_for_each(|path_str| -> Result<(), ()> {
             if cancellation_token.load(Ordering::Relaxed) {
@@ -1015,6 +1708,8 @@ pub fn generate_thumbnails_progressive(
                     "thumbnail-generated",
                     serde_json::json!({ "path": path_str, "data": thumbnail_data, "rating": rating }),
                 );
+            } else if is_locked(path_str) {
+                locked_paths.lock().unwrap().push(path_str.clone());
             }
 
             let completed = completed_count.fetch_add(1, Ordering::Relaxed) + 1;
@@ -1028,20 +1723,171 @@ pub fn generate_thumbnails_progressive(
             Ok(())
         });
 
+        evict_cache_dir(&thumb_cache_dir, thumbnail_cache_budget(&app_handle_clone));
         if !cancellation_token.load(Ordering::Relaxed) {
             let _ = app_handle_clone.emit("thumbnail-generation-complete", true);
         }
-    });
-
-    Ok(())
-}
-
-#[tauri::command]
-pub fn create_folder(path: String) -> Result<(), String> {
-    let path_obj = Path::new(&path);
-    if let (Some(parent), Some(new_folder_name_os)) = (path_obj.parent(), path_obj.file_name()) {
-        if let Some(new_folder_name) = new_folder_name_os.to_str() {
-            if parent.exists() {
+        // Waiting on locked files mustn't hold back pre-generation
+        drop(job);
+
+        // Files that were still being copied get another chance once they have settled
+        let mut locked_paths = locked_paths.into_inner().unwrap();
+        for _ in 0..LOCKED_THUMBNAIL_RETRIES {
+            if locked_paths.is_empty() || cancellation_token.load(Ordering::Relaxed) {
+                break;
+            }
+            std::thread::sleep(IN_PROGRESS_WRITE_WINDOW);
+            locked_paths.retain(|path_str| {
+                if cancellation_token.load(Ordering::Relaxed) {
+                    return false;
+                }
+                match generate_single_thumbnail_and_cache(
+                    path_str,
+                    &thumb_cache_dir,
+                    gpu_context.as_ref(),
+                    None,
+                    false,
+                    &app_handle_clone,
+                ) {
+                    Some((thumbnail_data, rating)) => {
+                        let _ = app_handle_clone.emit(
+                            "thumbnail-generated",
+                            serde_json::json!({ "path": path_str, "data": thumbnail_data, "rating": rating }),
+                        );
+                        false
+                    }
+                    None => is_locked(path_str),
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PregenerationSummary {
+    pub generated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub budget_reached: bool,
+    pub cancelled: bool,
+}
+
+/// Fills the thumbnail cache for every image under `root` ahead of browsing. Runs on a
+/// single background thread and pauses while interactive thumbnail requests are in
+/// flight. Stops once the cache reaches its size budget rather than evicting
+/// thumbnails the user has already browsed.
+#[tauri::command]
+pub fn pregenerate_thumbnails(root: String, app_handle: AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let cancel_token = Arc::new(AtomicBool::new(false));
+    {
+        let mut current_token = state
+            .thumbnail_pregeneration_cancellation_token
+            .lock()
+            .unwrap();
+        current_token.store(true, Ordering::SeqCst);
+        *current_token = cancel_token.clone();
+    }
+
+    let thumb_cache_dir = get_thumb_cache_dir(&app_handle)?;
+    let budget = thumbnail_cache_budget(&app_handle);
+
+    thread::spawn(move || {
+        let mut summary = PregenerationSummary::default();
+        // Listing a whole library can take a while, so it happens here rather than in the command
+        let paths: Vec<String> = match list_images_recursive(root, None, app_handle.clone()) {
+            Ok(images) => images.into_iter().map(|image| image.path).collect(),
+            Err(e) => {
+                log::warn!("Thumbnail pre-generation could not list images: {}", e);
+                let _ = app_handle.emit("thumbnail-pregeneration-complete", summary);
+                return;
+            }
+        };
+        let state = app_handle.state::<AppState>();
+        let gpu_context = gpu_processing::get_or_init_gpu_context(&state).ok();
+        let total = paths.len();
+        let mut cache_size = cache_dir_size(&thumb_cache_dir);
+
+        for (index, path_str) in paths.iter().enumerate() {
+            let mut paused = false;
+            while INTERACTIVE_THUMBNAIL_JOBS.load(Ordering::SeqCst) > 0
+                && !cancel_token.load(Ordering::SeqCst)
+            {
+                paused = true;
+                thread::sleep(std::time::Duration::from_millis(100));
+            }
+            // Interactive requests write thumbnails of their own while we wait
+            if paused {
+                cache_size = cache_dir_size(&thumb_cache_dir);
+            }
+            if cancel_token.load(Ordering::SeqCst) {
+                summary.cancelled = true;
+                break;
+            }
+            if cache_size >= budget {
+                summary.budget_reached = true;
+                break;
+            }
+
+            let cached = get_cache_key_hash(path_str)
+                .map(|hash| thumb_cache_dir.join(format!("{}.jpg", hash)))
+                .is_some_and(|cache_path| cache_path.exists());
+            if cached {
+                summary.skipped += 1;
+            } else {
+                let generated = generate_single_thumbnail_and_cache(
+                    path_str,
+                    &thumb_cache_dir,
+                    gpu_context.as_ref(),
+                    None,
+                    false,
+                    &app_handle,
+                );
+                let written = get_cache_key_hash(path_str)
+                    .and_then(|hash| fs::metadata(thumb_cache_dir.join(format!("{}.jpg", hash))).ok());
+                match (generated, written) {
+                    (Some(_), Some(meta)) => {
+                        summary.generated += 1;
+                        cache_size += meta.len();
+                    }
+                    _ => summary.failed += 1,
+                }
+            }
+
+            let _ = app_handle.emit(
+                "thumbnail-pregeneration-progress",
+                serde_json::json!({ "current": index + 1, "total": total }),
+            );
+        }
+
+        log::info!(
+            "Thumbnail pre-generation finished: {} generated, {} already cached, {} failed{}",
+            summary.generated,
+            summary.skipped,
+            summary.failed,
+            if summary.budget_reached {
+                " (cache budget reached)"
+            } else if summary.cancelled {
+                " (cancelled)"
+            } else {
+                ""
+            }
+        );
+        let _ = app_handle.emit("thumbnail-pregeneration-complete", summary);
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn create_folder(path: String) -> Result<(), String> {
+    let path_obj = Path::new(&path);
+    if let (Some(parent), Some(new_folder_name_os)) = (path_obj.parent(), path_obj.file_name()) {
+        if let Some(new_folder_name) = new_folder_name_os.to_str() {
+            if parent.exists() {
                 for entry in fs::read_dir(parent).map_err(|e| e.to_string())? {
                     if let Ok(entry) = entry {
                         if entry.file_name().to_string_lossy().to_lowercase()
@@ -1165,8 +2011,113 @@ fn find_all_associated_files(source_image_path: &Path) -> Result<Vec<PathBuf>, S
     Ok(associated_files)
 }
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileOpProgress {
+    operation: &'static str,
+    files_done: usize,
+    total_files: usize,
+    bytes_done: u64,
+    total_bytes: u64,
+    current_file: String,
+}
+
+/// Copies/renames files while emitting `file-op-progress` events so long transfers
+/// (e.g. RAWs to another drive) don't look like a hang.
+struct FileOpProgressTracker<'a> {
+    app_handle: &'a AppHandle,
+    operation: &'static str,
+    files_done: usize,
+    total_files: usize,
+    bytes_done: u64,
+    total_bytes: u64,
+    last_emitted_bytes: u64,
+}
+
+impl<'a> FileOpProgressTracker<'a> {
+    const CHUNK_SIZE: usize = 1024 * 1024;
+    const EMIT_EVERY_BYTES: u64 = 16 * 1024 * 1024;
+
+    fn new<'p>(
+        app_handle: &'a AppHandle,
+        operation: &'static str,
+        files: impl Iterator<Item = &'p PathBuf>,
+    ) -> Self {
+        let (total_files, total_bytes) = files.fold((0, 0), |(count, bytes), path| {
+            (count + 1, bytes + fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        });
+        Self {
+            app_handle,
+            operation,
+            files_done: 0,
+            total_files,
+            bytes_done: 0,
+            total_bytes,
+            last_emitted_bytes: 0,
+        }
+    }
+
+    fn emit(&mut self, current_file: &Path) {
+        self.last_emitted_bytes = self.bytes_done;
+        let _ = self.app_handle.emit(
+            "file-op-progress",
+            FileOpProgress {
+                operation: self.operation,
+                files_done: self.files_done,
+                total_files: self.total_files,
+                bytes_done: self.bytes_done,
+                total_bytes: self.total_bytes,
+                current_file: current_file.to_string_lossy().into_owned(),
+            },
+        );
+    }
+
+    fn copy_file(&mut self, source: &Path, dest: &Path) -> Result<(), String> {
+        let copy_err = |e: std::io::Error| {
+            format!("Failed to copy {} to {}: {}", source.display(), dest.display(), e)
+        };
+        let mut reader = fs::File::open(source).map_err(copy_err)?;
+        let mut writer = fs::File::create(dest).map_err(copy_err)?;
+        let mut buffer = vec![0u8; Self::CHUNK_SIZE];
+        loop {
+            let read = reader.read(&mut buffer).map_err(copy_err)?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read]).map_err(copy_err)?;
+            self.bytes_done += read as u64;
+            if self.bytes_done - self.last_emitted_bytes >= Self::EMIT_EVERY_BYTES {
+                self.emit(source);
+            }
+        }
+        writer.sync_all().map_err(copy_err)?;
+        if let Ok(metadata) = fs::metadata(source) {
+            let _ = fs::set_permissions(dest, metadata.permissions());
+        }
+        self.files_done += 1;
+        self.emit(source);
+        Ok(())
+    }
+
+    /// Attempts an atomic rename, which only succeeds within one filesystem.
+    fn try_rename(&mut self, source: &Path, dest: &Path) -> bool {
+        let size = fs::metadata(source).map(|m| m.len()).unwrap_or(0);
+        if fs::rename(source, dest).is_err() {
+            return false;
+        }
+        self.bytes_done += size;
+        self.files_done += 1;
+        self.emit(source);
+        true
+    }
+}
+
 #[tauri::command]
-pub fn copy_files(source_paths: Vec<String>, destination_folder: String) -> Result<(), String> {
+pub fn copy_files(
+    source_paths: Vec<String>,
+    destination_folder: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     let dest_path = Path::new(&destination_folder);
     if !dest_path.is_dir() {
         return Err(format!("Destination is not a folder: {}", destination_folder));
@@ -1177,9 +2128,17 @@ pub fn copy_files(source_paths: Vec<String>, destination_folder: String) -> Resu
         .map(|p| parse_virtual_path(p).0)
         .collect();
 
-    for source_image_path in unique_source_images {
-        let all_files_to_copy = find_all_associated_files(&source_image_path)?;
+    let copy_jobs = unique_source_images
+        .into_iter()
+        .map(|path| find_all_associated_files(&path).map(|files| (path, files)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut progress = FileOpProgressTracker::new(
+        &app_handle,
+        "copy",
+        copy_jobs.iter().flat_map(|(_, files)| files.iter()),
+    );
 
+    for (source_image_path, all_files_to_copy) in copy_jobs {
         let source_parent = source_image_path.parent().ok_or("Could not get parent directory")?;
         if source_parent == dest_path {
             let stem = source_image_path.file_stem().and_then(|s| s.to_str()).ok_or("Could not get file stem")?;
@@ -1202,13 +2161,13 @@ pub fn copy_files(source_paths: Vec<String>, destination_folder: String) -> Resu
                 let new_dest_filename = original_full_filename.replacen(&*source_base_filename, &*new_filename, 1);
                 let final_dest_path = dest_path.join(new_dest_filename);
 
-                fs::copy(&original_file, &final_dest_path).map_err(|e| e.to_string())?;
+                progress.copy_file(&original_file, &final_dest_path)?;
             }
         } else {
             for file_to_copy in all_files_to_copy {
                 if let Some(file_name) = file_to_copy.file_name() {
                     let dest_file_path = dest_path.join(file_name);
-                    fs::copy(&file_to_copy, &dest_file_path).map_err(|e| e.to_string())?;
+                    progress.copy_file(&file_to_copy, &dest_file_path)?;
                 }
             }
         }
@@ -1216,8 +2175,14 @@ pub fn copy_files(source_paths: Vec<String>, destination_folder: String) -> Resu
     Ok(())
 }
 
+/// Moves images and their sidecars, renaming in place when source and destination share
+/// a filesystem and falling back to copy + trash otherwise.
 #[tauri::command]
-pub fn move_files(source_paths: Vec<String>, destination_folder: String) -> Result<(), String> {
+pub fn move_files(
+    source_paths: Vec<String>,
+    destination_folder: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     let dest_path = Path::new(&destination_folder);
     if !dest_path.is_dir() {
         return Err(format!("Destination is not a folder: {}", destination_folder));
@@ -1228,8 +2193,7 @@ pub fn move_files(source_paths: Vec<String>, destination_folder: String) -> Resu
         .map(|p| parse_virtual_path(p).0)
         .collect();
 
-    let mut all_files_to_trash = Vec::new();
-
+    let mut move_jobs = Vec::new();
     for source_image_path in unique_source_images {
         let source_parent = source_image_path.parent().ok_or("Could not get parent directory")?;
         if source_parent == dest_path {
@@ -1246,14 +2210,20 @@ pub fn move_files(source_paths: Vec<String>, destination_folder: String) -> Resu
                 }
             }
         }
+        move_jobs.extend(files_to_move);
+    }
 
-        for file_to_move in &files_to_move {
-            if let Some(file_name) = file_to_move.file_name() {
-                let dest_file_path = dest_path.join(file_name);
-                fs::copy(file_to_move, &dest_file_path).map_err(|e| e.to_string())?;
+    let mut progress = FileOpProgressTracker::new(&app_handle, "move", move_jobs.iter());
+    let mut all_files_to_trash = Vec::new();
+
+    for file_to_move in move_jobs {
+        if let Some(file_name) = file_to_move.file_name() {
+            let dest_file_path = dest_path.join(file_name);
+            if !progress.try_rename(&file_to_move, &dest_file_path) {
+                progress.copy_file(&file_to_move, &dest_file_path)?;
+                all_files_to_trash.push(file_to_move);
             }
         }
-        all_files_to_trash.extend(files_to_move);
     }
 
     if !all_files_to_trash.is_empty() {
@@ -1280,20 +2250,23 @@ pub fn save_metadata_and_update_thumbnail(
     let (source_path, sidecar_path) = parse_virtual_path(&path);
     let source_path_str = source_path.to_string_lossy().to_string();
 
-    let mut metadata: ImageMetadata = if sidecar_path.exists() {
-        fs::read_to_string(&sidecar_path)
-            .ok()
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_default()
-    } else {
-        ImageMetadata::default()
-    };
+    let sidecar_lock = lock_sidecar(&sidecar_path);
+    let mut metadata = read_sidecar_metadata(&sidecar_path);
 
     metadata.rating = adjustments["rating"].as_u64().unwrap_or(0) as u8;
     metadata.adjustments = adjustments;
+    if let Ok(source_metadata) = fs::metadata(&source_path) {
+        let source_size = source_metadata.len();
+        if metadata.source_size != Some(source_size) || metadata.source_hash.is_none() {
+            metadata.source_hash = source_fingerprint(&source_path);
+        }
+        metadata.source_size = Some(source_size);
+    }
 
     let json_string = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
     std::fs::write(sidecar_path, json_string).map_err(|e| e.to_string())?;
+    drop(sidecar_lock);
+    emit_edit_state_changed(&app_handle, &path, &metadata);
 
     let loaded_image_lock = state.original_image.lock().unwrap();
     let preloaded_image_option = if let Some(loaded_image) = loaded_image_lock.as_ref() {
@@ -1327,7 +2300,7 @@ pub fn save_metadata_and_update_thumbnail(
             &path_clone,
             &thumb_cache_dir,
             gpu_context.as_ref(),
-            preloaded_image_option.as_ref(),
+            preloaded_image_option.as_deref(),
             true,
             &app_handle_clone,
         );
@@ -1349,23 +2322,67 @@ pub fn save_metadata_and_update_thumbnail(
     Ok(())
 }
 
+/// Expands an include list into concrete adjustment keys. Entries may be section
+/// names (e.g. "color"), top-level keys (e.g. "curves") or nested keys (e.g. "hsl.reds").
+fn expand_included_adjustments(entries: &[String]) -> HashSet<String> {
+    let mut include_set = HashSet::new();
+    for entry in entries {
+        match adjustment_section_keys(entry) {
+            Some(keys) => include_set.extend(keys.iter().map(|k| k.to_string())),
+            None => {
+                include_set.insert(entry.clone());
+            }
+        }
+    }
+    include_set
+}
+
+fn merge_included_adjustments(
+    target: &mut serde_json::Map<String, Value>,
+    source: &serde_json::Map<String, Value>,
+    include_set: &HashSet<String>,
+) {
+    for (key, value) in source {
+        if include_set.contains(key) {
+            target.insert(key.clone(), value.clone());
+            continue;
+        }
+
+        let Some(source_obj) = value.as_object() else {
+            continue;
+        };
+
+        for (sub_key, sub_value) in source_obj {
+            if !include_set.contains(&format!("{}.{}", key, sub_key)) {
+                continue;
+            }
+            let entry = target
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(serde_json::Map::new());
+            }
+            if let Some(target_obj) = entry.as_object_mut() {
+                target_obj.insert(sub_key.clone(), sub_value.clone());
+            }
+        }
+    }
+}
+
 #[tauri::command]
 pub fn apply_adjustments_to_paths(
     paths: Vec<String>,
     adjustments: Value,
+    included_adjustments: Option<Vec<String>>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
+    let include_set = included_adjustments.map(|entries| expand_included_adjustments(&entries));
+
     paths.par_iter().for_each(|path| {
         let (_, sidecar_path) = parse_virtual_path(path);
 
-        let mut existing_metadata: ImageMetadata = if sidecar_path.exists() {
-            fs::read_to_string(&sidecar_path)
-                .ok()
-                .and_then(|content| serde_json::from_str(&content).ok())
-                .unwrap_or_default()
-        } else {
-            ImageMetadata::default()
-        };
+        let _sidecar_lock = lock_sidecar(&sidecar_path);
+        let mut existing_metadata = read_sidecar_metadata(&sidecar_path);
 
         let mut new_adjustments = existing_metadata.adjustments;
         if new_adjustments.is_null() {
@@ -1375,8 +2392,13 @@ pub fn apply_adjustments_to_paths(
         if let (Some(new_map), Some(pasted_map)) =
             (new_adjustments.as_object_mut(), adjustments.as_object())
         {
-            for (k, v) in pasted_map {
-                new_map.insert(k.clone(), v.clone());
+            match &include_set {
+                Some(include_set) => merge_included_adjustments(new_map, pasted_map, include_set),
+                None => {
+                    for (k, v) in pasted_map {
+                        new_map.insert(k.clone(), v.clone());
+                    }
+                }
             }
         }
 
@@ -1384,7 +2406,9 @@ pub fn apply_adjustments_to_paths(
         existing_metadata.adjustments = new_adjustments;
 
         if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-            let _ = std::fs::write(sidecar_path, json_string);
+            if std::fs::write(sidecar_path, json_string).is_ok() {
+                emit_edit_state_changed(&app_handle, path, &existing_metadata);
+            }
         }
     });
 
@@ -1438,14 +2462,8 @@ pub fn reset_adjustments_for_paths(
     paths.par_iter().for_each(|path| {
         let (_, sidecar_path) = parse_virtual_path(path);
 
-        let mut existing_metadata: ImageMetadata = if sidecar_path.exists() {
-            fs::read_to_string(&sidecar_path)
-                .ok()
-                .and_then(|content| serde_json::from_str(&content).ok())
-                .unwrap_or_default()
-        } else {
-            ImageMetadata::default()
-        };
+        let _sidecar_lock = lock_sidecar(&sidecar_path);
+        let mut existing_metadata = read_sidecar_metadata(&sidecar_path);
 
         let new_adjustments = serde_json::json!({
             "rating": existing_metadata.rating
@@ -1454,7 +2472,9 @@ pub fn reset_adjustments_for_paths(
         existing_metadata.adjustments = new_adjustments;
 
         if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-            let _ = std::fs::write(sidecar_path, json_string);
+            if std::fs::write(sidecar_path, json_string).is_ok() {
+                emit_edit_state_changed(&app_handle, path, &existing_metadata);
+            }
         }
     });
 
@@ -1500,13 +2520,168 @@ pub fn reset_adjustments_for_paths(
     Ok(())
 }
 
+fn adjustment_section_keys(section: &str) -> Option<&'static [&'static str]> {
+    match section {
+        "basic" => Some(&[
+            "exposure",
+            "brightness",
+            "contrast",
+            "highlights",
+            "shadows",
+            "whites",
+            "blacks",
+            "toneMapper",
+        ]),
+        "curves" => Some(&["curves"]),
+        "color" => Some(&[
+            "saturation",
+            "temperature",
+            "tint",
+            "vibrance",
+            "hsl",
+            "colorGrading",
+            "colorCalibration",
+        ]),
+        "details" => Some(&[
+            "clarity",
+            "dehaze",
+            "structure",
+            "centré",
+            "sharpness",
+            "sharpeningMode",
+            "sharpeningRadius",
+            "sharpeningThreshold",
+            "lumaNoiseReduction",
+            "colorNoiseReduction",
+            "chromaticAberrationRedCyan",
+            "chromaticAberrationBlueYellow",
+        ]),
+        "effects" => Some(&[
+            "vignetteAmount",
+            "vignetteMidpoint",
+            "vignetteRoundness",
+            "vignetteFeather",
+            "grainAmount",
+            "grainSize",
+            "grainRoughness",
+            "grainSeed",
+            "enableNegativeConversion",
+            "filmBaseColor",
+            "negativeRedBalance",
+            "negativeGreenBalance",
+            "negativeBlueBalance",
+            "lutIntensity",
+            "lutPath",
+            "lutName",
+            "lutData",
+            "lutSize",
+        ]),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub fn reset_adjustment_section_for_paths(
+    paths: Vec<String>,
+    section: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let keys = adjustment_section_keys(&section)
+        .ok_or_else(|| format!("Unknown adjustment section: {}", section))?;
+
+    let affected_paths: Vec<String> = paths
+        .par_iter()
+        .filter(|path| {
+            let (_, sidecar_path) = parse_virtual_path(path);
+            if !sidecar_path.exists() {
+                return false;
+            }
+
+            let _sidecar_lock = lock_sidecar(&sidecar_path);
+            let mut existing_metadata: ImageMetadata = match fs::read_to_string(&sidecar_path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+            {
+                Some(metadata) => metadata,
+                None => return false,
+            };
+
+            let Some(adjustments) = existing_metadata.adjustments.as_object_mut() else {
+                return false;
+            };
+
+            let mut changed = false;
+            for key in keys {
+                changed |= adjustments.remove(*key).is_some();
+            }
+            if !changed {
+                return false;
+            }
+
+            let written = match serde_json::to_string_pretty(&existing_metadata) {
+                Ok(json_string) => std::fs::write(sidecar_path, json_string).is_ok(),
+                Err(_) => false,
+            };
+            if written {
+                emit_edit_state_changed(&app_handle, path, &existing_metadata);
+            }
+            written
+        })
+        .cloned()
+        .collect();
+
+    if affected_paths.is_empty() {
+        return Ok(());
+    }
+
+    thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        let cache_dir = app_handle.path().app_cache_dir().unwrap();
+        let thumb_cache_dir = cache_dir.join("thumbnails");
+        if !thumb_cache_dir.exists() {
+            fs::create_dir_all(&thumb_cache_dir).unwrap();
+        }
+
+        let gpu_context = gpu_processing::get_or_init_gpu_context(&state).ok();
+        let total_count = affected_paths.len();
+        let completed_count = Arc::new(AtomicUsize::new(0));
+
+        affected_paths.par_iter().for_each(|path_str| {
+            let result = generate_single_thumbnail_and_cache(
+                path_str,
+                &thumb_cache_dir,
+                gpu_context.as_ref(),
+                None,
+                true,
+                &app_handle,
+            );
+
+            if let Some((thumbnail_data, rating)) = result {
+                let _ = app_handle.emit(
+                    "thumbnail-generated",
+                    serde_json::json!({ "path": path_str, "data": thumbnail_data, "rating": rating }),
+                );
+            }
+
+            let completed = completed_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let _ = app_handle.emit(
+                "thumbnail-progress",
+                serde_json::json!({ "completed": completed, "total": total_count }),
+            );
+        });
+
+        let _ = app_handle.emit("thumbnail-generation-complete", true);
+    });
+
+    Ok(())
+}
+
 #[tauri::command]
 pub fn apply_auto_adjustments_to_paths(
     paths: Vec<String>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
 
     paths.par_iter().for_each(|path| {
         let result: Result<(), String> = (|| {
@@ -1518,21 +2693,15 @@ pub fn apply_auto_adjustments_to_paths(
                 &file_bytes,
                 &source_path_str,
                 false,
-                highlight_compression,
+                settings.raw_develop_params_for(&source_path),
             )
             .map_err(|e| e.to_string())?;
 
             let auto_results = perform_auto_analysis(&image);
             let auto_adjustments_json = auto_results_to_json(&auto_results);
 
-            let mut existing_metadata: ImageMetadata = if sidecar_path.exists() {
-                fs::read_to_string(&sidecar_path)
-                    .ok()
-                    .and_then(|content| serde_json::from_str(&content).ok())
-                    .unwrap_or_default()
-            } else {
-                ImageMetadata::default()
-            };
+            let _sidecar_lock = lock_sidecar(&sidecar_path);
+            let mut existing_metadata = read_sidecar_metadata(&sidecar_path);
 
             if existing_metadata.adjustments.is_null() {
                 existing_metadata.adjustments = serde_json::json!({});
@@ -1566,7 +2735,9 @@ pub fn apply_auto_adjustments_to_paths(
                 .unwrap_or(0) as u8;
 
             if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-                let _ = std::fs::write(sidecar_path, json_string);
+                if std::fs::write(sidecar_path, json_string).is_ok() {
+                    emit_edit_state_changed(&app_handle, path, &existing_metadata);
+                }
             }
             Ok(())
         })();
@@ -1618,50 +2789,355 @@ pub fn apply_auto_adjustments_to_paths(
 }
 
 #[tauri::command]
-pub fn set_color_label_for_paths(paths: Vec<String>, color: Option<String>) -> Result<(), String> {
-    paths.par_iter().for_each(|path| {
-        let (_, sidecar_path) = parse_virtual_path(path);
-
-        let mut metadata: ImageMetadata = if sidecar_path.exists() {
-            fs::read_to_string(&sidecar_path)
-                .ok()
-                .and_then(|content| serde_json::from_str(&content).ok())
-                .unwrap_or_default()
-        } else {
-            ImageMetadata::default()
-        };
-
-        let mut tags = metadata.tags.unwrap_or_else(Vec::new);
-        tags.retain(|tag| !tag.starts_with(COLOR_TAG_PREFIX));
-
-        if let Some(c) = &color {
-            if !c.is_empty() {
+pub fn set_color_label_for_paths(
+    paths: Vec<String>,
+    color: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let color = color.filter(|c| !c.is_empty());
+    let result = paths.par_iter().try_for_each(|path| {
+        update_sidecar_fields(path, &app_handle, |metadata| {
+            let mut tags = metadata.tags.take().unwrap_or_default();
+            tags.retain(|tag| !tag.starts_with(COLOR_TAG_PREFIX));
+            if let Some(c) = &color {
                 tags.push(format!("{}{}", COLOR_TAG_PREFIX, c));
             }
-        }
+            metadata.tags = if tags.is_empty() { None } else { Some(tags) };
+        })
+    });
 
-        if tags.is_empty() {
-            metadata.tags = None;
-        } else {
-            metadata.tags = Some(tags);
-        }
+    invalidate_tag_usage_cache(&app_handle);
+    result
+}
 
-        if let Ok(json_string) = serde_json::to_string_pretty(&metadata) {
-            let _ = std::fs::write(sidecar_path, json_string);
-        }
-    });
+/// Sidecars currently being rewritten. A path is in the set while a `SidecarLock` for
+/// it is alive; other writers to that path wait on the condvar, while writers to
+/// different sidecars proceed in parallel.
+static SIDECAR_LOCKS: LazyLock<(Mutex<HashSet<PathBuf>>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(HashSet::new()), Condvar::new()));
 
-    Ok(())
+pub struct SidecarLock {
+    sidecar_path: PathBuf,
 }
 
-#[tauri::command]
-pub fn load_metadata(path: String) -> Result<ImageMetadata, String> {
-    let (_, sidecar_path) = parse_virtual_path(&path);
-    if sidecar_path.exists() {
-        let file_content = std::fs::read_to_string(sidecar_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&file_content).map_err(|e| e.to_string())
-    } else {
-        Ok(ImageMetadata::default())
+impl Drop for SidecarLock {
+    fn drop(&mut self) {
+        let (locked, released) = &*SIDECAR_LOCKS;
+        locked.lock().unwrap().remove(&self.sidecar_path);
+        released.notify_all();
+    }
+}
+
+/// Serializes read-modify-write sequences on one sidecar, so two commands updating the
+/// same `.rrdata` at once can't drop each other's changes. Hold the returned guard from
+/// before the read until after the write, and don't lock the same sidecar again while
+/// holding it.
+pub fn lock_sidecar(sidecar_path: &Path) -> SidecarLock {
+    let key = sidecar_lock_key(sidecar_path);
+    let (locked, released) = &*SIDECAR_LOCKS;
+    let mut held = locked.lock().unwrap();
+    while held.contains(&key) {
+        held = released.wait(held).unwrap();
+    }
+    held.insert(key.clone());
+    SidecarLock { sidecar_path: key }
+}
+
+/// Callers spell the same sidecar differently (relative, `..`, symlinked folders), so locks
+/// are keyed by the canonical path. The sidecar itself may not exist yet, so only its
+/// folder is canonicalized.
+fn sidecar_lock_key(sidecar_path: &Path) -> PathBuf {
+    let (Some(parent), Some(file_name)) = (sidecar_path.parent(), sidecar_path.file_name()) else {
+        return sidecar_path.to_path_buf();
+    };
+    let parent = if parent.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent
+    };
+    fs::canonicalize(parent)
+        .map(|dir| dir.join(file_name))
+        .unwrap_or_else(|_| sidecar_path.to_path_buf())
+}
+
+pub fn read_sidecar_metadata(sidecar_path: &Path) -> ImageMetadata {
+    if sidecar_path.exists() {
+        fs::read_to_string(sidecar_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    } else {
+        ImageMetadata::default()
+    }
+}
+
+/// Rewrites a single sidecar after `modify`, leaving the thumbnail alone. Ratings and
+/// color labels don't affect the rendered image, so culling can skip the regeneration
+/// `save_metadata_and_update_thumbnail` would do.
+fn update_sidecar_fields(
+    path: &str,
+    app_handle: &AppHandle,
+    modify: impl FnOnce(&mut ImageMetadata),
+) -> Result<(), String> {
+    let (_, sidecar_path) = parse_virtual_path(path);
+    let _sidecar_lock = lock_sidecar(&sidecar_path);
+    let mut metadata = read_sidecar_metadata(&sidecar_path);
+    modify(&mut metadata);
+
+    let json_string = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(&sidecar_path, json_string).map_err(|e| e.to_string())?;
+    emit_edit_state_changed(app_handle, path, &metadata);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_rating_for_paths(
+    paths: Vec<String>,
+    rating: u8,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let rating = rating.min(5);
+    paths.par_iter().try_for_each(|path| {
+        update_sidecar_fields(path, &app_handle, |metadata| {
+            metadata.rating = rating;
+            if metadata.adjustments.is_null() {
+                metadata.adjustments = serde_json::json!({});
+            }
+            if let Some(adjustments) = metadata.adjustments.as_object_mut() {
+                adjustments.insert("rating".to_string(), serde_json::json!(rating));
+            }
+        })
+    })
+}
+
+/// Applies `criteria` the same way the library grid does (see `sortedImageList` in App.tsx).
+fn filter_image_files(images: Vec<ImageFile>, criteria: &FilterCriteria) -> Vec<ImageFile> {
+    let raw_base_names: HashSet<PathBuf> = if criteria.raw_status == "rawOverNonRaw" {
+        images
+            .iter()
+            .map(|image| parse_virtual_path(&image.path).0)
+            .filter(|source| is_raw_file(&source.to_string_lossy()))
+            .map(|source| source.with_extension(""))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    images
+        .into_iter()
+        .filter(|image| {
+            let (source_path, sidecar_path) = parse_virtual_path(&image.path);
+            let is_raw = is_raw_file(&source_path.to_string_lossy());
+
+            match criteria.raw_status.as_str() {
+                "rawOnly" if !is_raw => return false,
+                "nonRawOnly" if is_raw => return false,
+                "rawOverNonRaw"
+                    if !is_raw && raw_base_names.contains(&source_path.with_extension("")) =>
+                {
+                    return false;
+                }
+                _ => {}
+            }
+
+            if criteria.rating > 0 {
+                let rating = read_sidecar_metadata(&sidecar_path).rating;
+                let passes = if criteria.rating == 5 {
+                    rating == 5
+                } else {
+                    rating >= criteria.rating
+                };
+                if !passes {
+                    return false;
+                }
+            }
+
+            if !criteria.colors.is_empty() {
+                let image_color = image
+                    .tags
+                    .as_ref()
+                    .and_then(|tags| tags.iter().find_map(|t| t.strip_prefix("color:")));
+                let matches = match image_color {
+                    Some(color) => criteria.colors.iter().any(|c| c == color),
+                    None => criteria.colors.iter().any(|c| c == "none"),
+                };
+                if !matches {
+                    return false;
+                }
+            }
+
+            true
+        })
+        .collect()
+}
+
+/// Lists every image under `root_path` (including virtual copies) that passes `criteria`.
+pub fn resolve_filtered_image_paths(
+    root_path: String,
+    criteria: &FilterCriteria,
+    app_handle: AppHandle,
+) -> Result<Vec<String>, String> {
+    let images = list_images_recursive(root_path, None, app_handle)?;
+    Ok(filter_image_files(images, criteria)
+        .into_iter()
+        .map(|image| image.path)
+        .collect())
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualCopyInfo {
+    pub path: String,
+    pub copy_id: String,
+    pub rating: u8,
+    pub is_edited: bool,
+    pub created: u64,
+}
+
+/// Lists the virtual copies of `source_path` (ignoring any `?vc=` suffix), oldest first
+/// by sidecar modification time.
+#[tauri::command]
+pub fn list_virtual_copies(source_path: String) -> Result<Vec<VirtualCopyInfo>, String> {
+    let (source_path, _) = parse_virtual_path(&source_path);
+    let source_name = source_path
+        .file_name()
+        .ok_or_else(|| "Invalid source path".to_string())?
+        .to_string_lossy()
+        .into_owned();
+    let parent = source_path
+        .parent()
+        .ok_or_else(|| "Source path has no parent directory".to_string())?;
+    let source_path_str = source_path.to_string_lossy();
+
+    let mut copies = Vec::new();
+    for entry in fs::read_dir(parent).map_err(|e| e.to_string())?.filter_map(Result::ok) {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some(caps) = SIDECAR_RE.captures(&file_name) else {
+            continue;
+        };
+        if caps.get(1).map_or("", |m| m.as_str()) != source_name {
+            continue;
+        }
+        let copy_id = caps.get(2).map_or("", |m| m.as_str()).to_string();
+
+        let sidecar_path = entry.path();
+        let created = fs::metadata(&sidecar_path)
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let metadata = read_sidecar_metadata(&sidecar_path);
+        let is_edited = is_adjustments_edited(&metadata.adjustments);
+
+        copies.push(VirtualCopyInfo {
+            path: format!("{}?vc={}", source_path_str, copy_id),
+            copy_id,
+            rating: metadata.rating,
+            is_edited,
+            created,
+        });
+    }
+
+    copies.sort_by(|a, b| a.created.cmp(&b.created).then_with(|| a.copy_id.cmp(&b.copy_id)));
+    Ok(copies)
+}
+
+/// Returns the original image path behind a virtual copy path.
+#[tauri::command]
+pub fn get_virtual_copy_source(path: String) -> String {
+    parse_virtual_path(&path).0.to_string_lossy().into_owned()
+}
+
+/// Writes each image's rating and color label into its XMP sidecar so other
+/// applications (Lightroom, Bridge) can pick them up.
+#[tauri::command]
+pub fn export_labels_to_xmp(paths: Vec<String>) -> Result<usize, String> {
+    let written = paths
+        .par_iter()
+        .filter(|path| {
+            let (source_path, sidecar_path) = parse_virtual_path(path);
+            let metadata = read_sidecar_metadata(&sidecar_path);
+            let color_label = metadata.tags.as_ref().and_then(|tags| {
+                tags.iter()
+                    .find_map(|tag| tag.strip_prefix(COLOR_TAG_PREFIX))
+                    .map(color_label_to_xmp_label)
+            });
+
+            let attributes = [
+                ("xmp:Rating", metadata.rating.to_string()),
+                ("xmp:Label", color_label.unwrap_or_default()),
+            ];
+            match xmp_sidecar::update_xmp_sidecar(&source_path, &attributes) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to write XMP labels for {}: {}", path, e);
+                    false
+                }
+            }
+        })
+        .count();
+
+    Ok(written)
+}
+
+/// Reads ratings and color labels from XMP sidecars written by other applications.
+#[tauri::command]
+pub fn import_labels_from_xmp(paths: Vec<String>, app_handle: AppHandle) -> Result<usize, String> {
+    let imported = paths
+        .par_iter()
+        .filter(|path| {
+            let (source_path, sidecar_path) = parse_virtual_path(path);
+            let Ok(xmp_content) = fs::read_to_string(xmp_sidecar::xmp_sidecar_path(&source_path))
+            else {
+                return false;
+            };
+
+            let rating = xmp_sidecar::read_xmp_attribute(&xmp_content, "xmp:Rating")
+                .and_then(|r| r.trim().parse::<i32>().ok());
+            let label = xmp_sidecar::read_xmp_attribute(&xmp_content, "xmp:Label");
+            if rating.is_none() && label.is_none() {
+                return false;
+            }
+
+            let _sidecar_lock = lock_sidecar(&sidecar_path);
+            let mut metadata = read_sidecar_metadata(&sidecar_path);
+            if let Some(rating) = rating {
+                // Adobe uses -1 for rejected images, which has no equivalent here
+                metadata.rating = rating.clamp(0, 5) as u8;
+                if let Some(adjustments) = metadata.adjustments.as_object_mut() {
+                    adjustments.insert("rating".to_string(), serde_json::json!(metadata.rating));
+                }
+            }
+            if let Some(label) = label {
+                let mut tags = metadata.tags.take().unwrap_or_default();
+                tags.retain(|tag| !tag.starts_with(COLOR_TAG_PREFIX));
+                if !label.trim().is_empty() {
+                    tags.push(format!("{}{}", COLOR_TAG_PREFIX, xmp_label_to_color_label(&label)));
+                }
+                metadata.tags = if tags.is_empty() { None } else { Some(tags) };
+            }
+
+            serde_json::to_string_pretty(&metadata)
+                .ok()
+                .map_or(false, |json| fs::write(&sidecar_path, json).is_ok())
+        })
+        .count();
+
+    if imported > 0 {
+        invalidate_tag_usage_cache(&app_handle);
+    }
+    Ok(imported)
+}
+
+#[tauri::command]
+pub fn load_metadata(path: String) -> Result<ImageMetadata, String> {
+    let (_, sidecar_path) = parse_virtual_path(&path);
+    if sidecar_path.exists() {
+        let file_content = std::fs::read_to_string(sidecar_path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&file_content).map_err(|e| e.to_string())
+    } else {
+        Ok(ImageMetadata::default())
     }
 }
 
@@ -1715,66 +3191,540 @@ pub fn load_settings(app_handle: AppHandle) -> Result<AppSettings, String> {
     if !path.exists() {
         return Ok(AppSettings::default());
     }
-    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
-}
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_settings(settings: AppSettings, app_handle: AppHandle) -> Result<(), String> {
+    crate::configure_perf_metrics(&app_handle, settings.enable_perf_metrics.unwrap_or(false));
+    configure_file_lock_retries(settings.file_lock_retries.unwrap_or(DEFAULT_FILE_LOCK_RETRIES));
+    let path = get_settings_path(&app_handle)?;
+    let json_string = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(path, json_string).map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SettingsExportFile {
+    version: u32,
+    settings: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    presets: Option<Vec<PresetItem>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportResult {
+    pub settings: AppSettings,
+    pub presets_imported: bool,
+    pub warnings: Vec<String>,
+}
+
+const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+fn merge_json_objects(target: &mut Value, source: &Value) {
+    match (target, source) {
+        (Value::Object(target_map), Value::Object(source_map)) => {
+            for (key, source_value) in source_map {
+                match target_map.get_mut(key) {
+                    Some(target_value) if target_value.is_object() && source_value.is_object() => {
+                        merge_json_objects(target_value, source_value);
+                    }
+                    _ => {
+                        target_map.insert(key.clone(), source_value.clone());
+                    }
+                }
+            }
+        }
+        (target, source) => *target = source.clone(),
+    }
+}
+
+#[tauri::command]
+pub fn export_settings_to_file(
+    file_path: String,
+    include_presets: bool,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let settings_path = get_settings_path(&app_handle)?;
+    // Export the raw file so fields unknown to this version survive the round trip
+    let mut settings = if settings_path.exists() {
+        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+        serde_json::from_str::<Value>(&content)
+            .map_err(|e| format!("Failed to parse settings: {}", e))?
+    } else {
+        serde_json::to_value(AppSettings::default()).map_err(|e| e.to_string())?
+    };
+    // Window placement only makes sense on the machine it was saved on
+    if let Some(settings_obj) = settings.as_object_mut() {
+        settings_obj.remove("windowBounds");
+    }
+
+    let presets = if include_presets {
+        Some(load_presets(app_handle)?)
+    } else {
+        None
+    };
+
+    let export_file = SettingsExportFile {
+        version: SETTINGS_EXPORT_VERSION,
+        settings,
+        presets,
+    };
+
+    let json_string = serde_json::to_string_pretty(&export_file)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(file_path, json_string).map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+#[tauri::command]
+pub fn import_settings_from_file(
+    file_path: String,
+    app_handle: AppHandle,
+) -> Result<SettingsImportResult, String> {
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    let import_file: SettingsExportFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings file: {}", e))?;
+
+    if import_file.version > SETTINGS_EXPORT_VERSION {
+        return Err(format!(
+            "Settings file version {} is newer than supported version {}",
+            import_file.version, SETTINGS_EXPORT_VERSION
+        ));
+    }
+    if !import_file.settings.is_object() {
+        return Err("Settings file does not contain a settings object".to_string());
+    }
+
+    let settings_path = get_settings_path(&app_handle)?;
+    let mut merged = if settings_path.exists() {
+        fs::read_to_string(&settings_path)
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+            .filter(Value::is_object)
+            .unwrap_or_else(|| serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    merge_json_objects(&mut merged, &import_file.settings);
+
+    let settings: AppSettings = serde_json::from_value(merged.clone())
+        .map_err(|e| format!("Imported settings are invalid: {}", e))?;
+
+    // Folder paths are machine specific, so flag the ones that don't resolve here
+    let mut imported_paths: Vec<String> = settings.last_root_path.iter().cloned().collect();
+    imported_paths.extend(settings.pinned_folders.iter().cloned());
+    if let Some(folder_state) = &settings.last_folder_state {
+        imported_paths.push(folder_state.current_folder_path.clone());
+    }
+    let mut warnings: Vec<String> = Vec::new();
+    for folder_path in imported_paths {
+        let warning = format!("Folder does not exist on this machine: {}", folder_path);
+        if !folder_path.is_empty()
+            && !Path::new(&folder_path).exists()
+            && !warnings.contains(&warning)
+        {
+            warnings.push(warning);
+        }
+    }
+
+    let json_string = serde_json::to_string_pretty(&merged).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, json_string).map_err(|e| e.to_string())?;
+
+    let presets_imported = match import_file.presets {
+        Some(mut presets) => {
+            validate_imported_presets(&mut presets)?;
+            let mut current_presets = load_presets(app_handle.clone())?;
+            let skipped = merge_imported_presets(&mut current_presets, presets, false);
+            if skipped > 0 {
+                log::info!("Skipped {} presets that are already installed.", skipped);
+            }
+            save_presets(current_presets, app_handle)?;
+            true
+        }
+        None => false,
+    };
+
+    Ok(SettingsImportResult {
+        settings,
+        presets_imported,
+        warnings,
+    })
+}
+
+fn hash_canonical_json(value: &Value, hasher: &mut blake3::Hasher) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            hasher.update(b"{");
+            for key in keys {
+                hasher.update(key.as_bytes());
+                hasher.update(b":");
+                hash_canonical_json(&map[key], hasher);
+                hasher.update(b",");
+            }
+            hasher.update(b"}");
+        }
+        Value::Array(items) => {
+            hasher.update(b"[");
+            for item in items {
+                hash_canonical_json(item, hasher);
+                hasher.update(b",");
+            }
+            hasher.update(b"]");
+        }
+        // 10 and 10.0 are the same value, however the preset was last serialized
+        Value::Number(n) => {
+            hasher.update(&n.as_f64().unwrap_or(0.0).to_bits().to_le_bytes());
+        }
+        other => {
+            hasher.update(other.to_string().as_bytes());
+        }
+    }
+}
+
+/// Identifies a preset by what it does rather than by its id or name, so re-importing a
+/// shared file can recognise presets that are already installed.
+pub fn preset_content_hash(adjustments: &Value) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hash_canonical_json(adjustments, &mut hasher);
+    hasher.finalize().to_hex().to_string()
+}
+
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    let mut new_name = name.to_string();
+    let mut counter = 1;
+    while taken.contains(&new_name) {
+        new_name = format!("{} ({})", name, counter);
+        counter += 1;
+    }
+    new_name
+}
+
+const PRESET_PACK_EXTENSION: &str = "rrpack";
+const PRESET_PACK_JSON: &str = "presets.rrpreset";
+const PRESET_PACK_LUT_DIR: &str = "luts";
+
+fn for_each_preset_mut(items: &mut [PresetItem], mut f: impl FnMut(&mut Preset)) {
+    for item in items {
+        match item {
+            PresetItem::Preset(p) => f(p),
+            PresetItem::Folder(folder) => folder.children.iter_mut().for_each(&mut f),
+        }
+    }
+}
+
+fn is_preset_pack(file_path: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| ext.eq_ignore_ascii_case(PRESET_PACK_EXTENSION))
+}
+
+/// `name`, or `name` with a numeric suffix before the extension if `is_taken` says so.
+fn unique_file_name(name: &str, mut is_taken: impl FnMut(&str) -> bool) -> String {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("lut");
+    let extension = path.extension().and_then(|s| s.to_str());
+    let mut candidate = name.to_string();
+    let mut counter = 1;
+    while is_taken(&candidate) {
+        candidate = match extension {
+            Some(ext) => format!("{}_{}.{}", stem, counter, ext),
+            None => format!("{}_{}", stem, counter),
+        };
+        counter += 1;
+    }
+    candidate
+}
+
+/// Writes the presets and every LUT they reference into a zip, with each `lutPath`
+/// rewritten to the LUT's location inside the pack.
+fn write_preset_pack(presets: &[PresetItem], file_path: &str) -> Result<(), String> {
+    let mut presets = presets.to_vec();
+    let mut bundled: HashMap<String, String> = HashMap::new();
+    for_each_preset_mut(&mut presets, |preset| {
+        let Some(lut_path) = preset.adjustments["lutPath"].as_str().filter(|p| !p.is_empty()) else {
+            return;
+        };
+        let pack_path = match bundled.get(lut_path) {
+            Some(pack_path) => pack_path.clone(),
+            None => {
+                let file_name = Path::new(lut_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "lut.cube".to_string());
+                let file_name = unique_file_name(&file_name, |candidate| {
+                    let candidate = format!("{}/{}", PRESET_PACK_LUT_DIR, candidate);
+                    bundled.values().any(|taken| *taken == candidate)
+                });
+                let pack_path = format!("{}/{}", PRESET_PACK_LUT_DIR, file_name);
+                bundled.insert(lut_path.to_string(), pack_path.clone());
+                pack_path
+            }
+        };
+        preset.adjustments["lutPath"] = Value::String(pack_path);
+    });
+
+    let file = fs::File::create(file_path).map_err(|e| format!("Failed to create preset pack: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let preset_file = ExportPresetFile {
+        creator: "Anonymous",
+        presets: &presets,
+    };
+    let json_string = serde_json::to_string_pretty(&preset_file)
+        .map_err(|e| format!("Failed to serialize presets: {}", e))?;
+    zip.start_file(PRESET_PACK_JSON, options)
+        .and_then(|_| zip.write_all(json_string.as_bytes()).map_err(Into::into))
+        .map_err(|e| format!("Failed to write preset pack: {}", e))?;
+
+    for (source_path, pack_path) in &bundled {
+        let bytes = fs::read(source_path)
+            .map_err(|e| format!("Failed to read LUT '{}': {}", source_path, e))?;
+        zip.start_file(pack_path.as_str(), options)
+            .and_then(|_| zip.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to write preset pack: {}", e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write preset pack: {}", e))?;
+    Ok(())
+}
+
+/// Reads a pack written by `write_preset_pack`, extracting its LUTs into the app's LUT
+/// folder and pointing `lutPath` at them. A LUT that is already there with the same
+/// content is reused, so importing a pack twice doesn't pile up copies.
+fn read_preset_pack(file_path: &str, app_handle: &AppHandle) -> Result<Vec<PresetItem>, String> {
+    let file = fs::File::open(file_path).map_err(|e| format!("Failed to read preset pack: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to open preset pack: {}", e))?;
+
+    let mut preset_file: PresetFile = {
+        let mut entry = archive
+            .by_name(PRESET_PACK_JSON)
+            .map_err(|e| format!("Preset pack has no {}: {}", PRESET_PACK_JSON, e))?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read preset pack: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse preset file: {}", e))?
+    };
+
+    let lut_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(PRESET_PACK_LUT_DIR);
+    fs::create_dir_all(&lut_dir).map_err(|e| e.to_string())?;
+
+    let mut extracted: HashMap<String, String> = HashMap::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let Some(enclosed) = entry.enclosed_name() else {
+            continue;
+        };
+        if entry.is_dir() || !enclosed.starts_with(PRESET_PACK_LUT_DIR) {
+            continue;
+        }
+        let Some(file_name) = enclosed.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read '{}' from preset pack: {}", entry.name(), e))?;
+
+        let target_name = unique_file_name(&file_name, |candidate| {
+            fs::read(lut_dir.join(candidate)).map_or(false, |existing| existing != bytes)
+        });
+        let target_path = lut_dir.join(&target_name);
+        if !target_path.exists() {
+            fs::write(&target_path, &bytes)
+                .map_err(|e| format!("Failed to extract LUT '{}': {}", target_name, e))?;
+        }
+        extracted.insert(entry.name().to_string(), target_path.to_string_lossy().to_string());
+    }
+
+    for_each_preset_mut(&mut preset_file.presets, |preset| {
+        let extracted_path = preset.adjustments["lutPath"]
+            .as_str()
+            .and_then(|pack_path| extracted.get(pack_path));
+        if let Some(path) = extracted_path {
+            preset.adjustments["lutPath"] = Value::String(path.clone());
+        }
+    });
+
+    Ok(preset_file.presets)
+}
+
+/// Checks an imported preset's adjustments against the adjustment keys presets can carry.
+/// Unknown keys (typos, or settings from a newer version) are dropped with a warning so
+/// they never reach a sidecar when the preset is applied.
+fn validate_preset_adjustments(preset: &mut Preset, known_keys: &HashSet<String>) -> Result<(), String> {
+    let Some(adjustments) = preset.adjustments.as_object_mut() else {
+        return Err(format!(
+            "Preset '{}' is invalid: its adjustments must be an object.",
+            preset.name
+        ));
+    };
+
+    let unknown_keys: Vec<String> = adjustments
+        .keys()
+        .filter(|key| !known_keys.contains(*key))
+        .cloned()
+        .collect();
+    if !unknown_keys.is_empty() {
+        log::warn!(
+            "Preset '{}' has unknown adjustments that will be ignored: {}",
+            preset.name,
+            unknown_keys.join(", ")
+        );
+        for key in &unknown_keys {
+            adjustments.remove(key);
+        }
+    }
+    Ok(())
+}
+
+/// Imports a `.rrpreset` file or a `.rrpack` bundle. Presets whose adjustments match an
+/// installed preset are skipped, and folders merge into an existing folder of the same
+/// name, so importing the same pack twice changes nothing. `force_add` imports
+/// everything as new copies instead.
+fn validate_imported_presets(presets: &mut [PresetItem]) -> Result<(), String> {
+    let known_keys = default_included_adjustments();
+    for item in presets.iter_mut() {
+        match item {
+            PresetItem::Preset(p) => validate_preset_adjustments(p, &known_keys)?,
+            PresetItem::Folder(f) => {
+                for child in f.children.iter_mut() {
+                    validate_preset_adjustments(child, &known_keys)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds imported presets to `current_presets`, skipping ones whose adjustments are
+/// already installed unless `force_add` is set. Imported presets get fresh ids and are
+/// renamed on name clashes; folders with an existing name are merged into it. Returns
+/// how many presets were skipped.
+fn merge_imported_presets(
+    current_presets: &mut Vec<PresetItem>,
+    imported_presets: Vec<PresetItem>,
+    force_add: bool,
+) -> usize {
+    let mut current_names: HashSet<String> = current_presets
+        .iter()
+        .map(|item| match item {
+            PresetItem::Preset(p) => p.name.clone(),
+            PresetItem::Folder(f) => f.name.clone(),
+        })
+        .collect();
+    let mut existing_hashes: HashSet<String> = current_presets
+        .iter()
+        .flat_map(|item| match item {
+            PresetItem::Preset(p) => vec![preset_content_hash(&p.adjustments)],
+            PresetItem::Folder(f) => f
+                .children
+                .iter()
+                .map(|child| preset_content_hash(&child.adjustments))
+                .collect(),
+        })
+        .collect();
+    let mut skipped = 0;
+
+    for imported_item in imported_presets {
+        match imported_item {
+            PresetItem::Preset(mut p) => {
+                let hash = preset_content_hash(&p.adjustments);
+                if !force_add && existing_hashes.contains(&hash) {
+                    skipped += 1;
+                    continue;
+                }
+                p.id = Uuid::new_v4().to_string();
+                p.name = unique_name(&p.name, &current_names);
+                current_names.insert(p.name.clone());
+                existing_hashes.insert(hash);
+                current_presets.push(PresetItem::Preset(p));
+            }
+            PresetItem::Folder(mut f) => {
+                let mut children = Vec::new();
+                for mut child in f.children {
+                    let hash = preset_content_hash(&child.adjustments);
+                    if !force_add && existing_hashes.contains(&hash) {
+                        skipped += 1;
+                        continue;
+                    }
+                    child.id = Uuid::new_v4().to_string();
+                    existing_hashes.insert(hash);
+                    children.push(child);
+                }
+
+                let existing_folder = if force_add {
+                    None
+                } else {
+                    current_presets.iter_mut().find_map(|item| match item {
+                        PresetItem::Folder(existing) if existing.name == f.name => Some(existing),
+                        _ => None,
+                    })
+                };
+
+                match existing_folder {
+                    Some(existing) => {
+                        let mut child_names: HashSet<String> =
+                            existing.children.iter().map(|c| c.name.clone()).collect();
+                        for mut child in children {
+                            child.name = unique_name(&child.name, &child_names);
+                            child_names.insert(child.name.clone());
+                            existing.children.push(child);
+                        }
+                    }
+                    None if children.is_empty() && !force_add => {}
+                    None => {
+                        f.id = Uuid::new_v4().to_string();
+                        f.name = unique_name(&f.name, &current_names);
+                        f.children = children;
+                        current_names.insert(f.name.clone());
+                        current_presets.push(PresetItem::Folder(f));
+                    }
+                }
+            }
+        }
+    }
 
-#[tauri::command]
-pub fn save_settings(settings: AppSettings, app_handle: AppHandle) -> Result<(), String> {
-    let path = get_settings_path(&app_handle)?;
-    let json_string = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(path, json_string).map_err(|e| e.to_string())
+    skipped
 }
 
 #[tauri::command]
 pub fn handle_import_presets_from_file(
     file_path: String,
+    force_add: Option<bool>,
     app_handle: AppHandle,
 ) -> Result<Vec<PresetItem>, String> {
-    let content =
-        fs::read_to_string(file_path).map_err(|e| format!("Failed to read preset file: {}", e))?;
-    let imported_preset_file: PresetFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse preset file: {}", e))?;
+    let mut imported_presets = if is_preset_pack(&file_path) {
+        read_preset_pack(&file_path, &app_handle)?
+    } else {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read preset file: {}", e))?;
+        let imported_preset_file: PresetFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse preset file: {}", e))?;
+        imported_preset_file.presets
+    };
+    let force_add = force_add.unwrap_or(false);
+    validate_imported_presets(&mut imported_presets)?;
 
     let mut current_presets = load_presets(app_handle.clone())?;
+    let skipped = merge_imported_presets(&mut current_presets, imported_presets, force_add);
 
-    let mut current_names: HashSet<String> = current_presets
-        .iter()
-        .map(|item| match item {
-            PresetItem::Preset(p) => p.name.clone(),
-            PresetItem::Folder(f) => f.name.clone(),
-        })
-        .collect();
-
-    for mut imported_item in imported_preset_file.presets {
-        let (current_name, _new_id) = match &mut imported_item {
-            PresetItem::Preset(p) => {
-                p.id = Uuid::new_v4().to_string();
-                (p.name.clone(), p.id.clone())
-            }
-            PresetItem::Folder(f) => {
-                f.id = Uuid::new_v4().to_string();
-                for child in &mut f.children {
-                    child.id = Uuid::new_v4().to_string();
-                }
-                (f.name.clone(), f.id.clone())
-            }
-        };
-
-        let mut new_name = current_name.clone();
-        let mut counter = 1;
-        while current_names.contains(&new_name) {
-            new_name = format!("{} ({})", current_name, counter);
-            counter += 1;
-        }
-
-        match &mut imported_item {
-            PresetItem::Preset(p) => p.name = new_name.clone(),
-            PresetItem::Folder(f) => f.name = new_name.clone(),
-        }
-
-        current_names.insert(new_name);
-        current_presets.push(imported_item);
+    if skipped > 0 {
+        log::info!("Skipped {} presets that are already installed.", skipped);
     }
 
     save_presets(current_presets.clone(), app_handle)?;
@@ -1839,6 +3789,10 @@ pub fn handle_export_presets_to_file(
     presets_to_export: Vec<PresetItem>,
     file_path: String,
 ) -> Result<(), String> {
+    if is_preset_pack(&file_path) {
+        return write_preset_pack(&presets_to_export, &file_path);
+    }
+
     let preset_file = ExportPresetFile {
         creator: "Anonymous",
         presets: &presets_to_export,
@@ -1925,6 +3879,305 @@ pub fn clear_all_sidecars(root_path: String) -> Result<usize, String> {
     Ok(deleted_count)
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkMatch {
+    pub old_source: String,
+    pub new_source: String,
+    /// The original sidecar and any virtual copies that move with it.
+    pub sidecars: Vec<String>,
+    /// Whether the recorded file size was needed to pick between same-named files.
+    pub matched_by_size: bool,
+    /// Whether the recorded fingerprint was needed to pick between same-sized files.
+    #[serde(default)]
+    pub matched_by_hash: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AmbiguousRelink {
+    pub old_source: String,
+    pub candidates: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkReport {
+    pub matches: Vec<RelinkMatch>,
+    pub ambiguous: Vec<AmbiguousRelink>,
+    pub unmatched: Vec<String>,
+    /// Sidecars left in place because the destination already has one.
+    pub conflicts: Vec<String>,
+    pub relinked: usize,
+}
+
+/// Groups the `.rrdata` files under `root` whose original no longer exists by the
+/// original's path.
+fn find_orphaned_sidecars(root: &Path) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let original_sidecar_re = Regex::new(r"^(.*)\.rrdata$").unwrap();
+    let mut orphans: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    for entry in WalkDir::new(root).into_iter().filter_map(Result::ok) {
+        let sidecar_path = entry.path();
+        if !sidecar_path.is_file() {
+            continue;
+        }
+        let file_name = sidecar_path.file_name().unwrap_or_default().to_string_lossy();
+        let source_name = if let Some(caps) = SIDECAR_RE.captures(&file_name) {
+            caps.get(1).map(|m| m.as_str().to_string())
+        } else {
+            original_sidecar_re
+                .captures(&file_name)
+                .and_then(|caps| caps.get(1))
+                .map(|m| m.as_str().to_string())
+        };
+        let (Some(source_name), Some(parent)) = (source_name, sidecar_path.parent()) else {
+            continue;
+        };
+
+        let source_path = parent.join(source_name);
+        if !source_path.exists() {
+            orphans
+                .entry(source_path)
+                .or_default()
+                .push(sidecar_path.to_path_buf());
+        }
+    }
+    orphans
+}
+
+/// Bytes of the original hashed into `ImageMetadata::source_hash`. Enough to cover RAW
+/// headers and EXIF timestamps without reading whole files.
+const SOURCE_FINGERPRINT_BYTES: u64 = 1024 * 1024;
+
+pub fn source_fingerprint(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file.take(SOURCE_FINGERPRINT_BYTES), &mut hasher).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+/// The original's size and fingerprint as recorded by its sidecars, preferring the main one.
+fn recorded_source_identity(sidecars: &[PathBuf]) -> (Option<u64>, Option<String>) {
+    let mut sorted: Vec<&PathBuf> = sidecars.iter().collect();
+    sorted.sort_by_key(|path| {
+        SIDECAR_RE.is_match(&path.file_name().unwrap_or_default().to_string_lossy())
+    });
+    let metadata: Vec<ImageMetadata> = sorted
+        .into_iter()
+        .map(|path| read_sidecar_metadata(path))
+        .collect();
+    (
+        metadata.iter().find_map(|m| m.source_size),
+        metadata.iter().find_map(|m| m.source_hash.clone()),
+    )
+}
+
+fn match_orphan(
+    source_path: &Path,
+    sidecars: &[PathBuf],
+    files_by_name: &HashMap<String, Vec<PathBuf>>,
+    report: &mut RelinkReport,
+) {
+    let old_source = source_path.to_string_lossy().into_owned();
+    let file_name = source_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let candidates = files_by_name
+        .get(file_name.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    if candidates.is_empty() {
+        report.unmatched.push(old_source);
+        return;
+    }
+
+    let (recorded_size, recorded_hash) = recorded_source_identity(sidecars);
+    let mut remaining: Vec<&PathBuf> = candidates.iter().collect();
+    let mut matched_by_size = false;
+    let mut matched_by_hash = false;
+
+    // A lone same-named file is only taken on trust when nothing recorded contradicts it;
+    // otherwise it goes to the user like any other ambiguous match.
+    if let Some(size) = recorded_size {
+        remaining.retain(|c| fs::metadata(c).is_ok_and(|m| m.len() == size));
+        matched_by_size = candidates.len() > 1;
+    }
+    if let (true, Some(hash)) = (remaining.len() > 1, &recorded_hash) {
+        remaining.retain(|c| source_fingerprint(c).as_ref() == Some(hash));
+        matched_by_hash = true;
+    }
+
+    if remaining.len() != 1 {
+        report.ambiguous.push(AmbiguousRelink {
+            old_source,
+            candidates: candidates
+                .iter()
+                .map(|c| c.to_string_lossy().into_owned())
+                .collect(),
+        });
+        return;
+    }
+
+    report.matches.push(RelinkMatch {
+        old_source,
+        new_source: remaining[0].to_string_lossy().into_owned(),
+        sidecars: sidecars
+            .iter()
+            .map(|s| s.to_string_lossy().into_owned())
+            .collect(),
+        matched_by_size,
+        matched_by_hash,
+    });
+}
+
+/// Finds sidecars under `old_root` whose originals are gone and matches them by file name
+/// to images under `new_root`. Same-named candidates are only matched when the size the
+/// sidecar recorded, and failing that its fingerprint, singles one out.
+fn find_relinks(old_root: &Path, new_root: &Path) -> RelinkReport {
+    let mut files_by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for entry in WalkDir::new(new_root).into_iter().filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_file() && is_supported_image_file(&path.to_string_lossy()) {
+            files_by_name
+                .entry(
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned(),
+                )
+                .or_default()
+                .push(path.to_path_buf());
+        }
+    }
+
+    let mut orphans: Vec<(PathBuf, Vec<PathBuf>)> =
+        find_orphaned_sidecars(old_root).into_iter().collect();
+    orphans.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut report = RelinkReport::default();
+    for (source_path, sidecars) in &orphans {
+        match_orphan(source_path, sidecars, &files_by_name, &mut report);
+    }
+    report
+}
+
+/// Confirmed relinks come back from the frontend, so check that they still move sidecars
+/// out of `old_root` to an existing original under `new_root` before touching anything.
+fn is_valid_relink(relink: &RelinkMatch, old_root: &Path, new_root: &Path) -> bool {
+    let is_inside = |path: &Path, root: &Path| {
+        path.starts_with(root)
+            && !path
+                .components()
+                .any(|component| component == std::path::Component::ParentDir)
+    };
+    let new_source = Path::new(&relink.new_source);
+    is_inside(new_source, new_root)
+        && new_source.is_file()
+        && !relink.sidecars.is_empty()
+        && relink.sidecars.iter().all(|sidecar| {
+            let sidecar = Path::new(sidecar);
+            is_inside(sidecar, old_root) && sidecar.extension().is_some_and(|ext| ext == "rrdata")
+        })
+}
+
+/// Moves `from` to `to`, failing with `AlreadyExists` if `to` exists at that moment.
+/// `fs::rename` would replace a sidecar that appeared since the caller last looked.
+fn move_file_no_overwrite(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::hard_link(from, to) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Err(e),
+        // Another volume or no hard link support: copy into a file only we created
+        Err(_) => {
+            let mut source = fs::File::open(from)?;
+            let mut destination = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(to)?;
+            if let Err(e) =
+                std::io::copy(&mut source, &mut destination).and_then(|_| destination.sync_all())
+            {
+                let _ = fs::remove_file(to);
+                return Err(e);
+            }
+        }
+    }
+    fs::remove_file(from)
+}
+
+/// Without `confirmed`, reports which orphaned sidecars under `old_root` match originals
+/// under `new_root` and touches nothing, so the report can be shown for confirmation.
+/// With `confirmed`, moves exactly those sidecars next to their originals, never
+/// overwriting an existing sidecar; relinks that no longer check out are reported as
+/// unmatched.
+#[tauri::command]
+pub fn relink_originals(
+    old_root: String,
+    new_root: String,
+    confirmed: Option<Vec<RelinkMatch>>,
+) -> Result<RelinkReport, String> {
+    let old_root_path = Path::new(&old_root);
+    let new_root_path = Path::new(&new_root);
+    if !old_root_path.is_dir() {
+        return Err(format!("Folder does not exist: {}", old_root));
+    }
+    if !new_root_path.is_dir() {
+        return Err(format!("Folder does not exist: {}", new_root));
+    }
+
+    let Some(confirmed) = confirmed else {
+        return Ok(find_relinks(old_root_path, new_root_path));
+    };
+
+    let mut report = RelinkReport::default();
+    for relink in confirmed {
+        if !is_valid_relink(&relink, old_root_path, new_root_path) {
+            log::warn!(
+                "Skipping relink of {} to {}: it no longer matches the folders on disk",
+                relink.old_source,
+                relink.new_source
+            );
+            report.unmatched.push(relink.old_source);
+            continue;
+        }
+        let Some(destination_dir) = Path::new(&relink.new_source).parent() else {
+            continue;
+        };
+        for sidecar in &relink.sidecars {
+            let sidecar_path = Path::new(sidecar);
+            let destination = destination_dir.join(sidecar_path.file_name().unwrap_or_default());
+
+            let _sidecar_lock = lock_sidecar(sidecar_path);
+            match move_file_no_overwrite(sidecar_path, &destination) {
+                Ok(()) => report.relinked += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    report.conflicts.push(sidecar.clone());
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to relink sidecar {} to {:?}: {}",
+                        sidecar,
+                        destination,
+                        e
+                    );
+                }
+            }
+        }
+        report.matches.push(relink);
+    }
+
+    log::info!(
+        "Relinked {} sidecars from {} to {} ({} unmatched, {} conflicts)",
+        report.relinked,
+        old_root,
+        new_root,
+        report.unmatched.len(),
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
 #[tauri::command]
 pub fn clear_thumbnail_cache(app_handle: AppHandle) -> Result<(), String> {
     let cache_dir = app_handle
@@ -2104,6 +4357,153 @@ pub fn get_thumb_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
     Ok(thumb_cache_dir)
 }
 
+pub fn get_preview_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?;
+    let preview_cache_dir = cache_dir.join("previews");
+    if !preview_cache_dir.exists() {
+        fs::create_dir_all(&preview_cache_dir).map_err(|e| e.to_string())?;
+    }
+    Ok(preview_cache_dir)
+}
+
+fn preview_cache_file(app_handle: &AppHandle, job_hash: u64) -> Option<PathBuf> {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    if !settings.enable_preview_disk_cache.unwrap_or(false) {
+        return None;
+    }
+    get_preview_cache_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(format!("{:016x}.jpg", job_hash)))
+}
+
+pub fn read_cached_preview(app_handle: &AppHandle, source_path: &Path, job_hash: u64) -> Option<Vec<u8>> {
+    let cache_file = preview_cache_file(app_handle, job_hash)?;
+    let cache_modified = fs::metadata(&cache_file).ok()?.modified().ok()?;
+
+    // The cache key doesn't cover the file's contents, so a newer original invalidates the entry
+    if let Ok(source_modified) = fs::metadata(source_path).and_then(|m| m.modified()) {
+        if source_modified > cache_modified {
+            let _ = fs::remove_file(&cache_file);
+            return None;
+        }
+    }
+
+    let bytes = fs::read(&cache_file).ok()?;
+    if let Ok(file) = fs::File::options().write(true).open(&cache_file) {
+        let _ = file.set_modified(std::time::SystemTime::now());
+    }
+    Some(bytes)
+}
+
+pub fn write_cached_preview(app_handle: &AppHandle, job_hash: u64, bytes: &[u8]) {
+    let Some(cache_file) = preview_cache_file(app_handle, job_hash) else {
+        return;
+    };
+    if let Err(e) = fs::write(&cache_file, bytes) {
+        log::warn!("Failed to write preview cache entry {:?}: {}", cache_file, e);
+        return;
+    }
+
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let max_bytes = settings
+        .preview_disk_cache_size_mb
+        .unwrap_or(DEFAULT_PREVIEW_CACHE_SIZE_MB) as u64
+        * 1024
+        * 1024;
+    if let Some(dir) = cache_file.parent() {
+        evict_cache_dir(dir, max_bytes);
+    }
+}
+
+/// Removes the least recently used files in `dir` until it fits in `max_bytes`.
+fn evict_cache_dir(dir: &Path, max_bytes: u64) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut files: Vec<(PathBuf, u64, std::time::SystemTime)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            let modified = meta.modified().unwrap_or(std::time::UNIX_EPOCH);
+            Some((entry.path(), meta.len(), modified))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, len, _)| *len).sum();
+    if total <= max_bytes {
+        return;
+    }
+
+    files.sort_by_key(|(_, _, modified)| *modified);
+    for (path, len, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn clear_preview_cache(app_handle: AppHandle) -> Result<(), String> {
+    let preview_cache_dir = get_preview_cache_dir(&app_handle)?;
+
+    fs::remove_dir_all(&preview_cache_dir)
+        .map_err(|e| format!("Failed to remove preview cache: {}", e))?;
+    fs::create_dir_all(&preview_cache_dir)
+        .map_err(|e| format!("Failed to recreate preview cache directory: {}", e))?;
+
+    Ok(())
+}
+
+/// Returns the community preview cache directory for one source image set, removing
+/// entries left behind by previously used image sets.
+pub fn get_community_preview_cache_dir(
+    app_handle: &AppHandle,
+    source_set_hash: u64,
+) -> Result<PathBuf, String> {
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?;
+    let community_cache_dir = cache_dir.join("community_previews");
+    let set_dir = community_cache_dir.join(format!("{:016x}", source_set_hash));
+
+    if !set_dir.exists() {
+        if let Ok(entries) = fs::read_dir(&community_cache_dir) {
+            for entry in entries.filter_map(Result::ok) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+        fs::create_dir_all(&set_dir).map_err(|e| e.to_string())?;
+    }
+    Ok(set_dir)
+}
+
+#[tauri::command]
+pub fn clear_community_preview_cache(app_handle: AppHandle) -> Result<(), String> {
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?;
+    let community_cache_dir = cache_dir.join("community_previews");
+
+    if community_cache_dir.exists() {
+        fs::remove_dir_all(&community_cache_dir)
+            .map_err(|e| format!("Failed to remove community preview cache: {}", e))?;
+    }
+
+    Ok(())
+}
+
 pub fn get_cache_key_hash(path_str: &str) -> Option<String> {
     let (source_path, sidecar_path) = parse_virtual_path(path_str);
 
@@ -2436,4 +4836,294 @@ pub fn create_virtual_copy(source_virtual_path: String) -> Result<String, String
     }
 
     Ok(new_virtual_path)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn sidecar_lock_key_normalizes_path_spellings() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let direct = dir.path().join("a.jpg.rrdata");
+        let dotted = dir
+            .path()
+            .join("sub")
+            .join("..")
+            .join(".")
+            .join("a.jpg.rrdata");
+        assert_eq!(sidecar_lock_key(&direct), sidecar_lock_key(&dotted));
+        assert_ne!(
+            sidecar_lock_key(&direct),
+            sidecar_lock_key(&dir.path().join("b.jpg.rrdata"))
+        );
+    }
+
+    #[test]
+    fn lock_sidecar_serializes_concurrent_updates() {
+        const THREADS: usize = 8;
+        const UPDATES_PER_THREAD: usize = 50;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let sidecar = dir.path().join("a.jpg.rrdata");
+        fs::write(&sidecar, "0").unwrap();
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|i| {
+                let path = if i % 2 == 0 {
+                    sidecar.clone()
+                } else {
+                    dir.path().join("sub").join("..").join("a.jpg.rrdata")
+                };
+                thread::spawn(move || {
+                    for _ in 0..UPDATES_PER_THREAD {
+                        let _guard = lock_sidecar(&path);
+                        let count: usize = fs::read_to_string(&path).unwrap().parse().unwrap();
+                        thread::yield_now();
+                        fs::write(&path, (count + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let count: usize = fs::read_to_string(&sidecar).unwrap().parse().unwrap();
+        assert_eq!(count, THREADS * UPDATES_PER_THREAD);
+        assert!(SIDECAR_LOCKS.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn every_copyable_adjustment_belongs_to_exactly_one_section() {
+        // Panel state rather than an adjustment, so no section resets or applies it
+        const UI_STATE_KEYS: &[&str] = &["sectionVisibility", "showClipping"];
+        let sections = ["basic", "curves", "color", "details", "effects"];
+
+        for key in default_included_adjustments() {
+            if UI_STATE_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            let owners: Vec<&str> = sections
+                .iter()
+                .copied()
+                .filter(|section| {
+                    adjustment_section_keys(section)
+                        .unwrap()
+                        .contains(&key.as_str())
+                })
+                .collect();
+            assert_eq!(owners.len(), 1, "{} is in sections {:?}", key, owners);
+        }
+        assert!(adjustment_section_keys("masks").is_none());
+    }
+
+    #[test]
+    fn move_file_no_overwrite_keeps_an_existing_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a.jpg.rrdata");
+        let to = dir.path().join("b.jpg.rrdata");
+        fs::write(&from, "moved").unwrap();
+        fs::write(&to, "existing").unwrap();
+
+        let err = move_file_no_overwrite(&from, &to).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&from).unwrap(), "moved");
+        assert_eq!(fs::read_to_string(&to).unwrap(), "existing");
+
+        fs::remove_file(&to).unwrap();
+        move_file_no_overwrite(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "moved");
+    }
+
+    #[test]
+    fn confirmed_relinks_must_stay_inside_their_roots() {
+        let old_root = tempfile::tempdir().unwrap();
+        let new_root = tempfile::tempdir().unwrap();
+        let new_source = new_root.path().join("a.jpg");
+        fs::write(&new_source, "").unwrap();
+        let relink = |new_source: &Path, sidecar: PathBuf| RelinkMatch {
+            old_source: old_root.path().join("a.jpg").to_string_lossy().into_owned(),
+            new_source: new_source.to_string_lossy().into_owned(),
+            sidecars: vec![sidecar.to_string_lossy().into_owned()],
+            matched_by_size: false,
+        };
+        let sidecar = old_root.path().join("a.jpg.rrdata");
+
+        assert!(is_valid_relink(
+            &relink(&new_source, sidecar.clone()),
+            old_root.path(),
+            new_root.path()
+        ));
+        assert!(!is_valid_relink(
+            &relink(&new_root.path().join("missing.jpg"), sidecar.clone()),
+            old_root.path(),
+            new_root.path()
+        ));
+        assert!(!is_valid_relink(
+            &relink(&new_source, old_root.path().join("..").join("a.jpg.rrdata")),
+            old_root.path(),
+            new_root.path()
+        ));
+        assert!(!is_valid_relink(
+            &relink(&new_source, old_root.path().join("a.jpg")),
+            old_root.path(),
+            new_root.path()
+        ));
+    }
+
+    #[test]
+    fn depth_limited_trees_still_prune_empty_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("deep/a/b")).unwrap();
+        fs::write(root.join("deep/a/b/photo.jpg"), b"").unwrap();
+        fs::create_dir_all(root.join("empty/a/b")).unwrap();
+        fs::write(root.join("empty/a/b/notes.txt"), b"").unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+        fs::write(root.join(".hidden/photo.jpg"), b"").unwrap();
+
+        let library_ignore = LibraryIgnore {
+            matcher: None,
+            excluded_dirs: Vec::new(),
+        };
+        let tree_names = |options, depth| {
+            let root_str = root.to_string_lossy().into_owned();
+            get_folder_tree_sync(root_str, options, depth, &library_ignore)
+                .unwrap()
+                .children
+                .into_iter()
+                .map(|child| child.name)
+                .collect::<Vec<_>>()
+        };
+
+        let hide_empty = FolderTreeOptions::new(None, Some(true));
+        for depth in [Some(0), Some(1), Some(2), None] {
+            assert_eq!(tree_names(hide_empty, depth), ["deep"], "depth {:?}", depth);
+        }
+        let show_all = FolderTreeOptions::new(None, None);
+        assert_eq!(tree_names(show_all, Some(0)), ["deep", "empty"]);
+    }
+
+    fn write_sidecar(path: &Path, source_size: Option<u64>, source_hash: Option<String>) {
+        let metadata = ImageMetadata {
+            source_size,
+            source_hash,
+            ..ImageMetadata::default()
+        };
+        fs::write(path, serde_json::to_string(&metadata).unwrap()).unwrap();
+    }
+
+    fn relink_dirs() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let old_root = dir.path().join("old");
+        let new_root = dir.path().join("new");
+        fs::create_dir_all(&old_root).unwrap();
+        fs::create_dir_all(new_root.join("a")).unwrap();
+        fs::create_dir_all(new_root.join("b")).unwrap();
+        (dir, old_root, new_root)
+    }
+
+    #[test]
+    fn relink_matches_unique_name_and_groups_virtual_copies() {
+        let (_dir, old_root, new_root) = relink_dirs();
+        write_sidecar(&old_root.join("IMG_1.jpg.rrdata"), None, None);
+        write_sidecar(&old_root.join("IMG_1.jpg.a1b2c3.rrdata"), None, None);
+        fs::write(new_root.join("a").join("IMG_1.jpg"), b"jpeg").unwrap();
+
+        let report = find_relinks(&old_root, &new_root);
+        assert_eq!(report.matches.len(), 1);
+        let relink = &report.matches[0];
+        assert_eq!(Path::new(&relink.old_source), old_root.join("IMG_1.jpg"));
+        assert_eq!(
+            Path::new(&relink.new_source),
+            new_root.join("a").join("IMG_1.jpg")
+        );
+        let mut sidecars = relink.sidecars.clone();
+        sidecars.sort();
+        assert_eq!(
+            sidecars,
+            vec![
+                old_root
+                    .join("IMG_1.jpg.a1b2c3.rrdata")
+                    .to_string_lossy()
+                    .into_owned(),
+                old_root
+                    .join("IMG_1.jpg.rrdata")
+                    .to_string_lossy()
+                    .into_owned(),
+            ]
+        );
+        assert!(!relink.matched_by_size && !relink.matched_by_hash);
+        assert!(report.ambiguous.is_empty() && report.unmatched.is_empty());
+    }
+
+    #[test]
+    fn relink_reports_single_candidate_with_wrong_size_as_ambiguous() {
+        let (_dir, old_root, new_root) = relink_dirs();
+        write_sidecar(&old_root.join("IMG_1.jpg.rrdata"), Some(10), None);
+        fs::write(new_root.join("a").join("IMG_1.jpg"), b"jpeg").unwrap();
+
+        let report = find_relinks(&old_root, &new_root);
+        assert!(report.matches.is_empty());
+        assert_eq!(report.ambiguous.len(), 1);
+        assert_eq!(report.ambiguous[0].candidates.len(), 1);
+    }
+
+    #[test]
+    fn relink_uses_recorded_size_to_pick_between_same_names() {
+        let (_dir, old_root, new_root) = relink_dirs();
+        write_sidecar(&old_root.join("IMG_1.jpg.rrdata"), Some(6), None);
+        fs::write(new_root.join("a").join("IMG_1.jpg"), b"jpeg").unwrap();
+        fs::write(new_root.join("b").join("IMG_1.jpg"), b"jpeg!!").unwrap();
+
+        let report = find_relinks(&old_root, &new_root);
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(
+            Path::new(&report.matches[0].new_source),
+            new_root.join("b").join("IMG_1.jpg")
+        );
+        assert!(report.matches[0].matched_by_size);
+        assert!(!report.matches[0].matched_by_hash);
+    }
+
+    #[test]
+    fn relink_falls_back_to_fingerprint_for_same_sized_files() {
+        let (_dir, old_root, new_root) = relink_dirs();
+        let wanted = new_root.join("b").join("IMG_1.jpg");
+        fs::write(new_root.join("a").join("IMG_1.jpg"), b"aaaa").unwrap();
+        fs::write(&wanted, b"bbbb").unwrap();
+        write_sidecar(
+            &old_root.join("IMG_1.jpg.rrdata"),
+            Some(4),
+            source_fingerprint(&wanted),
+        );
+
+        let report = find_relinks(&old_root, &new_root);
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(Path::new(&report.matches[0].new_source), wanted);
+        assert!(report.matches[0].matched_by_hash);
+    }
+
+    #[test]
+    fn relink_without_a_tiebreak_is_ambiguous() {
+        let (_dir, old_root, new_root) = relink_dirs();
+        write_sidecar(&old_root.join("IMG_1.jpg.rrdata"), Some(4), None);
+        write_sidecar(&old_root.join("IMG_2.jpg.rrdata"), None, None);
+        fs::write(new_root.join("a").join("IMG_1.jpg"), b"aaaa").unwrap();
+        fs::write(new_root.join("b").join("IMG_1.jpg"), b"bbbb").unwrap();
+
+        let report = find_relinks(&old_root, &new_root);
+        assert!(report.matches.is_empty());
+        assert_eq!(report.ambiguous.len(), 1);
+        assert_eq!(report.ambiguous[0].candidates.len(), 2);
+        assert_eq!(
+            report.unmatched,
+            vec![old_root.join("IMG_2.jpg").to_string_lossy().into_owned()]
+        );
+    }
+}
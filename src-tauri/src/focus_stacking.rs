@@ -0,0 +1,319 @@
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb32FImage};
+use imageproc::filter::gaussian_blur_f32;
+use nalgebra::{Matrix3, Point3};
+use rayon::prelude::*;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use crate::file_management::AppSettings;
+use crate::formats::is_raw_file;
+use crate::image_processing::apply_cpu_default_raw_processing;
+use crate::panorama_utils::{processing, stitching};
+
+// Raising the focus measure to a high power makes the sharpest frame dominate while
+// the blurred measure still gives soft transitions between frames.
+const SHARPNESS_EXPONENT: i32 = 4;
+const FOCUS_MEASURE_SIGMA_FRACTION: f32 = 0.002;
+const MIN_FOCUS_MEASURE_SIGMA: f32 = 2.0;
+const FALLBACK_WEIGHT: f32 = 1e-6;
+
+type GrayF32Image = ImageBuffer<Luma<f32>, Vec<f32>>;
+
+fn load_frame(path: &str, settings: &AppSettings) -> Result<Rgb32FImage, String> {
+    let (source_path, _) = crate::file_management::parse_virtual_path(path);
+    let source_str = source_path.to_string_lossy().to_string();
+    let file_bytes =
+        fs::read(&source_path).map_err(|e| format!("Failed to read image {}: {}", path, e))?;
+    let raw_params = settings.raw_develop_params_for(&source_path);
+    let mut dynamic_image = crate::image_loader::load_base_image_from_bytes(
+        &file_bytes,
+        &source_str,
+        false,
+        raw_params,
+    )
+    .map_err(|e| format!("Failed to load image {}: {}", path, e))?;
+
+    if is_raw_file(&source_str) {
+        apply_cpu_default_raw_processing(&mut dynamic_image);
+    }
+    Ok(dynamic_image.to_rgb32f())
+}
+
+fn luma(p: &[f32]) -> f32 {
+    0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2]
+}
+
+fn to_gray_u8(image: &Rgb32FImage) -> GrayImage {
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let value = luma(&image.get_pixel(x, y).0);
+        Luma([(value.clamp(0.0, 1.0) * 255.0).round() as u8])
+    })
+}
+
+/// Resamples `frame` onto the reference grid. Pixels the frame doesn't cover (its edges
+/// move in and out as focus breathing changes the framing) are marked invalid.
+fn warp_to_reference(
+    frame: &Rgb32FImage,
+    homography: &Matrix3<f64>,
+    width: u32,
+    height: u32,
+) -> (Rgb32FImage, Vec<bool>) {
+    let (frame_w, frame_h) = frame.dimensions();
+    let mut warped = Rgb32FImage::new(width, height);
+    let mut valid = vec![false; (width * height) as usize];
+
+    warped
+        .par_chunks_mut(width as usize * 3)
+        .zip(valid.par_chunks_mut(width as usize))
+        .enumerate()
+        .for_each(|(y, (row, valid_row))| {
+            for x in 0..width as usize {
+                let p = homography * Point3::new(x as f64, y as f64, 1.0);
+                if p.z.abs() < 1e-8 {
+                    continue;
+                }
+                let (sx, sy) = (p.x / p.z, p.y / p.z);
+                if sx < 0.0 || sy < 0.0 || sx > (frame_w - 1) as f64 || sy > (frame_h - 1) as f64 {
+                    continue;
+                }
+                let pixel = stitching::get_interpolated_pixel(frame, sx, sy);
+                row[x * 3..x * 3 + 3].copy_from_slice(&pixel.0);
+                valid_row[x] = true;
+            }
+        });
+
+    (warped, valid)
+}
+
+/// Local sharpness: the absolute Laplacian of the luminance (in 8-bit units, so the
+/// weights stay well above the fallback), spread over a neighbourhood so that flat areas
+/// inside an in-focus region still favour that frame.
+fn focus_measure(image: &Rgb32FImage, sigma: f32) -> GrayF32Image {
+    let (width, height) = image.dimensions();
+    let gray: Vec<f32> = image.par_chunks(3).map(|p| luma(p) * 255.0).collect();
+    let at = |x: i64, y: i64| -> f32 {
+        let cx = x.clamp(0, width as i64 - 1) as usize;
+        let cy = y.clamp(0, height as i64 - 1) as usize;
+        gray[cy * width as usize + cx]
+    };
+
+    let mut laplacian = GrayF32Image::new(width, height);
+    laplacian
+        .par_chunks_mut(width as usize)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let y = y as i64;
+            for (x, out) in row.iter_mut().enumerate() {
+                let x = x as i64;
+                let l = 4.0 * at(x, y) - at(x - 1, y) - at(x + 1, y) - at(x, y - 1) - at(x, y + 1);
+                *out = l.abs();
+            }
+        });
+
+    gaussian_blur_f32(&laplacian, sigma)
+}
+
+/// Adds an aligned frame to the running weighted sums, weighting each pixel by the frame's
+/// local sharpness. Pixels outside the frame's coverage contribute nothing.
+fn accumulate_frame(
+    frame: &Rgb32FImage,
+    valid: &[bool],
+    sigma: f32,
+    accumulated: &mut [f32],
+    total_weight: &mut [f32],
+) {
+    let sharpness = focus_measure(frame, sigma);
+
+    accumulated
+        .par_chunks_mut(3)
+        .zip(total_weight.par_iter_mut())
+        .enumerate()
+        .for_each(|(idx, (acc, weight_sum))| {
+            if !valid[idx] {
+                return;
+            }
+            let weight = sharpness.as_raw()[idx].powi(SHARPNESS_EXPONENT) + FALLBACK_WEIGHT;
+            let pixel = &frame.as_raw()[idx * 3..idx * 3 + 3];
+            for c in 0..3 {
+                acc[c] += pixel[c] * weight;
+            }
+            *weight_sum += weight;
+        });
+}
+
+/// Aligns frames shot at different focus distances to the middle frame and blends them,
+/// weighting each pixel by how sharp each frame is there. Frames are processed one at a
+/// time so memory doesn't grow with the stack size.
+pub fn stack_images(
+    paths: Vec<String>,
+    settings: &AppSettings,
+    app_handle: AppHandle,
+) -> Result<DynamicImage, String> {
+    if paths.len() < 2 {
+        return Err("At least two images are required for focus stacking.".to_string());
+    }
+
+    let _ = app_handle.emit("focus-stack-progress", "Loading reference image...");
+    let reference_index = paths.len() / 2;
+    let reference = load_frame(&paths[reference_index], settings)?;
+    let (width, height) = reference.dimensions();
+    let reference_gray = to_gray_u8(&reference);
+    let brief_pairs = processing::generate_brief_pairs();
+    let sigma =
+        (width.max(height) as f32 * FOCUS_MEASURE_SIGMA_FRACTION).max(MIN_FOCUS_MEASURE_SIGMA);
+
+    let pixel_count = (width * height) as usize;
+    let mut accumulated = vec![0.0f32; pixel_count * 3];
+    let mut total_weight = vec![0.0f32; pixel_count];
+
+    for (i, path) in paths.iter().enumerate() {
+        let name = Path::new(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let _ = app_handle.emit(
+            "focus-stack-progress",
+            format!("Processing '{}' ({}/{})", name, i + 1, paths.len()),
+        );
+
+        let (mut frame, valid) = if i == reference_index {
+            (reference.clone(), vec![true; pixel_count])
+        } else {
+            let frame = load_frame(path, settings)?;
+            let homography =
+                processing::estimate_alignment(&reference_gray, &to_gray_u8(&frame), &brief_pairs)
+                    .unwrap_or_else(|| {
+                        let _ = app_handle.emit(
+                            "focus-stack-warning",
+                            format!("Could not align '{}'; stacking it unaligned.", name),
+                        );
+                        Matrix3::identity()
+                    });
+            warp_to_reference(&frame, &homography, width, height)
+        };
+
+        // Borrow the reference outside the frame's coverage so its edge doesn't read as detail
+        frame
+            .par_chunks_mut(3)
+            .zip(reference.par_chunks(3))
+            .zip(valid.par_iter())
+            .filter(|(_, is_valid)| !**is_valid)
+            .for_each(|((pixel, reference_pixel), _)| pixel.copy_from_slice(reference_pixel));
+
+        accumulate_frame(&frame, &valid, sigma, &mut accumulated, &mut total_weight);
+    }
+
+    let _ = app_handle.emit("focus-stack-progress", "Blending...");
+    accumulated
+        .par_chunks_mut(3)
+        .zip(total_weight.par_iter())
+        .for_each(|(acc, weight_sum)| {
+            if *weight_sum > 0.0 {
+                acc.iter_mut().for_each(|c| *c /= weight_sum);
+            }
+        });
+
+    let stacked = Rgb32FImage::from_raw(width, height, accumulated)
+        .ok_or_else(|| "Failed to assemble the stacked image.".to_string())?;
+    Ok(DynamicImage::ImageRgb32F(stacked))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u32 = 64;
+    const HEIGHT: u32 = 32;
+
+    fn checker(x: u32, y: u32) -> f32 {
+        if (x + y).is_multiple_of(2) { 0.2 } else { 0.8 }
+    }
+
+    /// A checkerboard inside `sharp_columns` and its blurred-out average elsewhere.
+    fn frame_sharp_in(sharp_columns: std::ops::Range<u32>) -> Rgb32FImage {
+        Rgb32FImage::from_fn(WIDTH, HEIGHT, |x, y| {
+            let v = if sharp_columns.contains(&x) {
+                checker(x, y)
+            } else {
+                0.5
+            };
+            image::Rgb([v, v, v])
+        })
+    }
+
+    #[test]
+    fn each_region_comes_from_the_frame_in_focus_there() {
+        let pixel_count = (WIDTH * HEIGHT) as usize;
+        let valid = vec![true; pixel_count];
+        let mut accumulated = vec![0.0f32; pixel_count * 3];
+        let mut total_weight = vec![0.0f32; pixel_count];
+
+        for frame in [
+            frame_sharp_in(0..WIDTH / 2),
+            frame_sharp_in(WIDTH / 2..WIDTH),
+        ] {
+            accumulate_frame(
+                &frame,
+                &valid,
+                MIN_FOCUS_MEASURE_SIGMA,
+                &mut accumulated,
+                &mut total_weight,
+            );
+        }
+
+        for y in 0..HEIGHT {
+            for x in (0..20).chain(WIDTH - 20..WIDTH) {
+                let idx = (y * WIDTH + x) as usize;
+                let blended = accumulated[idx * 3] / total_weight[idx];
+                assert!(
+                    (blended - checker(x, y)).abs() < 0.01,
+                    "pixel ({}, {}) blended to {}",
+                    x,
+                    y,
+                    blended
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn uncovered_pixels_add_no_weight() {
+        let pixel_count = (WIDTH * HEIGHT) as usize;
+        let mut accumulated = vec![0.0f32; pixel_count * 3];
+        let mut total_weight = vec![0.0f32; pixel_count];
+
+        accumulate_frame(
+            &frame_sharp_in(0..WIDTH),
+            &vec![false; pixel_count],
+            MIN_FOCUS_MEASURE_SIGMA,
+            &mut accumulated,
+            &mut total_weight,
+        );
+
+        assert!(total_weight.iter().all(|w| *w == 0.0));
+        assert!(accumulated.iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn warp_reproduces_an_aligned_frame_and_flags_uncovered_edges() {
+        let frame = frame_sharp_in(0..WIDTH);
+
+        let (same, valid) = warp_to_reference(&frame, &Matrix3::identity(), WIDTH, HEIGHT);
+        assert_eq!(same.as_raw(), frame.as_raw());
+        assert!(valid.iter().all(|v| *v));
+
+        let shift = Matrix3::new(1.0, 0.0, 10.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        let (shifted, valid) = warp_to_reference(&frame, &shift, WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let covered = x + 10 < WIDTH;
+                assert_eq!(valid[(y * WIDTH + x) as usize], covered);
+                if covered {
+                    assert_eq!(shifted.get_pixel(x, y), frame.get_pixel(x + 10, y));
+                }
+            }
+        }
+    }
+}
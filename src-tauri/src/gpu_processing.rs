@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 
 use bytemuck;
@@ -6,9 +6,15 @@ use half::f16;
 use image::{DynamicImage, GenericImageView, ImageBuffer, Luma, Rgba};
 use wgpu::util::{DeviceExt, TextureDataOrder};
 
-use crate::image_processing::{AllAdjustments, GpuContext};
+use crate::image_processing::{AllAdjustments, DEFAULT_SHARPEN_RADIUS, GpuContext};
 use crate::lut_processing::Lut;
-use crate::{AppState, GpuImageCache};
+use crate::{AppState, GpuImageCache, PerfMetric};
+
+#[derive(Default)]
+pub struct GpuTimings {
+    pub compute: std::time::Duration,
+    pub readback: std::time::Duration,
+}
 
 pub fn get_or_init_gpu_context(state: &tauri::State<AppState>) -> Result<GpuContext, String> {
     let mut context_lock = state.gpu_context.lock().unwrap();
@@ -49,6 +55,7 @@ pub fn get_or_init_gpu_context(state: &tauri::State<AppState>) -> Result<GpuCont
         device: Arc::new(device),
         queue: Arc::new(queue),
         limits,
+        adapter_info: adapter.get_info(),
     };
     *context_lock = Some(new_context.clone());
     Ok(new_context)
@@ -59,8 +66,9 @@ fn read_texture_data(
     queue: &wgpu::Queue,
     texture: &wgpu::Texture,
     size: wgpu::Extent3d,
+    bytes_per_pixel: u32,
 ) -> Result<Vec<u8>, String> {
-    let unpadded_bytes_per_row = 4 * size.width;
+    let unpadded_bytes_per_row = bytes_per_pixel * size.width;
     let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
     let padded_bytes_per_row = (unpadded_bytes_per_row + align - 1) & !(align - 1);
     let output_buffer_size = (padded_bytes_per_row * size.height) as u64;
@@ -129,13 +137,18 @@ fn to_rgba_f16(img: &DynamicImage) -> Vec<f16> {
         .collect()
 }
 
+// Output format for scene-linear exports, which must not be clamped to [0, 1].
+const LINEAR_OUTPUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+// Binding of `linear_output_texture`, the target of the shader's `main_linear` entry point.
+const LINEAR_OUTPUT_BINDING: u32 = 20;
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 struct BlurParams {
     radius: u32,
     tile_offset_x: u32,
     tile_offset_y: u32,
-    _pad: u32,
+    sigma: f32,
 }
 
 pub struct GpuProcessor {
@@ -146,6 +159,10 @@ pub struct GpuProcessor {
     blur_params_buffer: wgpu::Buffer,
     main_bgl: wgpu::BindGroupLayout,
     main_pipeline: wgpu::ComputePipeline,
+    shader_module: wgpu::ShaderModule,
+    linear_bgl: wgpu::BindGroupLayout,
+    linear_pipeline_layout: wgpu::PipelineLayout,
+    linear_pipeline: OnceLock<wgpu::ComputePipeline>,
     adjustments_buffer: wgpu::Buffer,
     dummy_blur_view: wgpu::TextureView,
     dummy_mask_view: wgpu::TextureView,
@@ -331,6 +348,16 @@ impl GpuProcessor {
             },
             count: None,
         });
+        bind_group_layout_entries.push(wgpu::BindGroupLayoutEntry {
+            binding: 8 + MAX_MASKS,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        });
 
         let main_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Main BGL"),
@@ -352,6 +379,28 @@ impl GpuProcessor {
             cache: None,
         });
 
+        // `main_linear` writes to its own float target instead of the RGBA8 output
+        bind_group_layout_entries[1] = wgpu::BindGroupLayoutEntry {
+            binding: LINEAR_OUTPUT_BINDING,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: LINEAR_OUTPUT_FORMAT,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let linear_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Linear Output BGL"),
+            entries: &bind_group_layout_entries,
+        });
+        let linear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Linear Output Pipeline Layout"),
+                bind_group_layouts: &[&linear_bgl],
+                immediate_size: 0,
+            });
+
         let adjustments_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Adjustments Buffer"),
             size: std::mem::size_of::<AllAdjustments>() as u64,
@@ -450,6 +499,10 @@ impl GpuProcessor {
             blur_params_buffer,
             main_bgl,
             main_pipeline,
+            shader_module,
+            linear_bgl,
+            linear_pipeline_layout,
+            linear_pipeline: OnceLock::new(),
             adjustments_buffer,
             dummy_blur_view,
             dummy_mask_view,
@@ -464,6 +517,25 @@ impl GpuProcessor {
         })
     }
 
+    /// The pipeline for the shader's `main_linear` entry point, compiled on first use
+    /// since only scene-linear exports need it.
+    fn linear_pipeline(&self) -> &wgpu::ComputePipeline {
+        self.linear_pipeline.get_or_init(|| {
+            self.context
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some("Linear Output Compute Pipeline"),
+                    layout: Some(&self.linear_pipeline_layout),
+                    module: &self.shader_module,
+                    entry_point: Some("main_linear"),
+                    compilation_options: Default::default(),
+                    cache: None,
+                })
+        })
+    }
+
+    /// Returns RGBA8 pixels, or little-endian RGBA f16 pixels when
+    /// `adjustments.global.output_linear` is set.
     pub fn run(
         &self,
         input_texture_view: &wgpu::TextureView,
@@ -472,10 +544,16 @@ impl GpuProcessor {
         adjustments: AllAdjustments,
         mask_bitmaps: &[ImageBuffer<Luma<u8>, Vec<u8>>],
         lut: Option<Arc<Lut>>,
+        mut timings: Option<&mut GpuTimings>,
     ) -> Result<Vec<u8>, String> {
         let device = &self.context.device;
         let queue = &self.context.queue;
-        let scale = (width.min(height) as f32) / 1080.0;
+        let (ref_width, ref_height) = if adjustments.full_width > 0 && adjustments.full_height > 0 {
+            (adjustments.full_width, adjustments.full_height)
+        } else {
+            (width, height)
+        };
+        let scale = (ref_width.min(ref_height) as f32) / 1080.0;
         const MAX_MASKS: u32 = 11;
 
         let full_texture_size = wgpu::Extent3d {
@@ -505,7 +583,9 @@ impl GpuProcessor {
             })
             .collect();
 
-        let (lut_texture_view, lut_sampler) = if let Some(lut_arc) = &lut {
+        let (lut_texture_view, lut_sampler) = if let Some(lut_arc) =
+            lut.as_ref().filter(|l| l.size > 0)
+        {
             let lut_data = &lut_arc.data;
             let size = lut_arc.size;
             let mut rgba_lut_data_f16 = Vec::with_capacity(lut_data.len() / 3 * 4);
@@ -548,10 +628,103 @@ impl GpuProcessor {
             (self.dummy_lut_view.clone(), self.dummy_lut_sampler.clone())
         };
 
+        let lut_1d_view = if let Some(lut_arc) = lut.as_ref().filter(|l| l.shaper_size > 1) {
+            // 1D LUTs can exceed the max texture width, so entries are wrapped into rows.
+            const LUT_1D_ROW_WIDTH: u32 = 1024;
+            let width = lut_arc.shaper_size.min(LUT_1D_ROW_WIDTH);
+            let height = lut_arc.shaper_size.div_ceil(width);
+            let mut rgba_lut_1d_f16 = vec![f16::ONE; (width * height * 4) as usize];
+            for (i, chunk) in lut_arc.shaper_data.chunks_exact(3).enumerate() {
+                rgba_lut_1d_f16[i * 4] = f16::from_f32(chunk[0]);
+                rgba_lut_1d_f16[i * 4 + 1] = f16::from_f32(chunk[1]);
+                rgba_lut_1d_f16[i * 4 + 2] = f16::from_f32(chunk[2]);
+            }
+            let lut_1d_texture = device.create_texture_with_data(
+                queue,
+                &wgpu::TextureDescriptor {
+                    label: Some("LUT 1D Texture"),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba16Float,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                },
+                TextureDataOrder::MipMajor,
+                bytemuck::cast_slice(&rgba_lut_1d_f16),
+            );
+            lut_1d_texture.create_view(&Default::default())
+        } else {
+            self.dummy_blur_view.clone()
+        };
+
+        let mut adjustments = adjustments;
+        if let Some(lut_arc) = &lut {
+            if adjustments.global.has_lut != 0 {
+                adjustments.global.has_lut = lut_arc.gpu_flags();
+            }
+            adjustments.global.lut_1d_size = lut_arc.shaper_size;
+            adjustments.global.lut_domain_min_r = lut_arc.domain_min[0];
+            adjustments.global.lut_domain_min_g = lut_arc.domain_min[1];
+            adjustments.global.lut_domain_min_b = lut_arc.domain_min[2];
+            adjustments.global.lut_domain_max_r = lut_arc.domain_max[0];
+            adjustments.global.lut_domain_max_g = lut_arc.domain_max[1];
+            adjustments.global.lut_domain_max_b = lut_arc.domain_max[2];
+        }
+
         const TILE_SIZE: u32 = 2048;
         const TILE_OVERLAP: u32 = 128;
 
-        let mut final_pixels = vec![0u8; (width * height * 4) as usize];
+        let linear_output = adjustments.global.output_linear != 0;
+        let sharpen_radius = if adjustments.global.sharpen_radius > 0.0 {
+            adjustments.global.sharpen_radius
+        } else {
+            DEFAULT_SHARPEN_RADIUS
+        };
+        let bytes_per_pixel: u32 = if linear_output { 8 } else { 4 };
+        let linear_output_texture = linear_output.then(|| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Linear Output Tile Texture"),
+                size: wgpu::Extent3d {
+                    width: width.min(TILE_SIZE + 2 * TILE_OVERLAP),
+                    height: height.min(TILE_SIZE + 2 * TILE_OVERLAP),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: LINEAR_OUTPUT_FORMAT,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            })
+        });
+        let linear_output_view = linear_output_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&Default::default()));
+        let (output_texture, output_texture_view, output_binding, main_bgl, main_pipeline) =
+            match (&linear_output_texture, &linear_output_view) {
+                (Some(texture), Some(view)) => (
+                    texture,
+                    view,
+                    LINEAR_OUTPUT_BINDING,
+                    &self.linear_bgl,
+                    self.linear_pipeline(),
+                ),
+                _ => (
+                    &self.output_texture,
+                    &self.output_texture_view,
+                    1,
+                    &self.main_bgl,
+                    &self.main_pipeline,
+                ),
+            };
+
+        let mut final_pixels = vec![0u8; (width * height * bytes_per_pixel) as usize];
         let tiles_x = (width + TILE_SIZE - 1) / TILE_SIZE;
         let tiles_y = (height + TILE_SIZE - 1) / TILE_SIZE;
 
@@ -575,13 +748,17 @@ impl GpuProcessor {
                     depth_or_array_layers: 1,
                 };
 
+                let tile_start = Instant::now();
                 let mut encoder = device.create_command_encoder(&Default::default());
 
                 let create_blur = |encoder: &mut wgpu::CommandEncoder,
                                    base_radius: f32,
                                    output_view: &wgpu::TextureView|
                  -> bool {
-                    let radius = (base_radius * scale).ceil().max(1.0) as u32;
+                    // The kernel spans whole pixels but keeps the fractional sigma, so
+                    // radii between integers still blur differently
+                    let scaled_radius = (base_radius * scale).max(0.5);
+                    let radius = scaled_radius.ceil() as u32;
                     if radius == 0 {
                         return false;
                     }
@@ -590,7 +767,7 @@ impl GpuProcessor {
                         radius,
                         tile_offset_x: input_x_start,
                         tile_offset_y: input_y_start,
-                        _pad: 0,
+                        sigma: scaled_radius / 2.0,
                     };
                     queue.write_buffer(&self.blur_params_buffer, 0, bytemuck::bytes_of(&params));
 
@@ -649,7 +826,7 @@ impl GpuProcessor {
                 };
 
                 let did_create_sharpness_blur =
-                    create_blur(&mut encoder, 2.0, &self.sharpness_blur_view);
+                    create_blur(&mut encoder, sharpen_radius, &self.sharpness_blur_view);
                 let did_create_clarity_blur =
                     create_blur(&mut encoder, 8.0, &self.clarity_blur_view);
                 let did_create_structure_blur =
@@ -670,8 +847,8 @@ impl GpuProcessor {
                         resource: wgpu::BindingResource::TextureView(input_texture_view),
                     },
                     wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::TextureView(&self.output_texture_view),
+                        binding: output_binding,
+                        resource: wgpu::BindingResource::TextureView(output_texture_view),
                     },
                     wgpu::BindGroupEntry {
                         binding: 2,
@@ -717,16 +894,20 @@ impl GpuProcessor {
                         &self.dummy_blur_view
                     }),
                 });
+                bind_group_entries.push(wgpu::BindGroupEntry {
+                    binding: 8 + MAX_MASKS,
+                    resource: wgpu::BindingResource::TextureView(&lut_1d_view),
+                });
 
                 let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
                     label: Some("Tile Bind Group"),
-                    layout: &self.main_bgl,
+                    layout: main_bgl,
                     entries: &bind_group_entries,
                 });
 
                 {
                     let mut compute_pass = encoder.begin_compute_pass(&Default::default());
-                    compute_pass.set_pipeline(&self.main_pipeline);
+                    compute_pass.set_pipeline(main_pipeline);
                     compute_pass.set_bind_group(0, &bind_group, &[]);
                     compute_pass.dispatch_workgroups(
                         (input_width + 7) / 8,
@@ -736,18 +917,44 @@ impl GpuProcessor {
                 }
                 queue.submit(Some(encoder.finish()));
 
-                let processed_tile_data =
-                    read_texture_data(device, queue, &self.output_texture, input_texture_size)?;
+                let readback_start = if let Some(timings) = timings.as_deref_mut() {
+                    // Wait for the dispatch so compute and readback can be timed separately
+                    let _ = device.poll(wgpu::PollType::Wait {
+                        submission_index: None,
+                        timeout: Some(std::time::Duration::from_secs(60)),
+                    });
+                    let now = Instant::now();
+                    timings.compute += now - tile_start;
+                    Some(now)
+                } else {
+                    None
+                };
+
+                let processed_tile_data = read_texture_data(
+                    device,
+                    queue,
+                    output_texture,
+                    input_texture_size,
+                    bytes_per_pixel,
+                )?;
+
+                if let (Some(timings), Some(readback_start)) =
+                    (timings.as_deref_mut(), readback_start)
+                {
+                    timings.readback += readback_start.elapsed();
+                }
 
                 let crop_x_start = x_start - input_x_start;
                 let crop_y_start = y_start - input_y_start;
 
                 for row in 0..tile_height {
                     let final_y = y_start + row;
-                    let final_row_offset = (final_y * width + x_start) as usize * 4;
+                    let final_row_offset =
+                        ((final_y * width + x_start) * bytes_per_pixel) as usize;
                     let source_y = crop_y_start + row;
-                    let source_row_offset = (source_y * input_width + crop_x_start) as usize * 4;
-                    let copy_bytes = (tile_width * 4) as usize;
+                    let source_row_offset =
+                        ((source_y * input_width + crop_x_start) * bytes_per_pixel) as usize;
+                    let copy_bytes = (tile_width * bytes_per_pixel) as usize;
 
                     final_pixels[final_row_offset..final_row_offset + copy_bytes].copy_from_slice(
                         &processed_tile_data[source_row_offset..source_row_offset + copy_bytes],
@@ -763,6 +970,7 @@ impl GpuProcessor {
 pub fn process_and_get_dynamic_image(
     context: &GpuContext,
     state: &tauri::State<AppState>,
+    app_handle: &tauri::AppHandle,
     base_image: &DynamicImage,
     transform_hash: u64,
     all_adjustments: AllAdjustments,
@@ -821,7 +1029,11 @@ pub fn process_and_get_dynamic_image(
         }
     }
 
+    let collect_metrics = crate::perf_metrics_enabled(state);
+    let mut upload_duration = None;
+
     if cache_lock.is_none() {
+        let upload_start = Instant::now();
         let img_rgba_f16 = to_rgba_f16(base_image);
         let texture_size = wgpu::Extent3d {
             width,
@@ -844,6 +1056,7 @@ pub fn process_and_get_dynamic_image(
             bytemuck::cast_slice(&img_rgba_f16),
         );
         let texture_view = texture.create_view(&Default::default());
+        upload_duration = Some(upload_start.elapsed());
 
         *cache_lock = Some(GpuImageCache {
             texture,
@@ -856,6 +1069,7 @@ pub fn process_and_get_dynamic_image(
 
     let cache = cache_lock.as_ref().unwrap();
     let start_time = Instant::now();
+    let mut gpu_timings = GpuTimings::default();
 
     let processed_pixels = processor.run(
         &cache.texture_view,
@@ -864,8 +1078,24 @@ pub fn process_and_get_dynamic_image(
         all_adjustments,
         mask_bitmaps,
         lut,
+        collect_metrics.then_some(&mut gpu_timings),
     )?;
 
+    if collect_metrics {
+        crate::emit_perf_metric(
+            app_handle,
+            PerfMetric {
+                context: caller_id.to_string(),
+                width,
+                height,
+                upload_ms: upload_duration.map(crate::duration_ms),
+                compute_ms: Some(crate::duration_ms(gpu_timings.compute)),
+                readback_ms: Some(crate::duration_ms(gpu_timings.readback)),
+                ..Default::default()
+            },
+        );
+    }
+
     let duration = start_time.elapsed();
     log::info!(
         "GPU adjustments for {}x{} image took {:?}",
@@ -874,6 +1104,16 @@ pub fn process_and_get_dynamic_image(
         duration
     );
 
+    if all_adjustments.global.output_linear != 0 {
+        let linear_pixels: Vec<f32> = processed_pixels
+            .chunks_exact(2)
+            .map(|bytes| f16::from_le_bytes([bytes[0], bytes[1]]).to_f32())
+            .collect();
+        let img_buf = ImageBuffer::<Rgba<f32>, Vec<f32>>::from_raw(width, height, linear_pixels)
+            .ok_or("Failed to create image buffer from GPU data")?;
+        return Ok(DynamicImage::ImageRgba32F(img_buf));
+    }
+
     let img_buf = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, processed_pixels)
         .ok_or("Failed to create image buffer from GPU data")?;
     Ok(DynamicImage::ImageRgba8(img_buf))
@@ -0,0 +1,455 @@
+use chrono::NaiveDateTime;
+use image::{DynamicImage, GrayImage, Luma, Rgb32FImage};
+use nalgebra::{Matrix3, Point3};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use crate::color_profile::{linear_to_srgb, srgb_to_linear};
+use crate::file_management::AppSettings;
+use crate::formats::is_raw_file;
+use crate::panorama_utils::{processing, stitching};
+
+const BRACKET_MAX_GAP_SECS: f64 = 3.0;
+const MAX_BRACKET_FRAMES: usize = 9;
+const SAME_EXPOSURE_TOLERANCE_EV: f64 = 0.3;
+const MIN_BRACKET_SPREAD_EV: f64 = 0.7;
+const CLIPPED_THRESHOLD: f32 = 0.99;
+const MIN_FRAME_WEIGHT: f32 = 1e-4;
+const WHITE_POINT_PERCENTILE: f64 = 0.999;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketSet {
+    pub paths: Vec<String>,
+    /// Exposure of each frame in stops, relative to the darkest one.
+    pub relative_exposures: Vec<f64>,
+}
+
+struct BracketFrame {
+    path: String,
+    captured: NaiveDateTime,
+    exposure_ev: f64,
+}
+
+fn first_f64(field: &exif::Field) -> Option<f64> {
+    match &field.value {
+        exif::Value::Rational(v) => v.first().map(|r| r.to_f64()),
+        exif::Value::SRational(v) => v.first().map(|r| r.to_f64()),
+        value => value.get_uint(0).map(f64::from),
+    }
+}
+
+fn capture_time(exif_obj: &exif::Exif) -> Option<NaiveDateTime> {
+    let field = exif_obj.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)?;
+    let text = field.display_value().to_string().replace('"', "");
+    let mut captured = NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(text.trim(), "%Y:%m:%d %H:%M:%S"))
+        .ok()?;
+
+    // Brackets are usually shot within the same second, so sub-seconds keep them ordered
+    if let Some(subsec) = exif_obj
+        .get_field(exif::Tag::SubSecTimeOriginal, exif::In::PRIMARY)
+        .map(|f| f.display_value().to_string().replace('"', ""))
+        .and_then(|s| format!("0.{}", s.trim()).parse::<f64>().ok())
+    {
+        captured += chrono::Duration::milliseconds((subsec * 1000.0) as i64);
+    }
+    Some(captured)
+}
+
+/// Brightness of a capture in stops (higher is brighter). Uses shutter, aperture and ISO
+/// when available and falls back to the exposure compensation for cameras that omit them.
+fn exposure_ev(exif_obj: &exif::Exif) -> Option<f64> {
+    let get = |tag| {
+        exif_obj
+            .get_field(tag, exif::In::PRIMARY)
+            .and_then(first_f64)
+    };
+
+    if let Some(time) = get(exif::Tag::ExposureTime).filter(|t| *t > 0.0) {
+        let aperture = get(exif::Tag::FNumber).filter(|f| *f > 0.0).unwrap_or(1.0);
+        let iso = get(exif::Tag::PhotographicSensitivity)
+            .filter(|i| *i > 0.0)
+            .unwrap_or(100.0);
+        return Some((time * iso / 100.0 / (aperture * aperture)).log2());
+    }
+    get(exif::Tag::ExposureBiasValue)
+}
+
+fn read_bracket_frame(path: &str) -> Option<BracketFrame> {
+    let (source_path, _) = crate::file_management::parse_virtual_path(path);
+    let file = fs::File::open(source_path).ok()?;
+    let mut reader = std::io::BufReader::new(&file);
+    let exif_obj = exif::Reader::new().read_from_container(&mut reader).ok()?;
+    Some(BracketFrame {
+        path: path.to_string(),
+        captured: capture_time(&exif_obj)?,
+        exposure_ev: exposure_ev(&exif_obj)?,
+    })
+}
+
+fn finish_bracket(frames: &[BracketFrame], sets: &mut Vec<BracketSet>) {
+    if frames.len() < 2 {
+        return;
+    }
+    let min_ev = frames
+        .iter()
+        .map(|f| f.exposure_ev)
+        .fold(f64::INFINITY, f64::min);
+    let max_ev = frames
+        .iter()
+        .map(|f| f.exposure_ev)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if max_ev - min_ev < MIN_BRACKET_SPREAD_EV {
+        return;
+    }
+    sets.push(BracketSet {
+        paths: frames.iter().map(|f| f.path.clone()).collect(),
+        relative_exposures: frames.iter().map(|f| f.exposure_ev - min_ev).collect(),
+    });
+}
+
+/// Groups images shot in quick succession at differing exposures into bracket sets.
+/// A frame repeating an exposure already in the current set starts a new set, which
+/// splits back-to-back brackets shot with the same sequence. Images without readable
+/// capture time or exposure settings are ignored.
+pub fn detect_brackets(paths: &[String]) -> Vec<BracketSet> {
+    let frames: Vec<BracketFrame> = paths
+        .par_iter()
+        .filter_map(|path| read_bracket_frame(path))
+        .collect();
+    group_brackets(frames)
+}
+
+fn group_brackets(mut frames: Vec<BracketFrame>) -> Vec<BracketSet> {
+    frames.sort_by(|a, b| {
+        a.captured
+            .cmp(&b.captured)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut sets = Vec::new();
+    let mut current: Vec<BracketFrame> = Vec::new();
+    for frame in frames {
+        let continues = current.last().is_some_and(|last| {
+            let gap = (frame.captured - last.captured).num_milliseconds() as f64 / 1000.0;
+            gap <= BRACKET_MAX_GAP_SECS
+                && current.len() < MAX_BRACKET_FRAMES
+                && current
+                    .iter()
+                    .all(|f| (f.exposure_ev - frame.exposure_ev).abs() > SAME_EXPOSURE_TOLERANCE_EV)
+        });
+        if !continues {
+            finish_bracket(&current, &mut sets);
+            current.clear();
+        }
+        current.push(frame);
+    }
+    finish_bracket(&current, &mut sets);
+    sets
+}
+
+/// Loads a frame as linear RGB so exposures can be combined by simple scaling. RAW
+/// highlights are left uncompressed: the merge needs radiance proportional to exposure,
+/// and clipped areas are taken from the darker frames anyway.
+fn load_linear_frame(path: &str, settings: &AppSettings) -> Result<Rgb32FImage, String> {
+    let (source_path, _) = crate::file_management::parse_virtual_path(path);
+    let source_str = source_path.to_string_lossy().to_string();
+    let file_bytes =
+        fs::read(&source_path).map_err(|e| format!("Failed to read image {}: {}", path, e))?;
+    let mut raw_params = settings.raw_develop_params_for(&source_path);
+    raw_params.highlight_compression = f32::INFINITY;
+    let dynamic_image = crate::image_loader::load_base_image_from_bytes(
+        &file_bytes,
+        &source_str,
+        false,
+        raw_params,
+    )
+    .map_err(|e| format!("Failed to load image {}: {}", path, e))?;
+
+    let mut image = dynamic_image.to_rgb32f();
+    if !is_raw_file(&source_str) {
+        image.par_iter_mut().for_each(|v| *v = srgb_to_linear(*v));
+    }
+    Ok(image)
+}
+
+/// A grayscale rendering of `image` brightened or darkened by `gain`, so frames shot at
+/// different exposures look alike to the feature detector.
+fn normalized_gray(image: &Rgb32FImage, gain: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    GrayImage::from_fn(width, height, |x, y| {
+        let p = image.get_pixel(x, y);
+        let luma = (0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2]) * gain;
+        Luma([(linear_to_srgb(luma.clamp(0.0, 1.0)) * 255.0).round() as u8])
+    })
+}
+
+/// Trusts mid-tones most; clipped highlights are dropped except in the darkest frame,
+/// and deep shadows only count when nothing better exposed is available.
+fn frame_weight(sample: [f32; 3], is_darkest: bool) -> f32 {
+    let peak = sample[0].max(sample[1]).max(sample[2]);
+    if peak >= CLIPPED_THRESHOLD && !is_darkest {
+        return 0.0;
+    }
+    let encoded = linear_to_srgb(peak.clamp(0.0, 1.0));
+    (1.0 - (2.0 * encoded - 1.0).powi(12)).max(MIN_FRAME_WEIGHT)
+}
+
+/// Compresses the merged radiance into display range with an extended Reinhard curve on
+/// luminance, whose white point is taken near the brightest part of the scene.
+fn tonemap(radiance: &mut Rgb32FImage) {
+    let mut luminances: Vec<f32> = radiance
+        .pixels()
+        .step_by(16)
+        .map(|p| 0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2])
+        .collect();
+    if luminances.is_empty() {
+        return;
+    }
+    luminances.sort_by(|a, b| a.total_cmp(b));
+    let index = ((luminances.len() - 1) as f64 * WHITE_POINT_PERCENTILE) as usize;
+    let white = luminances[index].max(1.0);
+    let white_sq = white * white;
+
+    radiance.par_chunks_mut(3).for_each(|pixel| {
+        let luma = 0.2126 * pixel[0] + 0.7152 * pixel[1] + 0.0722 * pixel[2];
+        if luma <= 1e-8 {
+            pixel.iter_mut().for_each(|c| *c = 0.0);
+            return;
+        }
+        let mapped = luma * (1.0 + luma / white_sq) / (1.0 + luma);
+        let ratio = mapped / luma;
+        for c in pixel.iter_mut() {
+            *c = linear_to_srgb((*c * ratio).clamp(0.0, 1.0));
+        }
+    });
+}
+
+/// Aligns and merges a bracketed sequence into one tone-mapped image. The frame closest
+/// to the middle exposure is the reference for both geometry and brightness; frames that
+/// can't be aligned are merged unwarped, which suits tripod brackets.
+pub fn merge_brackets(
+    paths: Vec<String>,
+    settings: &AppSettings,
+    app_handle: AppHandle,
+) -> Result<DynamicImage, String> {
+    if paths.len() < 2 {
+        return Err("At least two images are required for an HDR merge.".to_string());
+    }
+
+    let _ = app_handle.emit("hdr-progress", "Reading exposure settings...");
+    let exposures: Vec<f64> = paths
+        .iter()
+        .map(|path| {
+            read_bracket_frame(path)
+                .map(|frame| frame.exposure_ev)
+                .ok_or_else(|| format!("Could not read exposure settings from {}", path))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let _ = app_handle.emit("hdr-progress", "Loading images...");
+    let frames: Vec<Rgb32FImage> = paths
+        .par_iter()
+        .map(|path| load_linear_frame(path, settings))
+        .collect::<Result<_, _>>()?;
+
+    let dimensions = frames[0].dimensions();
+    if frames.iter().any(|f| f.dimensions() != dimensions) {
+        return Err("All bracketed images must have the same dimensions.".to_string());
+    }
+
+    let mut by_exposure: Vec<usize> = (0..frames.len()).collect();
+    by_exposure.sort_by(|&a, &b| exposures[a].total_cmp(&exposures[b]));
+    let reference = by_exposure[by_exposure.len() / 2];
+    let darkest = by_exposure[0];
+    let gains: Vec<f32> = exposures
+        .iter()
+        .map(|ev| 2f64.powf(exposures[reference] - ev) as f32)
+        .collect();
+
+    let _ = app_handle.emit("hdr-progress", "Aligning images...");
+    let brief_pairs = processing::generate_brief_pairs();
+    let reference_gray = normalized_gray(&frames[reference], 1.0);
+    let homographies: Vec<Option<Matrix3<f64>>> = (0..frames.len())
+        .into_par_iter()
+        .map(|i| {
+            if i == reference {
+                return None;
+            }
+            let frame_gray = normalized_gray(&frames[i], gains[i]);
+            let homography =
+                processing::estimate_alignment(&reference_gray, &frame_gray, &brief_pairs);
+            if homography.is_none() {
+                let name = Path::new(&paths[i])
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let _ = app_handle.emit(
+                    "hdr-warning",
+                    format!("Could not align '{}'; merging it unaligned.", name),
+                );
+            }
+            homography
+        })
+        .collect();
+
+    let _ = app_handle.emit("hdr-progress", "Merging exposures...");
+    let (width, height) = dimensions;
+    let mut merged = Rgb32FImage::new(width, height);
+    merged
+        .par_chunks_mut(width as usize * 3)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for x in 0..width as usize {
+                let mut sum = [0.0f32; 3];
+                let mut total_weight = 0.0f32;
+
+                for (i, frame) in frames.iter().enumerate() {
+                    let sample = match &homographies[i] {
+                        Some(h) => {
+                            let p = h * Point3::new(x as f64, y as f64, 1.0);
+                            if p.z.abs() < 1e-8 {
+                                continue;
+                            }
+                            let (sx, sy) = (p.x / p.z, p.y / p.z);
+                            if sx < 0.0 || sy < 0.0 || sx >= width as f64 || sy >= height as f64 {
+                                continue;
+                            }
+                            stitching::get_interpolated_pixel(frame, sx, sy).0
+                        }
+                        None => frame.get_pixel(x as u32, y as u32).0,
+                    };
+
+                    let weight = frame_weight(sample, i == darkest);
+                    for c in 0..3 {
+                        sum[c] += sample[c] * gains[i] * weight;
+                    }
+                    total_weight += weight;
+                }
+
+                if total_weight > 0.0 {
+                    for c in 0..3 {
+                        row[x * 3 + c] = sum[c] / total_weight;
+                    }
+                }
+            }
+        });
+
+    let _ = app_handle.emit("hdr-progress", "Tone mapping...");
+    tonemap(&mut merged);
+
+    Ok(DynamicImage::ImageRgb32F(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(path: &str, seconds: u32, millis: i64, exposure_ev: f64) -> BracketFrame {
+        let captured = chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, seconds))
+            .unwrap()
+            + chrono::Duration::milliseconds(millis);
+        BracketFrame {
+            path: path.to_string(),
+            captured,
+            exposure_ev,
+        }
+    }
+
+    fn paths(sets: &[BracketSet]) -> Vec<Vec<&str>> {
+        sets.iter()
+            .map(|set| set.paths.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn bracket_is_grouped_with_exposures_relative_to_the_darkest() {
+        let sets = group_brackets(vec![
+            frame("b.raw", 0, 200, 0.0),
+            frame("a.raw", 0, 0, -2.0),
+            frame("c.raw", 0, 400, 2.0),
+        ]);
+
+        assert_eq!(paths(&sets), vec![vec!["a.raw", "b.raw", "c.raw"]]);
+        assert_eq!(sets[0].relative_exposures, vec![0.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn repeated_exposure_starts_the_next_bracket() {
+        let sets = group_brackets(vec![
+            frame("1.raw", 0, 0, 0.0),
+            frame("2.raw", 0, 100, -1.0),
+            frame("3.raw", 0, 200, 1.0),
+            frame("4.raw", 1, 0, 0.1),
+            frame("5.raw", 1, 100, -1.0),
+            frame("6.raw", 1, 200, 1.0),
+        ]);
+
+        assert_eq!(
+            paths(&sets),
+            vec![
+                vec!["1.raw", "2.raw", "3.raw"],
+                vec!["4.raw", "5.raw", "6.raw"]
+            ]
+        );
+    }
+
+    #[test]
+    fn long_pause_between_frames_splits_the_sequence() {
+        let sets = group_brackets(vec![
+            frame("a.raw", 0, 0, 0.0),
+            frame("b.raw", 1, 0, 1.0),
+            frame("c.raw", 10, 0, 2.0),
+            frame("d.raw", 11, 0, 3.0),
+        ]);
+
+        assert_eq!(
+            paths(&sets),
+            vec![vec!["a.raw", "b.raw"], vec!["c.raw", "d.raw"]]
+        );
+    }
+
+    #[test]
+    fn bursts_narrow_spreads_and_lone_frames_are_not_brackets() {
+        let burst = group_brackets(vec![
+            frame("a.raw", 0, 0, 0.0),
+            frame("b.raw", 0, 100, 0.0),
+            frame("c.raw", 0, 200, 0.1),
+        ]);
+        assert!(burst.is_empty());
+
+        let narrow = group_brackets(vec![frame("a.raw", 0, 0, 0.0), frame("b.raw", 0, 100, 0.4)]);
+        assert!(narrow.is_empty());
+
+        let lone = group_brackets(vec![frame("a.raw", 0, 0, 0.0), frame("b.raw", 30, 0, 2.0)]);
+        assert!(lone.is_empty());
+    }
+
+    #[test]
+    fn brackets_are_capped_at_the_frame_limit() {
+        let frames = (0..MAX_BRACKET_FRAMES + 2)
+            .map(|i| frame(&format!("{:02}.raw", i), 0, i as i64 * 100, i as f64))
+            .collect();
+        let sets = group_brackets(frames);
+
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].paths.len(), MAX_BRACKET_FRAMES);
+        assert_eq!(sets[1].paths.len(), 2);
+    }
+
+    #[test]
+    fn files_without_exposure_data_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not_an_image.jpg");
+        fs::write(&path, b"no exif here").unwrap();
+
+        let sets = detect_brackets(&[path.to_string_lossy().to_string()]);
+        assert!(sets.is_empty());
+    }
+}
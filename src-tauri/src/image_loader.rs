@@ -1,14 +1,17 @@
 use crate::Cursor;
+use crate::color_profile::{IccProfile, profile_description};
 use crate::formats::is_raw_file;
 use crate::image_processing::apply_orientation;
 use crate::mask_generation::{MaskDefinition, SubMask, generate_mask_bitmap};
-use crate::raw_processing::develop_raw_image;
+use crate::raw_processing::{
+    RawDevelopParams, develop_linear_sensor_image, develop_raw_image, extract_embedded_preview,
+};
 use anyhow::{anyhow, Context, Result};
 use base64::{Engine as _, engine::general_purpose};
 use exif::{Reader as ExifReader, Tag};
 use exr::prelude::*;
 use exr::image::pixel_vec::PixelVec;
-use image::{DynamicImage, GenericImageView, ImageReader, imageops};
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageReader, imageops};
 use qoi::Channels;
 use rawler::Orientation;
 use rayon::prelude::*;
@@ -27,16 +30,20 @@ struct PatchMaskInfo {
     sub_masks: Vec<SubMask>,
 }
 
+/// Decodes `base_image`, falling back to a RAW's embedded preview like
+/// `decode_base_image_from_bytes`, and composites the adjustment patches on top.
 pub fn load_and_composite(
     base_image: &[u8],
     path: &str,
     adjustments: &Value,
     use_fast_raw_dev: bool,
-    highlight_compression: f32,
-) -> Result<DynamicImage> {
-    let base_image =
-        load_base_image_from_bytes(base_image, path, use_fast_raw_dev, highlight_compression)?;
-    composite_patches_on_image(&base_image, adjustments)
+    raw_params: RawDevelopParams,
+) -> Result<DecodedImage> {
+    let decoded = decode_base_image_from_bytes(base_image, path, use_fast_raw_dev, raw_params)?;
+    Ok(DecodedImage {
+        image: composite_patches_on_image(&decoded.image, adjustments)?,
+        ..decoded
+    })
 }
 
 fn load_exr_from_bytes(bytes: &[u8]) -> Result<DynamicImage> {
@@ -93,15 +100,82 @@ pub fn load_qoi_from_bytes(bytes: &[u8]) -> Result<DynamicImage> {
     }
 }
 
+pub struct DecodedImage {
+    pub image: DynamicImage,
+    /// The RAW data couldn't be developed and `image` is the camera's embedded JPEG,
+    /// which is already tone mapped and must not go through the RAW tone pipeline.
+    pub is_embedded_preview: bool,
+    /// Names the color profile the pixels were decoded from, for display. RAW, EXR and
+    /// QOI files carry no ICC profile and report `None`; other files without one are
+    /// treated as sRGB.
+    pub color_profile: Option<String>,
+}
+
+/// Like `load_base_image_from_bytes`, but falls back to the embedded preview when a RAW
+/// file can't be developed, reporting which one it returned.
+pub fn decode_base_image_from_bytes(
+    bytes: &[u8],
+    path_for_ext_check: &str,
+    use_fast_raw_dev: bool,
+    raw_params: RawDevelopParams,
+) -> Result<DecodedImage> {
+    match load_base_image_with_profile(bytes, path_for_ext_check, use_fast_raw_dev, raw_params) {
+        Ok((image, color_profile)) => Ok(DecodedImage {
+            image,
+            is_embedded_preview: false,
+            color_profile,
+        }),
+        Err(e) if is_raw_file(path_for_ext_check) => {
+            let preview = panic::catch_unwind(|| extract_embedded_preview(bytes))
+                .map_err(|_| anyhow!("Panic while extracting embedded preview"))
+                .and_then(|result| result);
+            match preview {
+                Ok(image) => {
+                    log::warn!(
+                        "Using the embedded preview for '{}' because the RAW data could not be developed.",
+                        path_for_ext_check
+                    );
+                    Ok(DecodedImage {
+                        image,
+                        is_embedded_preview: true,
+                        color_profile: None,
+                    })
+                }
+                Err(preview_error) => {
+                    log::warn!(
+                        "No usable embedded preview in '{}': {}",
+                        path_for_ext_check,
+                        preview_error
+                    );
+                    Err(e)
+                }
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
 pub fn load_base_image_from_bytes(
     bytes: &[u8],
     path_for_ext_check: &str,
     use_fast_raw_dev: bool,
-    highlight_compression: f32,
+    raw_params: RawDevelopParams,
 ) -> Result<DynamicImage> {
+    load_base_image_with_profile(bytes, path_for_ext_check, use_fast_raw_dev, raw_params)
+        .map(|(image, _)| image)
+}
+
+/// Like `load_base_image_from_bytes`, also naming the color profile as described on
+/// `DecodedImage::color_profile`.
+fn load_base_image_with_profile(
+    bytes: &[u8],
+    path_for_ext_check: &str,
+    use_fast_raw_dev: bool,
+    raw_params: RawDevelopParams,
+) -> Result<(DynamicImage, Option<String>)> {
     let path = std::path::Path::new(path_for_ext_check);
     if path.extension().and_then(|s| s.to_str()).map_or(false, |s| s.eq_ignore_ascii_case("exr")) {
-        return load_exr_from_bytes(bytes);
+        return load_exr_from_bytes(bytes).map(|image| (image, None));
     }
 
     if path
@@ -109,12 +183,12 @@ pub fn load_base_image_from_bytes(
         .and_then(|s| s.to_str())
         .map_or(false, |s| s.eq_ignore_ascii_case("qoi"))
     {
-        return load_qoi_from_bytes(bytes);
+        return load_qoi_from_bytes(bytes).map(|image| (image, None));
     }
 
     if is_raw_file(path_for_ext_check) {
-        match panic::catch_unwind(|| develop_raw_image(bytes, use_fast_raw_dev, highlight_compression)) {
-            Ok(Ok(image)) => Ok(image),
+        match panic::catch_unwind(|| develop_raw_image(bytes, use_fast_raw_dev, raw_params)) {
+            Ok(Ok(image)) => Ok((image, None)),
             Ok(Err(e)) => {
                 log::warn!("Error developing RAW file '{}': {}", path_for_ext_check, e);
                 Err(e)
@@ -125,36 +199,94 @@ pub fn load_base_image_from_bytes(
             }
         }
     } else {
-        load_image_with_orientation(bytes)
+        let (image, icc_profile) = load_image_with_orientation(bytes)?;
+        let profile_name = color_profile_name(icc_profile.as_deref(), path_for_ext_check);
+        Ok((image, Some(profile_name)))
+    }
+}
+
+/// The linear sensor data of a RAW file, before white balance, tone mapping or any other
+/// rendering transform, subsampled to at most `max_dim` pixels on the long edge. Only
+/// used for analysis; images for display come from `load_base_image_from_bytes`.
+pub fn load_linear_raw_from_bytes(
+    bytes: &[u8],
+    path_for_ext_check: &str,
+    raw_params: &RawDevelopParams,
+    max_dim: u32,
+) -> Result<DynamicImage> {
+    if !is_raw_file(path_for_ext_check) {
+        return Err(anyhow!("'{}' is not a RAW file", path_for_ext_check));
     }
+    panic::catch_unwind(|| develop_linear_sensor_image(bytes, raw_params, max_dim))
+        .map_err(|_| anyhow!("Failed to decode corrupt RAW file: {}", path_for_ext_check))
+        .and_then(|result| result)
 }
 
-pub fn load_image_with_orientation(bytes: &[u8]) -> Result<DynamicImage> {
+/// Decodes a standard image file, applying its orientation and converting it from any
+/// embedded ICC profile to sRGB. Returns the embedded profile alongside the image.
+pub fn load_image_with_orientation(bytes: &[u8]) -> Result<(DynamicImage, Option<Vec<u8>>)> {
     let cursor = Cursor::new(bytes);
     let mut reader = ImageReader::new(cursor.clone())
         .with_guessed_format()
         .context("Failed to guess image format")?;
 
     reader.no_limits();
-    let image = reader.decode().context("Failed to decode image")?;
-
-    let oriented_image = {
-        let exif_reader = ExifReader::new();
-        if let Ok(exif) = exif_reader.read_from_container(&mut cursor.clone()) {
-            if let Some(orientation) = exif
-                .get_field(Tag::Orientation, exif::In::PRIMARY)
-                .and_then(|f| f.value.get_uint(0))
-            {
-                apply_orientation(image, Orientation::from_u16(orientation as u16))
+    let mut decoder = reader.into_decoder().context("Failed to decode image")?;
+    let decoder_orientation = decoder.orientation().ok();
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let mut image = DynamicImage::from_decoder(decoder).context("Failed to decode image")?;
+
+    // Decoders that parse EXIF themselves report the orientation directly. Only fall back
+    // to reading the EXIF block when they don't, so the rotation is never applied twice.
+    let oriented_image = match decoder_orientation {
+        Some(orientation) if orientation != image::metadata::Orientation::NoTransforms => {
+            image.apply_orientation(orientation);
+            image
+        }
+        _ => {
+            let exif_reader = ExifReader::new();
+            if let Ok(exif) = exif_reader.read_from_container(&mut cursor.clone()) {
+                if let Some(orientation) = exif
+                    .get_field(Tag::Orientation, exif::In::PRIMARY)
+                    .and_then(|f| f.value.get_uint(0))
+                {
+                    apply_orientation(image, Orientation::from_u16(orientation as u16))
+                } else {
+                    image
+                }
             } else {
                 image
             }
-        } else {
-            image
         }
     };
 
-    Ok(DynamicImage::ImageRgb32F(oriented_image.to_rgb32f()))
+    let mut rgb_image = oriented_image.to_rgb32f();
+    if let Some(profile) = icc_profile.as_deref().and_then(IccProfile::parse) {
+        if !profile.is_srgb() {
+            profile.convert_to_srgb(&mut rgb_image);
+        }
+    }
+
+    Ok((DynamicImage::ImageRgb32F(rgb_image), icc_profile))
+}
+
+fn color_profile_name(icc_profile: Option<&[u8]>, path_for_ext_check: &str) -> String {
+    match icc_profile {
+        Some(data) => {
+            let name = profile_description(data).unwrap_or_else(|| "Embedded profile".to_string());
+            if IccProfile::parse(data).is_some() {
+                name
+            } else {
+                log::warn!(
+                    "Unsupported ICC profile '{}' in {}; treating as sRGB",
+                    name,
+                    path_for_ext_check
+                );
+                format!("{} (unsupported, treated as sRGB)", name)
+            }
+        }
+        None => "sRGB (assumed)".to_string(),
+    }
 }
 
 pub fn composite_patches_on_image(
@@ -208,9 +340,10 @@ pub fn composite_patches_on_image(
             opacity: 100.0,
             adjustments: Value::Null,
             sub_masks: patch_info.sub_masks,
+            range_mask: None,
         };
 
-        let mask_bitmap = generate_mask_bitmap(&mask_def, base_w, base_h, 1.0, (0.0, 0.0))
+        let mask_bitmap = generate_mask_bitmap(&mask_def, base_w, base_h, 1.0, (0.0, 0.0), None)
             .context("Failed to generate mask from sub_masks for compositing")?;
 
         let patch_data = patch_obj.get("patchData").context("Missing patchData")?;
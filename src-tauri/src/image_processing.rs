@@ -11,15 +11,44 @@ use std::f32::consts::PI;
 use std::sync::Arc;
 
 pub use crate::gpu_processing::{get_or_init_gpu_context, process_and_get_dynamic_image};
-use crate::{load_settings, mask_generation::MaskDefinition, AppState};
+use crate::{mask_generation::MaskDefinition, AppState};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ImageMetadata {
+    #[serde(default = "default_metadata_version", alias = "Version")]
     pub version: u32,
+    #[serde(default, alias = "Rating", deserialize_with = "deserialize_lenient_rating")]
     pub rating: u8,
+    #[serde(default, alias = "Adjustments")]
     pub adjustments: Value,
-    #[serde(default)]
+    #[serde(default, alias = "Tags", alias = "keywords", alias = "Keywords")]
     pub tags: Option<Vec<String>>,
+    /// Byte size of the original when the edit was last saved, used to tell apart
+    /// same-named files when relinking moved originals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_size: Option<u64>,
+    /// Fingerprint of the original's leading bytes, used when several same-named
+    /// candidates also share the recorded size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+}
+
+fn default_metadata_version() -> u32 {
+    1
+}
+
+// Sidecars written by other forks may store the rating as a float or string
+fn deserialize_lenient_rating<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    let rating = match &value {
+        Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        Value::String(s) => s.trim().parse::<f64>().unwrap_or(0.0),
+        _ => 0.0,
+    };
+    Ok(rating.round().clamp(0.0, 5.0) as u8)
 }
 
 impl Default for ImageMetadata {
@@ -29,6 +58,8 @@ impl Default for ImageMetadata {
             rating: 0,
             adjustments: Value::Null,
             tags: None,
+            source_size: None,
+            source_hash: None,
         }
     }
 }
@@ -41,7 +72,33 @@ pub struct Crop {
     pub height: f64,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DownscaleQuality {
+    #[default]
+    Box,
+    Lanczos,
+}
+
+impl DownscaleQuality {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("lanczos") => DownscaleQuality::Lanczos,
+            _ => DownscaleQuality::Box,
+        }
+    }
+}
+
 pub fn downscale_f32_image(image: &DynamicImage, nwidth: u32, nheight: u32) -> DynamicImage {
+    downscale_f32_image_with_quality(image, nwidth, nheight, DownscaleQuality::Box)
+}
+
+pub fn downscale_f32_image_with_quality(
+    image: &DynamicImage,
+    nwidth: u32,
+    nheight: u32,
+    quality: DownscaleQuality,
+) -> DynamicImage {
     let (width, height) = image.dimensions();
     if nwidth == 0 || nheight == 0 {
         return image.clone();
@@ -59,6 +116,15 @@ pub fn downscale_f32_image(image: &DynamicImage, nwidth: u32, nheight: u32) -> D
     }
 
     let img = image.to_rgb32f();
+
+    if quality == DownscaleQuality::Lanczos {
+        let mut resized =
+            image::imageops::resize(&img, new_w, new_h, image::imageops::FilterType::Lanczos3);
+        // Lanczos ringing can undershoot below black
+        resized.par_iter_mut().for_each(|v| *v = v.max(0.0));
+        return DynamicImage::ImageRgb32F(resized);
+    }
+
     let mut out = Rgb32FImage::new(new_w, new_h);
 
     let x_ratio = width as f32 / new_w as f32;
@@ -128,9 +194,9 @@ pub fn apply_orientation(image: DynamicImage, orientation: Orientation) -> Dynam
         Orientation::HorizontalFlip => image.fliph(),
         Orientation::Rotate180 => image.rotate180(),
         Orientation::VerticalFlip => image.flipv(),
-        Orientation::Transpose => image.rotate90().flipv(),
+        Orientation::Transpose => image.rotate90().fliph(),
         Orientation::Rotate90 => image.rotate90(),
-        Orientation::Transverse => image.rotate90().fliph(),
+        Orientation::Transverse => image.rotate90().flipv(),
         Orientation::Rotate270 => image.rotate270(),
     }
 }
@@ -144,6 +210,16 @@ pub fn apply_coarse_rotation(image: DynamicImage, orientation_steps: u8) -> Dyna
     }
 }
 
+/// Background for regions left empty by rotation: the `fillColor` adjustment as display-referred
+/// sRGB in [0, 1], or `None` to leave them transparent. The shader lays it under the finished
+/// image using the alpha rotation leaves behind, so no adjustment alters the fill itself.
+pub fn rotation_fill_color(adjustments: &Value) -> Option<[f32; 3]> {
+    adjustments["fillColor"]
+        .as_str()
+        .and_then(crate::parse_hex_color)
+        .map(|rgb| rgb.map(|c| c as f32 / 255.0))
+}
+
 pub fn apply_rotation(image: &DynamicImage, rotation_degrees: f32) -> DynamicImage {
     if rotation_degrees % 360.0 == 0.0 {
         return image.clone();
@@ -185,6 +261,61 @@ pub fn apply_crop(mut image: DynamicImage, crop_value: &Value) -> DynamicImage {
     image
 }
 
+/// Fits a proposed crop to `ratio` (width / height), keeping its area and center where
+/// possible and clamping it to the image. A missing or non-positive ratio means the
+/// image's own aspect; with `match_orientation` the ratio is flipped to follow the crop.
+#[tauri::command]
+pub fn constrain_crop_to_aspect(
+    crop: Crop,
+    ratio: Option<f64>,
+    image_dims: (u32, u32),
+    match_orientation: Option<bool>,
+) -> Result<Crop, String> {
+    let (img_w, img_h) = (image_dims.0 as f64, image_dims.1 as f64);
+    if img_w <= 0.0 || img_h <= 0.0 {
+        return Err("Image dimensions must be non-zero".to_string());
+    }
+
+    let mut ratio = ratio
+        .filter(|r| r.is_finite() && *r > 0.0)
+        .unwrap_or(img_w / img_h);
+
+    let base = if crop.width > 0.0 && crop.height > 0.0 {
+        crop
+    } else {
+        Crop {
+            x: 0.0,
+            y: 0.0,
+            width: img_w,
+            height: img_h,
+        }
+    };
+
+    if match_orientation.unwrap_or(true)
+        && base.width != base.height
+        && (base.width < base.height) != (ratio < 1.0)
+    {
+        ratio = 1.0 / ratio;
+    }
+
+    let center_x = (base.x + base.width / 2.0).clamp(0.0, img_w);
+    let center_y = (base.y + base.height / 2.0).clamp(0.0, img_h);
+
+    let area = base.width * base.height;
+    let mut width = (area * ratio).sqrt();
+    let mut height = width / ratio;
+    let scale = (img_w / width).min(img_h / height).min(1.0);
+    width *= scale;
+    height *= scale;
+
+    Ok(Crop {
+        x: (center_x - width / 2.0).clamp(0.0, img_w - width),
+        y: (center_y - height / 2.0).clamp(0.0, img_h - height),
+        width,
+        height,
+    })
+}
+
 pub fn apply_flip(image: DynamicImage, horizontal: bool, vertical: bool) -> DynamicImage {
     let mut img = image;
     if horizontal {
@@ -311,20 +442,19 @@ pub struct GlobalAdjustments {
     pub negative_red_balance: f32,
     pub negative_green_balance: f32,
     pub negative_blue_balance: f32,
-    _pad_neg1: f32,
-    _pad_neg2: f32,
+    pub grain_scale: f32,
+    pub grain_seed: u32,
 
     pub has_lut: u32,
     pub lut_intensity: f32,
     pub tonemapper_mode: u32,
-    _pad_lut2: f32,
-    _pad_lut3: f32,
-    _pad_lut4: f32,
-    _pad_lut5: f32,
-
-    _pad_agx1: f32,
-    _pad_agx2: f32,
-    _pad_agx3: f32,
+    pub lut_domain_min_r: f32,
+    pub lut_domain_min_g: f32,
+    pub lut_domain_min_b: f32,
+    pub lut_domain_max_r: f32,
+    pub lut_domain_max_g: f32,
+    pub lut_domain_max_b: f32,
+    pub lut_1d_size: u32,
     pub agx_pipe_to_rendering_matrix: GpuMat3,
     pub agx_rendering_to_pipe_matrix: GpuMat3,
 
@@ -351,10 +481,14 @@ pub struct GlobalAdjustments {
     pub red_curve_count: u32,
     pub green_curve_count: u32,
     pub blue_curve_count: u32,
-    _pad_end1: f32,
-    _pad_end2: f32,
-    _pad_end3: f32,
-    _pad_end4: f32,
+    pub output_linear: u32,
+    pub sharpen_mode: u32,
+    pub sharpen_radius: f32,
+    pub sharpen_threshold: f32,
+    pub has_fill: u32,
+    pub fill_r: f32,
+    pub fill_g: f32,
+    pub fill_b: f32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Pod, Zeroable, Default)]
@@ -419,8 +553,18 @@ pub struct AllAdjustments {
     pub tile_offset_x: u32,
     pub tile_offset_y: u32,
     pub mask_atlas_cols: u32,
+    pub region_offset_x: u32,
+    pub region_offset_y: u32,
+    pub full_width: u32,
+    pub full_height: u32,
 }
 
+pub const SHARPEN_MODE_UNSHARP: u32 = 0;
+pub const SHARPEN_MODE_RATIO: u32 = 1;
+/// Blur radius of the sharpening detail layer in pixels at full resolution. Edits saved
+/// before the radius was configurable were sharpened with this.
+pub const DEFAULT_SHARPEN_RADIUS: f32 = 2.0;
+
 struct AdjustmentScales {
     exposure: f32,
     brightness: f32,
@@ -435,6 +579,7 @@ struct AdjustmentScales {
     vibrance: f32,
 
     sharpness: f32,
+    sharpen_threshold: f32,
     luma_noise_reduction: f32,
     color_noise_reduction: f32,
     clarity: f32,
@@ -479,6 +624,7 @@ const SCALES: AdjustmentScales = AdjustmentScales {
     vibrance: 100.0,
 
     sharpness: 80.0,
+    sharpen_threshold: 1000.0,
     luma_noise_reduction: 100.0,
     color_noise_reduction: 100.0,
     clarity: 200.0,
@@ -754,6 +900,8 @@ fn get_global_adjustments_from_json(
     };
 
     let tone_mapper = js_adjustments["toneMapper"].as_str().unwrap_or("basic");
+    let sharpening_mode = js_adjustments["sharpeningMode"].as_str().unwrap_or("unsharp");
+    let fill = rotation_fill_color(js_adjustments);
     let (pipe_to_rendering, rendering_to_pipe) = calculate_agx_matrices();
 
     GlobalAdjustments {
@@ -849,24 +997,24 @@ fn get_global_adjustments_from_json(
             .as_f64()
             .unwrap_or(0.0) as f32
             / 100.0,
-        _pad_neg1: 0.0,
-        _pad_neg2: 0.0,
+        grain_scale: 1.0,
+        grain_seed: explicit_grain_seed(js_adjustments).unwrap_or(0),
 
+        // Refined to the loaded LUT's stages in `GpuProcessor::run`.
         has_lut: if js_adjustments["lutPath"].is_string() {
-            1
+            crate::lut_processing::LUT_FLAG_3D
         } else {
             0
         },
         lut_intensity: js_adjustments["lutIntensity"].as_f64().unwrap_or(100.0) as f32 / 100.0,
         tonemapper_mode: if tone_mapper == "agx" { 1 } else { 0 },
-        _pad_lut2: 0.0,
-        _pad_lut3: 0.0,
-        _pad_lut4: 0.0,
-        _pad_lut5: 0.0,
-
-        _pad_agx1: 0.0,
-        _pad_agx2: 0.0,
-        _pad_agx3: 0.0,
+        lut_domain_min_r: 0.0,
+        lut_domain_min_g: 0.0,
+        lut_domain_min_b: 0.0,
+        lut_domain_max_r: 1.0,
+        lut_domain_max_g: 1.0,
+        lut_domain_max_b: 1.0,
+        lut_1d_size: 0,
         agx_pipe_to_rendering_matrix: pipe_to_rendering,
         agx_rendering_to_pipe_matrix: rendering_to_pipe,
 
@@ -917,10 +1065,28 @@ fn get_global_adjustments_from_json(
         red_curve_count: red_points.len() as u32,
         green_curve_count: green_points.len() as u32,
         blue_curve_count: blue_points.len() as u32,
-        _pad_end1: 0.0,
-        _pad_end2: 0.0,
-        _pad_end3: 0.0,
-        _pad_end4: 0.0,
+        output_linear: 0,
+        sharpen_mode: if is_visible("details") && sharpening_mode == "ratio" {
+            SHARPEN_MODE_RATIO
+        } else {
+            SHARPEN_MODE_UNSHARP
+        },
+        sharpen_radius: get_val(
+            "details",
+            "sharpeningRadius",
+            1.0,
+            Some(DEFAULT_SHARPEN_RADIUS as f64),
+        ),
+        sharpen_threshold: get_val(
+            "details",
+            "sharpeningThreshold",
+            SCALES.sharpen_threshold,
+            None,
+        ),
+        has_fill: fill.is_some() as u32,
+        fill_r: fill.map_or(0.0, |c| c[0]),
+        fill_g: fill.map_or(0.0, |c| c[1]),
+        fill_b: fill.map_or(0.0, |c| c[2]),
     }
 }
 
@@ -1050,6 +1216,30 @@ fn get_mask_adjustments_from_json(adj: &serde_json::Value) -> MaskAdjustments {
     }
 }
 
+fn explicit_grain_seed(adjustments: &Value) -> Option<u32> {
+    adjustments["grainSeed"].as_u64().map(|seed| seed as u32)
+}
+
+/// Seed for the film grain pattern. An explicit `grainSeed` adjustment wins; otherwise
+/// the seed is derived from the image path and grain settings so every render of the
+/// same edit, preview or export, produces the same grain. FNV-1a keeps it stable
+/// across builds, unlike `DefaultHasher`.
+pub fn grain_seed(path: &str, adjustments: &Value) -> u32 {
+    if let Some(seed) = explicit_grain_seed(adjustments) {
+        return seed;
+    }
+    let grain_settings = format!(
+        "{}|{}|{}",
+        adjustments["grainAmount"], adjustments["grainSize"], adjustments["grainRoughness"]
+    );
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in path.bytes().chain(grain_settings.bytes()) {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
 pub fn get_all_adjustments_from_json(
     js_adjustments: &serde_json::Value,
     is_raw: bool,
@@ -1080,6 +1270,10 @@ pub fn get_all_adjustments_from_json(
         tile_offset_x: 0,
         tile_offset_y: 0,
         mask_atlas_cols: 1,
+        region_offset_x: 0,
+        region_offset_y: 0,
+        full_width: 0,
+        full_height: 0,
     }
 }
 
@@ -1088,6 +1282,7 @@ pub struct GpuContext {
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
     pub limits: wgpu::Limits,
+    pub adapter_info: wgpu::AdapterInfo,
 }
 
 #[derive(Serialize, Clone)]
@@ -1098,6 +1293,23 @@ pub struct HistogramData {
     luma: Vec<f32>,
 }
 
+const RAW_HISTOGRAM_BINS: usize = 256;
+// Demosaicing can leave clipped photosites a hair under 1.0
+const RAW_CLIPPING_THRESHOLD: f32 = 0.999;
+
+/// Histogram of a RAW file's linear sensor data, as opposed to `HistogramData`, which
+/// describes the rendered, display-referred image. Bins are evenly spaced in linear light
+/// from the black level (first bin) to the white level (last bin) and hold pixel counts.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RawHistogramData {
+    red: Vec<u32>,
+    green: Vec<u32>,
+    blue: Vec<u32>,
+    /// Percentage of pixels at the sensor's white level, per channel (R, G, B).
+    clipped: [f32; 3],
+}
+
 #[tauri::command]
 pub fn generate_histogram(
     state: tauri::State<AppState>,
@@ -1118,8 +1330,7 @@ pub fn generate_histogram(
             .image
             .clone();
 
-        let settings = load_settings(app_handle).unwrap_or_default();
-        let preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
+        let preview_dim = crate::get_editor_preview_dim(&app_handle);
         let preview = downscale_f32_image(&image, preview_dim, preview_dim);
         calculate_histogram_from_image(&preview)
     }
@@ -1166,6 +1377,39 @@ pub fn calculate_histogram_from_image(image: &DynamicImage) -> Result<HistogramD
     })
 }
 
+pub fn calculate_raw_histogram(image: &DynamicImage) -> RawHistogramData {
+    let converted;
+    let buffer = match image.as_rgba32f() {
+        Some(buffer) => buffer,
+        None => {
+            converted = image.to_rgba32f();
+            &converted
+        }
+    };
+
+    let mut counts = [[0u32; RAW_HISTOGRAM_BINS]; 3];
+    let mut clipped_counts = [0u64; 3];
+    for pixel in buffer.pixels() {
+        for c in 0..3 {
+            let value = pixel[c].clamp(0.0, 1.0);
+            let bin = ((value * RAW_HISTOGRAM_BINS as f32) as usize).min(RAW_HISTOGRAM_BINS - 1);
+            counts[c][bin] += 1;
+            if value >= RAW_CLIPPING_THRESHOLD {
+                clipped_counts[c] += 1;
+            }
+        }
+    }
+
+    let total = (buffer.width() as u64 * buffer.height() as u64).max(1) as f32;
+    let [red, green, blue] = counts.map(|channel| channel.to_vec());
+    RawHistogramData {
+        red,
+        green,
+        blue,
+        clipped: clipped_counts.map(|count| count as f32 / total * 100.0),
+    }
+}
+
 fn apply_gaussian_smoothing(histogram: &mut Vec<f32>, sigma: f32) {
     if sigma <= 0.0 {
         return;
@@ -1262,8 +1506,7 @@ pub fn generate_waveform(
             .image
             .clone();
 
-        let settings = load_settings(app_handle).unwrap_or_default();
-        let preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
+        let preview_dim = crate::get_editor_preview_dim(&app_handle);
         let preview = downscale_f32_image(&image, preview_dim, preview_dim);
         calculate_waveform_from_image(&preview)
     }
@@ -1497,6 +1740,31 @@ pub fn perform_auto_analysis(image: &DynamicImage) -> AutoAdjustmentResults {
     }
 }
 
+/// Temperature/tint change that neutralizes a sampled colour (display-referred RGB in
+/// 0..1), matching the editor's white balance picker.
+pub fn white_balance_correction(r: f32, g: f32, b: f32) -> (f32, f32) {
+    let lin_r = r.max(0.0).powf(2.2);
+    let lin_g = g.max(0.0).powf(2.2);
+    let lin_b = b.max(0.0).powf(2.2);
+
+    let sum_rb = lin_r + lin_b;
+    let delta_temp = if sum_rb > 0.0001 {
+        (lin_b - lin_r) / sum_rb * 125.0
+    } else {
+        0.0
+    };
+
+    let lin_m = sum_rb / 2.0;
+    let sum_gm = lin_g + lin_m;
+    let delta_tint = if sum_gm > 0.0001 {
+        (lin_g - lin_m) / sum_gm * 400.0
+    } else {
+        0.0
+    };
+
+    (delta_temp, delta_tint)
+}
+
 pub fn auto_results_to_json(results: &AutoAdjustmentResults) -> serde_json::Value {
     json!({
         "exposure": results.exposure,
@@ -1534,4 +1802,251 @@ pub fn calculate_auto_adjustments(
     let results = perform_auto_analysis(&original_image);
 
     Ok(auto_results_to_json(&results))
-}
\ No newline at end of file
+}
+
+fn sample_channel_bilinear(image: &Rgb32FImage, x: f32, y: f32, channel: usize) -> Option<f32> {
+    let (width, height) = image.dimensions();
+    if x < 0.0 || y < 0.0 || x >= (width - 1) as f32 || y >= (height - 1) as f32 {
+        return None;
+    }
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let fx = x - x0 as f32;
+    let fy = y - y0 as f32;
+
+    let p00 = image.get_pixel(x0, y0)[channel];
+    let p10 = image.get_pixel(x0 + 1, y0)[channel];
+    let p01 = image.get_pixel(x0, y0 + 1)[channel];
+    let p11 = image.get_pixel(x0 + 1, y0 + 1)[channel];
+
+    let top = p00 + (p10 - p00) * fx;
+    let bottom = p01 + (p11 - p01) * fx;
+    Some(top + (bottom - top) * fy)
+}
+
+fn estimate_channel_ca(
+    image: &Rgb32FImage,
+    edge_points: &[(u32, u32)],
+    channel: usize,
+) -> (f32, f32) {
+    const MAX_SLIDER: i32 = 100;
+    let (width, height) = image.dimensions();
+    let center = (width as f32 / 2.0, height as f32 / 2.0);
+
+    let error_for = |slider: i32| -> f32 {
+        // Mirrors the shader: the channel is sampled at `center + offset * (1 - ca)`
+        let ca = slider as f32 / SCALES.chromatic_aberration;
+        let mut total = 0.0f32;
+        let mut count = 0u32;
+        for &(x, y) in edge_points {
+            let sx = center.0 + (x as f32 - center.0) * (1.0 - ca);
+            let sy = center.1 + (y as f32 - center.1) * (1.0 - ca);
+            if let Some(value) = sample_channel_bilinear(image, sx, sy, channel) {
+                let diff = value - image.get_pixel(x, y)[1];
+                total += diff * diff;
+                count += 1;
+            }
+        }
+        if count == 0 { f32::MAX } else { total / count as f32 }
+    };
+
+    let baseline_error = error_for(0);
+    let (best_slider, best_error) = (-MAX_SLIDER..=MAX_SLIDER)
+        .into_par_iter()
+        .map(|slider| (slider, error_for(slider)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap_or((0, baseline_error));
+
+    let improvement = if baseline_error > 0.0 {
+        (baseline_error - best_error) / baseline_error
+    } else {
+        0.0
+    };
+    (best_slider as f32, improvement)
+}
+
+pub fn perform_ca_analysis(image: &DynamicImage) -> (f32, f32) {
+    const ANALYSIS_DIM: u32 = 1024;
+    const EDGE_THRESHOLD: f32 = 0.15;
+    const MAX_EDGE_POINTS: usize = 20000;
+    const MIN_EDGE_POINTS: usize = 200;
+    const MIN_IMPROVEMENT: f32 = 0.03;
+
+    let analysis = downscale_f32_image(image, ANALYSIS_DIM, ANALYSIS_DIM).to_rgb32f();
+    let (width, height) = analysis.dimensions();
+    if width < 16 || height < 16 {
+        return (0.0, 0.0);
+    }
+
+    let center = (width as f32 / 2.0, height as f32 / 2.0);
+    let max_radius = (center.0 * center.0 + center.1 * center.1).sqrt();
+
+    // Lateral CA is radial, so only edges running across the radius reveal misregistration
+    let mut edge_points: Vec<(u32, u32, f32)> = Vec::new();
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let gx = analysis.get_pixel(x + 1, y)[1] - analysis.get_pixel(x - 1, y)[1];
+            let gy = analysis.get_pixel(x, y + 1)[1] - analysis.get_pixel(x, y - 1)[1];
+            let dx = x as f32 - center.0;
+            let dy = y as f32 - center.1;
+            let radius = (dx * dx + dy * dy).sqrt();
+            if radius < max_radius * 0.3 {
+                continue;
+            }
+            let radial_gradient = (gx * dx + gy * dy).abs() / radius;
+            if radial_gradient > EDGE_THRESHOLD {
+                edge_points.push((x, y, radial_gradient));
+            }
+        }
+    }
+
+    if edge_points.len() < MIN_EDGE_POINTS {
+        return (0.0, 0.0);
+    }
+
+    edge_points.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    let edge_points: Vec<(u32, u32)> = edge_points
+        .into_iter()
+        .take(MAX_EDGE_POINTS)
+        .map(|(x, y, _)| (x, y))
+        .collect();
+
+    let (red_cyan, red_improvement) = estimate_channel_ca(&analysis, &edge_points, 0);
+    let (blue_yellow, blue_improvement) = estimate_channel_ca(&analysis, &edge_points, 2);
+
+    (
+        if red_improvement >= MIN_IMPROVEMENT { red_cyan } else { 0.0 },
+        if blue_improvement >= MIN_IMPROVEMENT { blue_yellow } else { 0.0 },
+    )
+}
+
+#[tauri::command]
+pub fn detect_chromatic_aberration(
+    state: tauri::State<AppState>,
+) -> Result<serde_json::Value, String> {
+    let original_image = state
+        .original_image
+        .lock()
+        .unwrap()
+        .as_ref()
+        .ok_or("No image loaded for chromatic aberration detection")?
+        .image
+        .clone();
+
+    let (red_cyan, blue_yellow) = perform_ca_analysis(&original_image);
+
+    Ok(json!({
+        "chromaticAberrationRedCyan": red_cyan,
+        "chromaticAberrationBlueYellow": blue_yellow,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ImageMetadata, RAW_HISTOGRAM_BINS, apply_rotation, calculate_raw_histogram,
+        get_global_adjustments_from_json,
+    };
+    use image::{DynamicImage, ImageBuffer, Rgba};
+    use serde_json::{Value, json};
+
+    #[test]
+    fn empty_sidecar_uses_defaults() {
+        let metadata: ImageMetadata = serde_json::from_str("{}").unwrap();
+        assert_eq!(metadata.version, 1);
+        assert_eq!(metadata.rating, 0);
+        assert_eq!(metadata.adjustments, Value::Null);
+        assert!(metadata.tags.is_none());
+        assert!(metadata.source_size.is_none());
+        assert!(metadata.source_hash.is_none());
+    }
+
+    #[test]
+    fn older_sidecar_without_newer_fields_parses() {
+        let metadata: ImageMetadata = serde_json::from_value(json!({
+            "version": 1,
+            "rating": 3,
+            "adjustments": { "exposure": 0.5 }
+        }))
+        .unwrap();
+        assert_eq!(metadata.rating, 3);
+        assert_eq!(metadata.adjustments["exposure"], 0.5);
+        assert!(metadata.tags.is_none());
+        assert!(metadata.source_size.is_none());
+        assert!(metadata.source_hash.is_none());
+    }
+
+    #[test]
+    fn differently_cased_and_loosely_typed_fields_parse() {
+        let metadata: ImageMetadata = serde_json::from_value(json!({
+            "Rating": "4",
+            "Keywords": ["beach", "sunset"]
+        }))
+        .unwrap();
+        assert_eq!(metadata.version, 1);
+        assert_eq!(metadata.rating, 4);
+        assert_eq!(metadata.tags, Some(vec!["beach".to_string(), "sunset".to_string()]));
+
+        let rounded: ImageMetadata = serde_json::from_value(json!({ "rating": 4.6 })).unwrap();
+        assert_eq!(rounded.rating, 5);
+        let clamped: ImageMetadata = serde_json::from_value(json!({ "rating": 9 })).unwrap();
+        assert_eq!(clamped.rating, 5);
+    }
+
+    #[test]
+    fn raw_histogram_bins_linear_values_and_counts_clipping() {
+        let pixels = [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.25, 0.0],
+            [1.0, 0.9995, 0.5],
+            [1.5, 0.998, -0.1],
+        ];
+        let buffer = ImageBuffer::<Rgba<f32>, _>::from_fn(2, 2, |x, y| {
+            let [r, g, b] = pixels[(y * 2 + x) as usize];
+            Rgba([r, g, b, 1.0])
+        });
+        let histogram = calculate_raw_histogram(&DynamicImage::ImageRgba32F(buffer));
+
+        let last = RAW_HISTOGRAM_BINS - 1;
+        assert_eq!(histogram.red.iter().sum::<u32>(), 4);
+        assert_eq!(
+            (histogram.red[0], histogram.red[128], histogram.red[last]),
+            (1, 1, 2)
+        );
+        assert_eq!((histogram.green[0], histogram.green[64]), (1, 1));
+        assert_eq!(histogram.green[last], 2);
+        assert_eq!((histogram.blue[0], histogram.blue[128]), (3, 1));
+
+        // Values past the white level count as clipped; 0.998 is under the threshold
+        assert_eq!(histogram.clipped, [50.0, 25.0, 0.0]);
+    }
+
+    #[test]
+    fn rotation_leaves_uncovered_area_transparent() {
+        let buffer = ImageBuffer::<Rgba<f32>, _>::from_pixel(32, 32, Rgba([0.8, 0.4, 0.2, 1.0]));
+        let rotated = apply_rotation(&DynamicImage::ImageRgba32F(buffer), 10.0).to_rgba32f();
+
+        // The shader composites the fill under this alpha, so nothing may be painted in here
+        assert_eq!(rotated.get_pixel(0, 0).0, [0.0; 4]);
+        assert_eq!(rotated.get_pixel(31, 31).0, [0.0; 4]);
+        let center = rotated.get_pixel(16, 16);
+        assert_eq!(center[3], 1.0);
+        assert!((center[0] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fill_color_is_passed_to_the_shader_untouched() {
+        let filled = get_global_adjustments_from_json(
+            &json!({ "fillColor": "#ff8000", "exposure": 2.0 }),
+            true,
+        );
+        assert_eq!(filled.has_fill, 1);
+        assert_eq!(
+            [filled.fill_r, filled.fill_g, filled.fill_b],
+            [1.0, 128.0 / 255.0, 0.0]
+        );
+
+        let unfilled = get_global_adjustments_from_json(&json!({ "fillColor": null }), false);
+        assert_eq!(unfilled.has_fill, 0);
+    }
+}
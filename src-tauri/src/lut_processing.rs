@@ -2,12 +2,131 @@ use anyhow::{Result, anyhow};
 use image::{DynamicImage, GenericImageView};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use serde::Serialize;
 use std::path::Path;
 
+/// Bits of `GlobalAdjustments::has_lut` telling the shader which LUT stages to run.
+pub const LUT_FLAG_3D: u32 = 1;
+pub const LUT_FLAG_1D: u32 = 2;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LutDimension {
+    OneD,
+    ThreeD,
+    ShaperThreeD,
+}
+
+/// A parsed colour LUT. `size`/`data` hold the 3D lattice and `shaper_size`/`shaper_data`
+/// an optional per-channel 1D curve applied before it (or on its own for 1D-only files).
+/// The domain maps input values onto the first stage; shaper outputs are stored already
+/// normalised to the lattice's unit input range.
 #[derive(Debug, Clone)]
 pub struct Lut {
+    pub dimension: LutDimension,
     pub size: u32,
     pub data: Vec<f32>,
+    pub shaper_size: u32,
+    pub shaper_data: Vec<f32>,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+}
+
+impl Lut {
+    fn from_3d(size: u32, data: Vec<f32>) -> Self {
+        Lut {
+            dimension: LutDimension::ThreeD,
+            size,
+            data,
+            shaper_size: 0,
+            shaper_data: Vec::new(),
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+        }
+    }
+
+    /// The size reported to the frontend: the 3D lattice size, or the 1D entry count for 1D-only LUTs.
+    pub fn display_size(&self) -> u32 {
+        match self.dimension {
+            LutDimension::OneD => self.shaper_size,
+            _ => self.size,
+        }
+    }
+
+    pub fn gpu_flags(&self) -> u32 {
+        match self.dimension {
+            LutDimension::OneD => LUT_FLAG_1D,
+            LutDimension::ThreeD => LUT_FLAG_3D,
+            LutDimension::ShaperThreeD => LUT_FLAG_1D | LUT_FLAG_3D,
+        }
+    }
+}
+
+fn parse_size_line(parts: &[&str], line_num: usize, line: &str) -> Result<u32> {
+    if parts.len() < 2 {
+        return Err(anyhow!(
+            "Malformed {} on line {}: '{}'",
+            parts[0],
+            line_num,
+            line
+        ));
+    }
+    parts[1].parse().map_err(|e| {
+        anyhow!(
+            "Failed to parse {} on line {}: '{}'. Error: {}",
+            parts[0],
+            line_num,
+            line,
+            e
+        )
+    })
+}
+
+fn parse_input_range_line(parts: &[&str], line_num: usize, line: &str) -> Result<(f32, f32)> {
+    if parts.len() < 3 {
+        return Err(anyhow!(
+            "Malformed {} on line {}: '{}'. Expected min and max values",
+            parts[0],
+            line_num,
+            line
+        ));
+    }
+    let parse = |s: &str| -> Result<f32> {
+        s.parse().map_err(|e| {
+            anyhow!(
+                "Failed to parse {} on line {}: '{}'. Error: {}",
+                parts[0],
+                line_num,
+                line,
+                e
+            )
+        })
+    };
+    Ok((parse(parts[1])?, parse(parts[2])?))
+}
+
+fn parse_domain_line(parts: &[&str], line_num: usize, line: &str) -> Result<[f32; 3]> {
+    if parts.len() < 4 {
+        return Err(anyhow!(
+            "Malformed {} on line {}: '{}'. Expected 3 float values",
+            parts[0],
+            line_num,
+            line
+        ));
+    }
+    let mut values = [0.0f32; 3];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = parts[i + 1].parse().map_err(|e| {
+            anyhow!(
+                "Failed to parse {} on line {}: '{}'. Error: {}",
+                parts[0],
+                line_num,
+                line,
+                e
+            )
+        })?;
+    }
+    Ok(values)
 }
 
 fn parse_cube(path: &Path) -> Result<Lut> {
@@ -15,7 +134,13 @@ fn parse_cube(path: &Path) -> Result<Lut> {
     let reader = BufReader::new(file);
 
     let mut size: Option<u32> = None;
+    let mut size_1d: Option<u32> = None;
     let mut data: Vec<f32> = Vec::new();
+    let mut has_explicit_domain = false;
+    let mut domain_min = [0.0f32; 3];
+    let mut domain_max = [1.0f32; 3];
+    let mut input_range_1d: Option<(f32, f32)> = None;
+    let mut input_range_3d: Option<(f32, f32)> = None;
     let mut line_num = 0;
 
     for line in reader.lines() {
@@ -33,27 +158,27 @@ fn parse_cube(path: &Path) -> Result<Lut> {
         }
 
         match parts[0].to_uppercase().as_str() {
-            "TITLE" | "DOMAIN_MIN" | "DOMAIN_MAX" => continue,
-
-            "LUT_3D_SIZE" => {
-                if parts.len() < 2 {
-                    return Err(anyhow!(
-                        "Malformed LUT_3D_SIZE on line {}: '{}'",
-                        line_num,
-                        line
-                    ));
-                }
-                size = Some(parts[1].parse().map_err(|e| {
-                    anyhow!(
-                        "Failed to parse LUT_3D_SIZE on line {}: '{}'. Error: {}",
-                        line_num,
-                        line,
-                        e
-                    )
-                })?);
+            "TITLE" => continue,
+
+            "DOMAIN_MIN" => {
+                domain_min = parse_domain_line(&parts, line_num, &line)?;
+                has_explicit_domain = true;
+            }
+            "DOMAIN_MAX" => {
+                domain_max = parse_domain_line(&parts, line_num, &line)?;
+                has_explicit_domain = true;
             }
+            "LUT_1D_INPUT_RANGE" => {
+                input_range_1d = Some(parse_input_range_line(&parts, line_num, &line)?)
+            }
+            "LUT_3D_INPUT_RANGE" => {
+                input_range_3d = Some(parse_input_range_line(&parts, line_num, &line)?)
+            }
+
+            "LUT_1D_SIZE" => size_1d = Some(parse_size_line(&parts, line_num, &line)?),
+            "LUT_3D_SIZE" => size = Some(parse_size_line(&parts, line_num, &line)?),
             _ => {
-                if size.is_some() {
+                if size.is_some() || size_1d.is_some() {
                     if parts.len() < 3 {
                         return Err(anyhow!(
                             "Invalid data line on line {}: '{}'. Expected 3 float values, found {}",
@@ -94,20 +219,78 @@ fn parse_cube(path: &Path) -> Result<Lut> {
         }
     }
 
-    let lut_size = size.ok_or(anyhow!("LUT_3D_SIZE not found in .cube file"))?;
-    let expected_len = (lut_size * lut_size * lut_size * 3) as usize;
+    if size.is_none() && size_1d.is_none() {
+        return Err(anyhow!(
+            "Neither LUT_3D_SIZE nor LUT_1D_SIZE found in .cube file"
+        ));
+    }
+    let lut_size = size.unwrap_or(0);
+    let shaper_size = size_1d.unwrap_or(0);
+    if size_1d == Some(1) {
+        return Err(anyhow!("LUT_1D_SIZE must be at least 2"));
+    }
+
+    // When both are present (e.g. Resolve shaper LUTs) the 1D entries precede the 3D lattice.
+    let shaper_len = (shaper_size * 3) as usize;
+    let expected_len = shaper_len + (lut_size * lut_size * lut_size * 3) as usize;
     if data.len() != expected_len {
         return Err(anyhow!(
-            "LUT data size mismatch. Expected {} float values (for size {}), but found {}. The file may be corrupt or incomplete.",
+            "LUT data size mismatch. Expected {} float values (for 1D size {}, 3D size {}), but found {}. The file may be corrupt or incomplete.",
             expected_len,
+            shaper_size,
             lut_size,
             data.len()
         ));
     }
+    let data_3d = data.split_off(shaper_len);
+    let mut shaper_data = data;
+
+    let dimension = match (size_1d.is_some(), size.is_some()) {
+        (true, true) => LutDimension::ShaperThreeD,
+        (true, false) => LutDimension::OneD,
+        _ => LutDimension::ThreeD,
+    };
+
+    // Explicit DOMAIN_MIN/DOMAIN_MAX win; otherwise the input range of the first stage applies.
+    let first_stage_range = if size_1d.is_some() {
+        input_range_1d
+    } else {
+        input_range_3d
+    };
+    if let (false, Some((min, max))) = (has_explicit_domain, first_stage_range) {
+        domain_min = [min; 3];
+        domain_max = [max; 3];
+    }
+
+    // With a shaper, LUT_3D_INPUT_RANGE describes where the shaper's outputs land on the
+    // lattice, so fold it into the shaper values rather than the input domain.
+    if let (Some(_), Some(_), Some((min, max))) = (size_1d, size, input_range_3d) {
+        if max <= min {
+            return Err(anyhow!(
+                "Invalid LUT_3D_INPUT_RANGE: max must be greater than min"
+            ));
+        }
+        for value in shaper_data.iter_mut() {
+            *value = (*value - min) / (max - min);
+        }
+    }
+
+    for i in 0..3 {
+        if domain_max[i] <= domain_min[i] {
+            return Err(anyhow!(
+                "Invalid LUT domain: DOMAIN_MAX must be greater than DOMAIN_MIN for every channel"
+            ));
+        }
+    }
 
     Ok(Lut {
+        dimension,
         size: lut_size,
-        data,
+        data: data_3d,
+        shaper_size,
+        shaper_data,
+        domain_min,
+        domain_max,
     })
 }
 
@@ -147,7 +330,7 @@ fn parse_3dl(path: &Path) -> Result<Lut> {
         ));
     }
 
-    Ok(Lut { size, data })
+    Ok(Lut::from_3d(size, data))
 }
 
 fn parse_hald(image: DynamicImage) -> Result<Lut> {
@@ -179,7 +362,7 @@ fn parse_hald(image: DynamicImage) -> Result<Lut> {
         data.push(pixel[2] as f32 / 255.0);
     }
 
-    Ok(Lut { size, data })
+    Ok(Lut::from_3d(size, data))
 }
 
 pub fn parse_lut_file(path_str: &str) -> Result<Lut> {
@@ -200,3 +383,201 @@ pub fn parse_lut_file(path_str: &str) -> Result<Lut> {
         _ => Err(anyhow!("Unsupported LUT file format: {}", extension)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const IDENTITY_3D_SIZE_2: &str = "\
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+
+    /// Lattice that maps every input to its complement, written with red varying fastest.
+    const INVERT_3D_SIZE_2: &str = "\
+1 1 1
+0 1 1
+1 0 1
+0 0 1
+1 1 0
+0 1 0
+1 0 0
+0 0 0
+";
+
+    /// CPU mirror of the shader's LUT stage: domain, then shaper, then trilinear lattice lookup.
+    fn apply_lut(lut: &Lut, rgb: [f32; 3]) -> [f32; 3] {
+        let mut uv = [0, 1, 2]
+            .map(|c| (rgb[c] - lut.domain_min[c]) / (lut.domain_max[c] - lut.domain_min[c]));
+        if lut.gpu_flags() & LUT_FLAG_1D != 0 {
+            let last = (lut.shaper_size - 1) as f32;
+            for (c, value) in uv.iter_mut().enumerate() {
+                let scaled = value.clamp(0.0, 1.0) * last;
+                let i0 = scaled.floor() as usize;
+                let i1 = (i0 + 1).min(last as usize);
+                let f = scaled - i0 as f32;
+                let a = lut.shaper_data[i0 * 3 + c];
+                let b = lut.shaper_data[i1 * 3 + c];
+                *value = a + (b - a) * f;
+            }
+        }
+        if lut.gpu_flags() & LUT_FLAG_3D == 0 {
+            return uv;
+        }
+        let n = lut.size as usize;
+        let last = (n - 1) as f32;
+        let scaled = uv.map(|v| v.clamp(0.0, 1.0) * last);
+        let i0 = scaled.map(|v| v.floor() as usize);
+        let f = [0, 1, 2].map(|c| scaled[c] - i0[c] as f32);
+        let mut out = [0.0f32; 3];
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let idx = [0, 1, 2].map(|c| (i0[c] + offset[c]).min(n - 1));
+            let weight: f32 = (0..3)
+                .map(|c| if offset[c] == 1 { f[c] } else { 1.0 - f[c] })
+                .product();
+            let base = (idx[0] + idx[1] * n + idx[2] * n * n) * 3;
+            for (c, value) in out.iter_mut().enumerate() {
+                *value += lut.data[base + c] * weight;
+            }
+        }
+        out
+    }
+
+    fn assert_rgb_close(actual: [f32; 3], expected: [f32; 3]) {
+        for c in 0..3 {
+            assert!(
+                (actual[c] - expected[c]).abs() < 1e-5,
+                "expected {:?}, got {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    fn parse_cube_str(contents: &str) -> Result<Lut> {
+        let mut file = tempfile::Builder::new().suffix(".cube").tempfile()?;
+        file.write_all(contents.as_bytes())?;
+        parse_lut_file(file.path().to_str().unwrap())
+    }
+
+    #[test]
+    fn cube_without_domain_defaults_to_unit_range() {
+        let lut = parse_cube_str(&format!("LUT_3D_SIZE 2\n{}", IDENTITY_3D_SIZE_2)).unwrap();
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+    }
+
+    #[test]
+    fn cube_reads_per_channel_domain() {
+        let lut = parse_cube_str(&format!(
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0.0 0.1 -0.5\nDOMAIN_MAX 1.0 2.0 4.0\n{}",
+            IDENTITY_3D_SIZE_2
+        ))
+        .unwrap();
+        assert_eq!(lut.domain_min, [0.0, 0.1, -0.5]);
+        assert_eq!(lut.domain_max, [1.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn explicit_domain_overrides_input_range() {
+        let with_range_only = parse_cube_str(&format!(
+            "LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE -0.25 1.5\n{}",
+            IDENTITY_3D_SIZE_2
+        ))
+        .unwrap();
+        assert_eq!(with_range_only.domain_min, [-0.25; 3]);
+        assert_eq!(with_range_only.domain_max, [1.5; 3]);
+
+        let with_both = parse_cube_str(&format!(
+            "LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE -0.25 1.5\nDOMAIN_MAX 2.0 2.0 2.0\n{}",
+            IDENTITY_3D_SIZE_2
+        ))
+        .unwrap();
+        assert_eq!(with_both.domain_min, [0.0; 3]);
+        assert_eq!(with_both.domain_max, [2.0; 3]);
+    }
+
+    #[test]
+    fn cube_rejects_empty_or_malformed_domain() {
+        let inverted = format!(
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 0 1\nDOMAIN_MAX 1 1 1\n{}",
+            IDENTITY_3D_SIZE_2
+        );
+        assert!(parse_cube_str(&inverted).is_err());
+
+        let missing_value = format!("LUT_3D_SIZE 2\nDOMAIN_MAX 1 1\n{}", IDENTITY_3D_SIZE_2);
+        assert!(parse_cube_str(&missing_value).is_err());
+    }
+
+    #[test]
+    fn one_d_only_cube_becomes_a_shaper() {
+        let lut = parse_cube_str(
+            "TITLE \"Contrast\"\nLUT_1D_SIZE 3\nLUT_1D_INPUT_RANGE 0.0 2.0\n0 0 0\n0.4 0.5 0.6\n1 1 1\n",
+        )
+        .unwrap();
+        assert_eq!(lut.dimension, LutDimension::OneD);
+        assert_eq!(lut.gpu_flags(), LUT_FLAG_1D);
+        assert_eq!(lut.display_size(), 3);
+        assert_eq!(lut.size, 0);
+        assert!(lut.data.is_empty());
+        assert_eq!(lut.shaper_size, 3);
+        assert_eq!(
+            lut.shaper_data,
+            vec![0.0, 0.0, 0.0, 0.4, 0.5, 0.6, 1.0, 1.0, 1.0]
+        );
+        assert_eq!(lut.domain_max, [2.0; 3]);
+    }
+
+    #[test]
+    fn inverting_3d_lut_maps_known_inputs() {
+        let lut = parse_cube_str(&format!("LUT_3D_SIZE 2\n{}", INVERT_3D_SIZE_2)).unwrap();
+        assert_rgb_close(apply_lut(&lut, [0.0, 0.0, 0.0]), [1.0, 1.0, 1.0]);
+        assert_rgb_close(apply_lut(&lut, [1.0, 0.0, 0.0]), [0.0, 1.0, 1.0]);
+        assert_rgb_close(apply_lut(&lut, [0.0, 1.0, 1.0]), [1.0, 0.0, 0.0]);
+        assert_rgb_close(apply_lut(&lut, [0.25, 0.5, 0.75]), [0.75, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn input_range_scales_inputs_onto_the_lattice() {
+        let lut = parse_cube_str(&format!(
+            "LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE 0.0 2.0\n{}",
+            INVERT_3D_SIZE_2
+        ))
+        .unwrap();
+        assert_rgb_close(apply_lut(&lut, [2.0, 0.0, 1.0]), [0.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn shaper_cube_applies_both_ranges() {
+        // The shaper squares its input over [0, 4] and emits values in [0, 16], which
+        // LUT_3D_INPUT_RANGE then places onto the inverting lattice.
+        let lut = parse_cube_str(&format!(
+            "LUT_1D_SIZE 3\nLUT_3D_SIZE 2\nLUT_1D_INPUT_RANGE 0.0 4.0\nLUT_3D_INPUT_RANGE 0.0 16.0\n\
+             0 0 0\n4 4 4\n16 16 16\n{}",
+            INVERT_3D_SIZE_2
+        ))
+        .unwrap();
+        assert_eq!(lut.dimension, LutDimension::ShaperThreeD);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [4.0; 3]);
+        assert_eq!(
+            lut.shaper_data,
+            vec![0.0, 0.0, 0.0, 0.25, 0.25, 0.25, 1.0, 1.0, 1.0]
+        );
+        assert_rgb_close(apply_lut(&lut, [0.0, 2.0, 4.0]), [1.0, 0.75, 0.0]);
+    }
+
+    #[test]
+    fn one_d_cube_with_wrong_entry_count_is_rejected() {
+        assert!(parse_cube_str("LUT_1D_SIZE 3\n0 0 0\n1 1 1\n").is_err());
+        assert!(parse_cube_str("LUT_1D_SIZE 1\n0.5 0.5 0.5\n").is_err());
+    }
+}
@@ -6,25 +6,32 @@ static GLOBAL: MiMalloc = MiMalloc;
 
 mod ai_processing;
 mod ai_connector;
+mod color_profile;
+mod crop_overlay;
 mod culling;
 mod denoising;
+mod errors;
 mod file_management;
+mod focus_stacking;
 mod formats;
 mod gpu_processing;
+mod hdr_merge;
 mod image_loader;
 mod image_processing;
 mod inpainting;
 mod lut_processing;
 mod mask_generation;
+mod palette;
 mod panorama_stitching;
 mod panorama_utils;
 mod preset_converter;
 mod raw_processing;
 mod tagging;
 mod tagging_utils;
+mod xmp_sidecar;
 
 use log;
-use std::collections::{HashMap, hash_map::DefaultHasher};
+use std::collections::{HashMap, HashSet, hash_map::DefaultHasher};
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io::Cursor;
@@ -62,28 +69,44 @@ use crate::ai_processing::{
     generate_image_embeddings, get_or_init_ai_models, run_sam_decoder, run_sky_seg_model,
     run_u2netp_model,
 };
+use crate::errors::AppError;
 use crate::file_management::{
-    AppSettings, load_settings, parse_virtual_path,
+    AppSettings, WindowBounds, load_settings, parse_virtual_path,
     read_file_mapped,
 };
-use crate::formats::is_raw_file;
+use crate::formats::{is_raw_file, is_supported_image_file};
 use crate::image_loader::{
-    composite_patches_on_image, load_and_composite, load_base_image_from_bytes,
+    DecodedImage, composite_patches_on_image, decode_base_image_from_bytes, load_and_composite,
+    load_linear_raw_from_bytes,
 };
 use crate::image_processing::{
     Crop, GpuContext, ImageMetadata, apply_coarse_rotation, apply_crop, apply_flip, apply_rotation,
     get_all_adjustments_from_json, get_or_init_gpu_context, process_and_get_dynamic_image,
-    downscale_f32_image, apply_cpu_default_raw_processing,
+    downscale_f32_image, downscale_f32_image_with_quality, apply_cpu_default_raw_processing,
+    DownscaleQuality, HistogramData, RawHistogramData, WaveformData, grain_seed,
 };
 use crate::lut_processing::Lut;
-use crate::mask_generation::{AiPatchDefinition, MaskDefinition, generate_mask_bitmap};
+use crate::mask_generation::{
+    AiPatchDefinition, MaskDefinition, RangeSource, generate_mask_bitmap,
+};
+use crate::raw_processing::RawDevelopParams;
 use tagging_utils::{candidates, hierarchy};
 
 #[derive(Clone)]
 pub struct LoadedImage {
     path: String,
-    image: DynamicImage,
+    image: Arc<DynamicImage>,
     is_raw: bool,
+    is_embedded_preview: bool,
+    develop_hash: u64,
+}
+
+impl LoadedImage {
+    /// Whether the pixels are developed sensor data that need the RAW tone pipeline. A RAW
+    /// that fell back to its embedded JPEG is still a RAW to the UI, but not here.
+    fn develops_as_raw(&self) -> bool {
+        self.is_raw && !self.is_embedded_preview
+    }
 }
 
 #[derive(Clone)]
@@ -91,6 +114,7 @@ pub struct CachedPreview {
     image: DynamicImage,
     small_image: DynamicImage,
     transform_hash: u64,
+    preview_dim: u32,
     scale: f32,
     unscaled_crop_offset: (f32, f32),
 }
@@ -125,6 +149,8 @@ pub struct AppState {
     export_task_handle: Mutex<Option<JoinHandle<()>>>,
     panorama_result: Arc<Mutex<Option<DynamicImage>>>,
     denoise_result: Arc<Mutex<Option<DynamicImage>>>,
+    hdr_result: Arc<Mutex<Option<DynamicImage>>>,
+    focus_stack_result: Arc<Mutex<Option<DynamicImage>>>,
     indexing_task_handle: Mutex<Option<JoinHandle<()>>>,
     pub lut_cache: Mutex<HashMap<String, Arc<Lut>>>,
     initial_file_path: Mutex<Option<String>>,
@@ -132,6 +158,47 @@ pub struct AppState {
     preview_worker_tx: Mutex<Option<Sender<PreviewJob>>>,
     pub mask_cache: Mutex<HashMap<u64, GrayImage>>,
     pub patch_cache: Mutex<HashMap<String, serde_json::Value>>,
+    pub tag_usage_cache: Mutex<HashMap<String, tagging::ExistingTags>>,
+    display_preview_dim: Mutex<Option<u32>>,
+    last_processed_preview: Mutex<Option<RgbImage>>,
+    fullscreen_cancellation_token: Mutex<Arc<AtomicBool>>,
+    full_resolution_cancellation_token: Mutex<Arc<AtomicBool>>,
+    community_preview_cancellation_token: Mutex<Arc<AtomicBool>>,
+    thumbnail_pregeneration_cancellation_token: Mutex<Arc<AtomicBool>>,
+    perf_metrics_enabled: AtomicBool,
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfMetric {
+    pub context: String,
+    pub width: u32,
+    pub height: u32,
+    pub decode_ms: Option<f64>,
+    pub transform_ms: Option<f64>,
+    pub upload_ms: Option<f64>,
+    pub compute_ms: Option<f64>,
+    pub readback_ms: Option<f64>,
+}
+
+/// Perf metrics are only emitted while the debug setting is enabled.
+pub fn configure_perf_metrics(app_handle: &tauri::AppHandle, enabled: bool) {
+    let state = app_handle.state::<AppState>();
+    state.perf_metrics_enabled.store(enabled, Ordering::Relaxed);
+}
+
+pub fn perf_metrics_enabled(state: &AppState) -> bool {
+    state.perf_metrics_enabled.load(Ordering::Relaxed)
+}
+
+pub fn emit_perf_metric(app_handle: &tauri::AppHandle, metric: PerfMetric) {
+    if perf_metrics_enabled(&app_handle.state::<AppState>()) {
+        let _ = app_handle.emit("perf-metric", metric);
+    }
+}
+
+fn duration_ms(duration: std::time::Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
 }
 
 #[derive(serde::Serialize)]
@@ -141,6 +208,8 @@ struct LoadImageResult {
     metadata: ImageMetadata,
     exif: HashMap<String, String>,
     is_raw: bool,
+    is_embedded_preview: bool,
+    source_color_profile: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -169,6 +238,8 @@ struct ExportSettings {
     strip_gps: bool,
     filename_template: Option<String>,
     watermark: Option<WatermarkSettings>,
+    #[serde(default)]
+    scene_linear: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -181,6 +252,7 @@ pub struct CommunityPreset {
 #[derive(Serialize)]
 struct LutParseResult {
     size: u32,
+    dimension: lut_processing::LutDimension,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -213,6 +285,17 @@ struct ImageDimensions {
     height: u32,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DisplayInfo {
+    physical_width: u32,
+    physical_height: u32,
+    scale_factor: f64,
+    preview_dimension: u32,
+}
+
+const MIN_EDITOR_PREVIEW_DIM: u32 = 720;
+
 fn apply_all_transformations(
     image: &DynamicImage,
     adjustments: &serde_json::Value,
@@ -314,6 +397,24 @@ fn calculate_transform_hash(adjustments: &serde_json::Value) -> u64 {
     hasher.finish()
 }
 
+/// Identifies how the loaded pixels were produced from the file: the effective RAW develop
+/// parameters (per-camera overrides included) and the source profile they were converted from.
+/// Anything persisted across sessions must key on this, since the same path and adjustments
+/// render differently once these settings change.
+fn calculate_develop_hash(
+    develops_as_raw: bool,
+    raw_params: &RawDevelopParams,
+    color_profile: Option<&str>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    develops_as_raw.hash(&mut hasher);
+    if develops_as_raw {
+        raw_params.hash(&mut hasher);
+    }
+    color_profile.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn calculate_full_job_hash(path: &str, adjustments: &serde_json::Value) -> u64 {
     let mut hasher = DefaultHasher::new();
     path.hash(&mut hasher);
@@ -368,27 +469,55 @@ fn hydrate_adjustments(state: &tauri::State<AppState>, adjustments: &mut serde_j
     }
 }
 
+pub fn get_editor_preview_dim(app_handle: &tauri::AppHandle) -> u32 {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let ceiling = settings.editor_preview_resolution.unwrap_or(1920);
+    let display_dim = *app_handle.state::<AppState>().display_preview_dim.lock().unwrap();
+
+    match display_dim {
+        Some(dim) => dim.clamp(MIN_EDITOR_PREVIEW_DIM.min(ceiling), ceiling),
+        None => ceiling,
+    }
+}
+
+fn get_preview_downscale_quality(app_handle: &tauri::AppHandle) -> DownscaleQuality {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    DownscaleQuality::from_setting(settings.preview_downscale_quality.as_deref())
+}
+
 fn generate_transformed_preview(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
     loaded_image: &LoadedImage,
     adjustments: &serde_json::Value,
-    app_handle: &tauri::AppHandle,
+    final_preview_dim: u32,
+    downscale_quality: DownscaleQuality,
 ) -> Result<(DynamicImage, f32, (f32, f32)), String> {
+    let transform_start = std::time::Instant::now();
     let patched_original_image = composite_patches_on_image(&loaded_image.image, adjustments)
         .map_err(|e| format!("Failed to composite AI patches: {}", e))?;
 
     let (transformed_full_res, unscaled_crop_offset) =
         apply_all_transformations(&patched_original_image, adjustments);
 
-    let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let final_preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
-
     let (full_res_w, full_res_h) = transformed_full_res.dimensions();
+    emit_perf_metric(
+        app_handle,
+        PerfMetric {
+            context: "generate_transformed_preview".to_string(),
+            width: full_res_w,
+            height: full_res_h,
+            transform_ms: Some(duration_ms(transform_start.elapsed())),
+            ..Default::default()
+        },
+    );
 
     let final_preview_base = if full_res_w > final_preview_dim || full_res_h > final_preview_dim {
-        downscale_f32_image(
+        downscale_f32_image_with_quality(
             &transformed_full_res,
             final_preview_dim,
             final_preview_dim,
+            downscale_quality,
         )
     } else {
         transformed_full_res
@@ -443,28 +572,29 @@ async fn load_image(
     path: String,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
-) -> Result<LoadImageResult, String> {
+) -> Result<LoadImageResult, AppError> {
     let (source_path, sidecar_path) = parse_virtual_path(&path);
     let source_path_str = source_path.to_string_lossy().to_string();
 
     let metadata: ImageMetadata = if sidecar_path.exists() {
-        let file_content = fs::read_to_string(sidecar_path).map_err(|e| e.to_string())?;
+        let file_content = fs::read_to_string(sidecar_path)?;
         serde_json::from_str(&file_content).unwrap_or_default()
     } else {
         ImageMetadata::default()
     };
 
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
+    let raw_params = settings.raw_develop_params_for(&source_path);
 
     let path_clone = source_path_str.clone();
-    let (pristine_img, exif_data) = tokio::task::spawn_blocking(move || {
-        let result: Result<(DynamicImage, HashMap<String, String>), String> = (|| {
+    let decode_start = std::time::Instant::now();
+    let (decoded, exif_data) = tokio::task::spawn_blocking(move || {
+        let result: Result<(DecodedImage, HashMap<String, String>), AppError> = (|| {
             match read_file_mapped(Path::new(&path_clone)) {
                 Ok(mmap) => {
                     let img =
-                        load_base_image_from_bytes(&mmap, &path_clone, false, highlight_compression)
-                            .map_err(|e| e.to_string())?;
+                        decode_base_image_from_bytes(&mmap, &path_clone, false, raw_params)
+                            .map_err(|e| AppError::Decode(e.to_string()))?;
                     let exif = read_exif_data(&mmap);
                     Ok((img, exif))
                 }
@@ -474,16 +604,14 @@ async fn load_image(
                         path_clone,
                         e
                     );
-                    let bytes = fs::read(&path_clone).map_err(|io_err| {
-                        format!("Fallback read failed for {}: {}", path_clone, io_err)
-                    })?;
-                    let img = load_base_image_from_bytes(
+                    let bytes = fs::read(&path_clone)?;
+                    let img = decode_base_image_from_bytes(
                         &bytes,
                         &path_clone,
                         false,
-                        highlight_compression,
+                        raw_params,
                     )
-                    .map_err(|e| e.to_string())?;
+                    .map_err(|e| AppError::Decode(e.to_string()))?;
                     let exif = read_exif_data(&bytes);
                     Ok((img, exif))
                 }
@@ -492,20 +620,44 @@ async fn load_image(
         result
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| AppError::Other(e.to_string()))??;
 
+    let DecodedImage {
+        image: pristine_img,
+        is_embedded_preview,
+        color_profile: source_color_profile,
+    } = decoded;
     let (orig_width, orig_height) = pristine_img.dimensions();
     let is_raw = is_raw_file(&source_path_str);
+    let develop_hash = calculate_develop_hash(
+        is_raw && !is_embedded_preview,
+        &raw_params,
+        source_color_profile.as_deref(),
+    );
+
+    emit_perf_metric(
+        &app_handle,
+        PerfMetric {
+            context: "load_image".to_string(),
+            width: orig_width,
+            height: orig_height,
+            decode_ms: Some(duration_ms(decode_start.elapsed())),
+            ..Default::default()
+        },
+    );
 
     *state.cached_preview.lock().unwrap() = None;
     *state.gpu_image_cache.lock().unwrap() = None;
+    *state.last_processed_preview.lock().unwrap() = None;
     state.mask_cache.lock().unwrap().clear();
     state.patch_cache.lock().unwrap().clear();
 
     *state.original_image.lock().unwrap() = Some(LoadedImage {
         path: source_path_str.clone(),
-        image: pristine_img,
+        image: Arc::new(pristine_img),
         is_raw,
+        is_embedded_preview,
+        develop_hash,
     });
 
     Ok(LoadImageResult {
@@ -514,6 +666,8 @@ async fn load_image(
         metadata,
         exif: exif_data,
         is_raw,
+        is_embedded_preview,
+        source_color_profile,
     })
 }
 
@@ -525,6 +679,32 @@ fn get_image_dimensions(path: String) -> Result<ImageDimensions, String> {
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn get_display_info(
+    window: tauri::Window,
+    state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<DisplayInfo, String> {
+    let (physical_width, physical_height, scale_factor) =
+        match window.current_monitor().map_err(|e| e.to_string())? {
+            Some(monitor) => (monitor.size().width, monitor.size().height, monitor.scale_factor()),
+            None => {
+                let size = window.inner_size().map_err(|e| e.to_string())?;
+                let scale = window.scale_factor().map_err(|e| e.to_string())?;
+                (size.width, size.height, scale)
+            }
+        };
+
+    *state.display_preview_dim.lock().unwrap() = Some(physical_width.max(physical_height));
+
+    Ok(DisplayInfo {
+        physical_width,
+        physical_height,
+        scale_factor,
+        preview_dimension: get_editor_preview_dim(&app_handle),
+    })
+}
+
 #[tauri::command]
 fn cancel_thumbnail_generation(state: tauri::State<AppState>) -> Result<(), String> {
     state
@@ -533,6 +713,16 @@ fn cancel_thumbnail_generation(state: tauri::State<AppState>) -> Result<(), Stri
     Ok(())
 }
 
+#[tauri::command]
+fn cancel_thumbnail_pregeneration(state: tauri::State<AppState>) -> Result<(), String> {
+    state
+        .thumbnail_pregeneration_cancellation_token
+        .lock()
+        .unwrap()
+        .store(true, Ordering::SeqCst);
+    Ok(())
+}
+
 fn apply_watermark(
     base_image: &mut DynamicImage,
     watermark_settings: &WatermarkSettings,
@@ -601,11 +791,23 @@ pub fn get_cached_or_generate_mask(
     height: u32,
     scale: f32,
     crop_offset: (f32, f32),
+    range_source: RangeSource,
 ) -> Option<GrayImage> {
     let mut hasher = DefaultHasher::new();
     
     let def_json = serde_json::to_string(&def).unwrap_or_default();
     def_json.hash(&mut hasher);
+
+    // Range masks depend on the pixels too, and the cache is shared between images
+    if def.range_mask.is_some() {
+        range_source.is_linear.hash(&mut hasher);
+        let bytes = range_source.image.as_bytes();
+        let stride = (bytes.len() / 4096).max(1);
+        bytes
+            .iter()
+            .step_by(stride)
+            .for_each(|b| b.hash(&mut hasher));
+    }
     
     width.hash(&mut hasher);
     height.hash(&mut hasher);
@@ -622,7 +824,8 @@ pub fn get_cached_or_generate_mask(
         }
     }
 
-    let generated = generate_mask_bitmap(def, width, height, scale, crop_offset);
+    let generated =
+        generate_mask_bitmap(def, width, height, scale, crop_offset, Some(range_source));
 
     if let Some(img) = &generated {
         let mut cache = state.mask_cache.lock().unwrap();
@@ -650,6 +853,8 @@ fn process_preview_job(
     drop(loaded_image_guard);
 
     let new_transform_hash = calculate_transform_hash(&adjustments_clone);
+    let final_preview_dim = get_editor_preview_dim(app_handle);
+    let downscale_quality = get_preview_downscale_quality(app_handle);
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
     let hq_live = settings.enable_high_quality_live_previews.unwrap_or(false);
     let interactive_divisor = if hq_live { 1.5 } else { 2.0 };
@@ -659,7 +864,9 @@ fn process_preview_job(
 
     let (final_preview_base, small_preview_base, scale_for_gpu, unscaled_crop_offset) =
         if let Some(cached) = &*cached_preview_lock {
-            if cached.transform_hash == new_transform_hash {
+            if cached.transform_hash == new_transform_hash
+                && cached.preview_dim == final_preview_dim
+            {
                 (
                     cached.image.clone(),
                     cached.small_image.clone(),
@@ -668,10 +875,15 @@ fn process_preview_job(
                 )
             } else {
                 *state.gpu_image_cache.lock().unwrap() = None;
-                let (base, scale, offset) =
-                    generate_transformed_preview(&loaded_image, &adjustments_clone, &app_handle)?;
+                let (base, scale, offset) = generate_transformed_preview(
+                    &state,
+                    app_handle,
+                    &loaded_image,
+                    &adjustments_clone,
+                    final_preview_dim,
+                    downscale_quality,
+                )?;
 
-                let final_preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
                 let target_size = (final_preview_dim as f32 / interactive_divisor) as u32;
 
                 let (w, h) = base.dimensions();
@@ -688,6 +900,7 @@ fn process_preview_job(
                     image: base.clone(),
                     small_image: small_base.clone(),
                     transform_hash: new_transform_hash,
+                    preview_dim: final_preview_dim,
                     scale,
                     unscaled_crop_offset: offset,
                 });
@@ -695,10 +908,15 @@ fn process_preview_job(
             }
         } else {
             *state.gpu_image_cache.lock().unwrap() = None;
-            let (base, scale, offset) =
-                generate_transformed_preview(&loaded_image, &adjustments_clone, &app_handle)?;
+            let (base, scale, offset) = generate_transformed_preview(
+                &state,
+                app_handle,
+                &loaded_image,
+                &adjustments_clone,
+                final_preview_dim,
+                downscale_quality,
+            )?;
 
-            let final_preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
             let target_size = (final_preview_dim as f32 / interactive_divisor) as u32;
 
             let (w, h) = base.dimensions();
@@ -715,6 +933,7 @@ fn process_preview_job(
                 image: base.clone(),
                 small_image: small_base.clone(),
                 transform_hash: new_transform_hash,
+                preview_dim: final_preview_dim,
                 scale,
                 unscaled_crop_offset: offset,
             });
@@ -734,6 +953,7 @@ fn process_preview_job(
     };
 
     let (preview_width, preview_height) = processing_image.dimensions();
+    let is_raw = loaded_image.develops_as_raw();
 
     let mask_definitions: Vec<MaskDefinition> = adjustments_clone
         .get("masks")
@@ -755,18 +975,24 @@ fn process_preview_job(
                 preview_height,
                 effective_scale,
                 scaled_crop_offset,
+                RangeSource {
+                    image: &processing_image,
+                    is_linear: is_raw,
+                },
             )
         })
         .collect();
 
-    let is_raw = loaded_image.is_raw;
-    let final_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    let mut final_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    final_adjustments.global.grain_scale = effective_scale;
+    final_adjustments.global.grain_seed = grain_seed(&loaded_image.path, &adjustments_clone);
     let lut_path = adjustments_clone["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
 
     let final_processed_image_result = process_and_get_dynamic_image(
         &context,
         &state,
+        app_handle,
         &processing_image,
         new_transform_hash,
         final_adjustments,
@@ -776,6 +1002,8 @@ fn process_preview_job(
     );
 
     if let Ok(final_processed_image) = final_processed_image_result {
+        let processed_rgb = final_processed_image.to_rgb8();
+
         if !job.is_interactive {
             if let Ok(histogram_data) =
                 image_processing::calculate_histogram_from_image(&final_processed_image)
@@ -790,11 +1018,16 @@ fn process_preview_job(
         }
 
         let mut buf = Cursor::new(Vec::new());
-        if final_processed_image
-            .to_rgb8()
+        let encoded = processed_rgb
             .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, jpeg_quality))
-            .is_ok()
-        {
+            .is_ok();
+
+        // Stored before the event so listeners that query it see this preview
+        if !job.is_interactive {
+            *state.last_processed_preview.lock().unwrap() = Some(processed_rgb);
+        }
+
+        if encoded {
             let _ = app_handle.emit("preview-update-final", buf.get_ref());
         }
     }
@@ -859,28 +1092,33 @@ fn generate_uncropped_preview(
     thread::spawn(move || {
         let state = app_handle.state::<AppState>();
         let path = loaded_image.path.clone();
-        let is_raw = loaded_image.is_raw;
+        let is_raw = loaded_image.develops_as_raw();
         let unique_hash = calculate_full_job_hash(&path, &adjustments_clone);
         let patched_image =
             match composite_patches_on_image(&loaded_image.image, &adjustments_clone) {
                 Ok(img) => img,
                 Err(e) => {
                     eprintln!("Failed to composite patches for uncropped preview: {}", e);
-                    loaded_image.image
+                    loaded_image.image.as_ref().clone()
                 }
             };
 
         let orientation_steps = adjustments_clone["orientationSteps"].as_u64().unwrap_or(0) as u8;
         let coarse_rotated_image = apply_coarse_rotation(patched_image, orientation_steps);
 
-        let settings = load_settings(app_handle.clone()).unwrap_or_default();
-        let preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
+        let preview_dim = get_editor_preview_dim(&app_handle);
+        let downscale_quality = get_preview_downscale_quality(&app_handle);
 
         let (rotated_w, rotated_h) = coarse_rotated_image.dimensions();
 
         let (processing_base, scale_for_gpu) = if rotated_w > preview_dim || rotated_h > preview_dim
         {
-            let base = downscale_f32_image(&coarse_rotated_image, preview_dim, preview_dim);
+            let base = downscale_f32_image_with_quality(
+                &coarse_rotated_image,
+                preview_dim,
+                preview_dim,
+                downscale_quality,
+            );
             let scale = if rotated_w > 0 {
                 base.width() as f32 / rotated_w as f32
             } else {
@@ -908,17 +1146,24 @@ fn generate_uncropped_preview(
                     preview_height,
                     scale_for_gpu,
                     (0.0, 0.0),
+                    RangeSource {
+                        image: &processing_base,
+                        is_linear: is_raw,
+                    },
                 )
             })
             .collect();
 
-        let uncropped_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+        let mut uncropped_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+        uncropped_adjustments.global.grain_scale = scale_for_gpu;
+        uncropped_adjustments.global.grain_seed = grain_seed(&path, &adjustments_clone);
         let lut_path = adjustments_clone["lutPath"].as_str();
         let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
 
         if let Ok(processed_image) = process_and_get_dynamic_image(
             &context,
             &state,
+            &app_handle,
             &processing_base,
             unique_hash,
             uncropped_adjustments,
@@ -956,20 +1201,30 @@ fn generate_original_transformed_preview(
     let mut adjustments_clone = js_adjustments.clone();
     hydrate_adjustments(&state, &mut adjustments_clone);
 
-    let mut image_for_preview = loaded_image.image.clone();
-    if loaded_image.is_raw {
-        apply_cpu_default_raw_processing(&mut image_for_preview);
-    }
+    let raw_processed_image;
+    let image_for_preview: &DynamicImage = if loaded_image.develops_as_raw() {
+        let mut image = loaded_image.image.as_ref().clone();
+        apply_cpu_default_raw_processing(&mut image);
+        raw_processed_image = image;
+        &raw_processed_image
+    } else {
+        &loaded_image.image
+    };
 
     let (transformed_full_res, _unscaled_crop_offset) =
-        apply_all_transformations(&image_for_preview, &adjustments_clone);
+        apply_all_transformations(image_for_preview, &adjustments_clone);
 
-    let settings = load_settings(app_handle).unwrap_or_default();
-    let preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
+    let preview_dim = get_editor_preview_dim(&app_handle);
+    let downscale_quality = get_preview_downscale_quality(&app_handle);
 
     let (w, h) = transformed_full_res.dimensions();
     let transformed_image = if w > preview_dim || h > preview_dim {
-        downscale_f32_image(&transformed_full_res, preview_dim, preview_dim)
+        downscale_f32_image_with_quality(
+            &transformed_full_res,
+            preview_dim,
+            preview_dim,
+            downscale_quality,
+        )
     } else {
         transformed_full_res
     };
@@ -985,73 +1240,308 @@ fn generate_original_transformed_preview(
 
 fn get_full_image_for_processing(
     state: &tauri::State<AppState>,
-) -> Result<(DynamicImage, bool), String> {
+) -> Result<(Arc<DynamicImage>, bool), String> {
     let original_image_lock = state.original_image.lock().unwrap();
     let loaded_image = original_image_lock
         .as_ref()
         .ok_or("No original image loaded")?;
-    Ok((loaded_image.image.clone(), loaded_image.is_raw))
+    Ok((loaded_image.image.clone(), loaded_image.develops_as_raw()))
+}
+
+fn render_fullscreen_preview(
+    js_adjustments: serde_json::Value,
+    state: &tauri::State<AppState>,
+    app_handle: &tauri::AppHandle,
+    cancel_token: &AtomicBool,
+) -> Result<Vec<u8>, AppError> {
+    let mut adjustments_clone = js_adjustments;
+    hydrate_adjustments(state, &mut adjustments_clone);
+
+    let (path, develop_hash) = state
+        .original_image
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|loaded_image| (loaded_image.path.clone(), loaded_image.develop_hash))
+        .ok_or(AppError::NoImageLoaded)?;
+    let unique_hash = calculate_full_job_hash(&path, &adjustments_clone);
+    let disk_cache_key = {
+        let mut hasher = DefaultHasher::new();
+        unique_hash.hash(&mut hasher);
+        develop_hash.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    if let Some(cached_bytes) =
+        file_management::read_cached_preview(app_handle, Path::new(&path), disk_cache_key)
+    {
+        return Ok(cached_bytes);
+    }
+
+    let context = get_or_init_gpu_context(state).map_err(AppError::Gpu)?;
+    let (original_image, is_raw) =
+        get_full_image_for_processing(state).map_err(|_| AppError::NoImageLoaded)?;
+    let base_image = composite_patches_on_image(&original_image, &adjustments_clone).map_err(|e| {
+        AppError::Other(format!("Failed to composite AI patches for fullscreen: {}", e))
+    })?;
+
+    let (transformed_image, unscaled_crop_offset) =
+        apply_all_transformations(&base_image, &adjustments_clone);
+    let (img_w, img_h) = transformed_image.dimensions();
+
+    let mask_definitions: Vec<MaskDefinition> = adjustments_clone
+        .get("masks")
+        .and_then(|m| serde_json::from_value(m.clone()).ok())
+        .unwrap_or_else(Vec::new);
+
+    let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
+        .iter()
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                img_w,
+                img_h,
+                1.0,
+                unscaled_crop_offset,
+                Some(RangeSource {
+                    image: &transformed_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
+        .collect();
+
+    let mut all_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    all_adjustments.global.grain_seed = grain_seed(&path, &adjustments_clone);
+    let lut_path = adjustments_clone["lutPath"].as_str();
+    let lut = lut_path.and_then(|p| get_or_load_lut(state, p).ok());
+
+    if cancel_token.load(Ordering::SeqCst) {
+        return Err(AppError::Cancelled);
+    }
+
+    let final_image = process_and_get_dynamic_image(
+        &context,
+        state,
+        app_handle,
+        &transformed_image,
+        unique_hash,
+        all_adjustments,
+        &mask_bitmaps,
+        lut,
+        "generate_fullscreen_preview",
+    )
+    .map_err(AppError::Gpu)?;
+
+    if cancel_token.load(Ordering::SeqCst) {
+        return Err(AppError::Cancelled);
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    final_image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, 92))?;
+
+    let jpeg_bytes = buf.into_inner();
+    file_management::write_cached_preview(app_handle, disk_cache_key, &jpeg_bytes);
+
+    Ok(jpeg_bytes)
+}
+
+/// Cancels the render holding `token_slot` and installs a fresh token for a new one.
+fn replace_cancellation_token(token_slot: &Mutex<Arc<AtomicBool>>) -> Arc<AtomicBool> {
+    let cancel_token = Arc::new(AtomicBool::new(false));
+    let mut current_token = token_slot.lock().unwrap();
+    current_token.store(true, Ordering::SeqCst);
+    *current_token = cancel_token.clone();
+    cancel_token
+}
+
+async fn spawn_fullscreen_render(
+    js_adjustments: serde_json::Value,
+    app_handle: tauri::AppHandle,
+    cancel_token: Arc<AtomicBool>,
+) -> Result<Response, AppError> {
+    let jpeg_bytes = tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        render_fullscreen_preview(js_adjustments, &state, &app_handle, &cancel_token)
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))??;
+
+    Ok(Response::new(jpeg_bytes))
 }
 
 #[tauri::command]
-fn generate_fullscreen_preview(
+async fn generate_fullscreen_preview(
     js_adjustments: serde_json::Value,
-    state: tauri::State<AppState>,
-) -> Result<Response, String> {
-    let context = get_or_init_gpu_context(&state)?;
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Response, AppError> {
+    let cancel_token = replace_cancellation_token(&state.fullscreen_cancellation_token);
+    spawn_fullscreen_render(js_adjustments, app_handle, cancel_token).await
+}
 
-    let mut adjustments_clone = js_adjustments.clone();
-    hydrate_adjustments(&state, &mut adjustments_clone);
+/// Same render as the fullscreen view, for zooming in the editor. It has its own token
+/// so a zoom request and the fullscreen view never cancel each other.
+#[tauri::command]
+async fn generate_full_resolution_preview(
+    js_adjustments: serde_json::Value,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Response, AppError> {
+    let cancel_token = replace_cancellation_token(&state.full_resolution_cancellation_token);
+    spawn_fullscreen_render(js_adjustments, app_handle, cancel_token).await
+}
+
+const REGION_PREVIEW_MARGIN: u32 = 64;
+
+fn render_region_preview(
+    js_adjustments: serde_json::Value,
+    region: Crop,
+    output_width: u32,
+    output_height: u32,
+    state: &tauri::State<AppState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<u8>, AppError> {
+    if output_width == 0 || output_height == 0 {
+        return Err(AppError::Other("Output size must be non-zero".to_string()));
+    }
+
+    let mut adjustments_clone = js_adjustments;
+    hydrate_adjustments(state, &mut adjustments_clone);
 
-    let (original_image, is_raw) = get_full_image_for_processing(&state)?;
     let path = state
         .original_image
         .lock()
         .unwrap()
         .as_ref()
-        .ok_or("Original image path not found")?
+        .ok_or(AppError::NoImageLoaded)?
         .path
         .clone();
-    let unique_hash = calculate_full_job_hash(&path, &adjustments_clone);
-    let base_image = composite_patches_on_image(&original_image, &adjustments_clone)
-        .map_err(|e| format!("Failed to composite AI patches for fullscreen: {}", e))?;
+
+    let context = get_or_init_gpu_context(state).map_err(AppError::Gpu)?;
+    let (original_image, is_raw) =
+        get_full_image_for_processing(state).map_err(|_| AppError::NoImageLoaded)?;
+    let base_image = composite_patches_on_image(&original_image, &adjustments_clone).map_err(|e| {
+        AppError::Other(format!("Failed to composite AI patches for region preview: {}", e))
+    })?;
 
     let (transformed_image, unscaled_crop_offset) =
         apply_all_transformations(&base_image, &adjustments_clone);
     let (img_w, img_h) = transformed_image.dimensions();
 
+    let x0 = (region.x.max(0.0).floor() as u32).min(img_w.saturating_sub(1));
+    let y0 = (region.y.max(0.0).floor() as u32).min(img_h.saturating_sub(1));
+    let x1 = ((region.x + region.width).ceil().max(0.0) as u32).clamp(x0 + 1, img_w);
+    let y1 = ((region.y + region.height).ceil().max(0.0) as u32).clamp(y0 + 1, img_h);
+
+    // Render a margin around the region so blur-based effects near its edges
+    // see the same neighbourhood they would in a full render.
+    let pad_x0 = x0.saturating_sub(REGION_PREVIEW_MARGIN);
+    let pad_y0 = y0.saturating_sub(REGION_PREVIEW_MARGIN);
+    let pad_x1 = (x1 + REGION_PREVIEW_MARGIN).min(img_w);
+    let pad_y1 = (y1 + REGION_PREVIEW_MARGIN).min(img_h);
+    let (pad_w, pad_h) = (pad_x1 - pad_x0, pad_y1 - pad_y0);
+
+    let region_image = transformed_image.crop_imm(pad_x0, pad_y0, pad_w, pad_h);
+
     let mask_definitions: Vec<MaskDefinition> = adjustments_clone
         .get("masks")
         .and_then(|m| serde_json::from_value(m.clone()).ok())
         .unwrap_or_else(Vec::new);
 
+    let region_crop_offset = (
+        unscaled_crop_offset.0 + pad_x0 as f32,
+        unscaled_crop_offset.1 + pad_y0 as f32,
+    );
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
-        .filter_map(|def| generate_mask_bitmap(def, img_w, img_h, 1.0, unscaled_crop_offset))
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                pad_w,
+                pad_h,
+                1.0,
+                region_crop_offset,
+                Some(RangeSource {
+                    image: &region_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
         .collect();
 
-    let all_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    let mut all_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    all_adjustments.region_offset_x = pad_x0;
+    all_adjustments.region_offset_y = pad_y0;
+    all_adjustments.full_width = img_w;
+    all_adjustments.full_height = img_h;
+    all_adjustments.global.grain_seed = grain_seed(&path, &adjustments_clone);
+
     let lut_path = adjustments_clone["lutPath"].as_str();
-    let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
+    let lut = lut_path.and_then(|p| get_or_load_lut(state, p).ok());
 
-    let final_image = process_and_get_dynamic_image(
+    let mut hasher = DefaultHasher::new();
+    calculate_full_job_hash(&path, &adjustments_clone).hash(&mut hasher);
+    (pad_x0, pad_y0, pad_w, pad_h).hash(&mut hasher);
+    let unique_hash = hasher.finish();
+
+    let processed = process_and_get_dynamic_image(
         &context,
-        &state,
-        &transformed_image,
+        state,
+        app_handle,
+        &region_image,
         unique_hash,
         all_adjustments,
         &mask_bitmaps,
         lut,
-        "generate_fullscreen_preview",
-    )?;
+        "generate_region_preview",
+    )
+    .map_err(AppError::Gpu)?;
+
+    let region_result = processed.crop_imm(x0 - pad_x0, y0 - pad_y0, x1 - x0, y1 - y0);
+    let final_image = if region_result.dimensions() == (output_width, output_height) {
+        region_result
+    } else if output_width < x1 - x0 || output_height < y1 - y0 {
+        region_result.resize_exact(output_width, output_height, imageops::FilterType::Lanczos3)
+    } else {
+        region_result.resize_exact(output_width, output_height, imageops::FilterType::Nearest)
+    };
 
     let mut buf = Cursor::new(Vec::new());
     final_image
         .to_rgb8()
-        .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, 92))
-        .map_err(|e| e.to_string())?;
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, 92))?;
 
-    Ok(Response::new(buf.into_inner()))
+    Ok(buf.into_inner())
+}
+
+/// Renders a rectangle of the edited image, given in full-resolution transformed
+/// image coordinates, at the requested output size. Used for pan/zoom tiles.
+#[tauri::command]
+async fn generate_region_preview(
+    js_adjustments: serde_json::Value,
+    region: Crop,
+    output_width: u32,
+    output_height: u32,
+    app_handle: tauri::AppHandle,
+) -> Result<Response, AppError> {
+    let jpeg_bytes = tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        render_region_preview(
+            js_adjustments,
+            region,
+            output_width,
+            output_height,
+            &state,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))??;
+
+    Ok(Response::new(jpeg_bytes))
 }
 
 fn process_image_for_export(
@@ -1061,6 +1551,7 @@ fn process_image_for_export(
     export_settings: &ExportSettings,
     context: &GpuContext,
     state: &tauri::State<AppState>,
+    app_handle: &tauri::AppHandle,
     is_raw: bool,
 ) -> Result<DynamicImage, String> {
     let (transformed_image, unscaled_crop_offset) =
@@ -1074,11 +1565,25 @@ fn process_image_for_export(
 
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
-        .filter_map(|def| generate_mask_bitmap(def, img_w, img_h, 1.0, unscaled_crop_offset))
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                img_w,
+                img_h,
+                1.0,
+                unscaled_crop_offset,
+                Some(RangeSource {
+                    image: &transformed_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
         .collect();
 
     let mut all_adjustments = get_all_adjustments_from_json(&js_adjustments, is_raw);
     all_adjustments.global.show_clipping = 0;
+    all_adjustments.global.grain_seed = grain_seed(path, js_adjustments);
+    all_adjustments.global.output_linear = export_settings.scene_linear as u32;
 
     let lut_path = js_adjustments["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
@@ -1088,6 +1593,7 @@ fn process_image_for_export(
     let mut final_image = process_and_get_dynamic_image(
         &context,
         &state,
+        app_handle,
         &transformed_image,
         unique_hash,
         all_adjustments,
@@ -1153,6 +1659,12 @@ fn process_image_for_export(
         }
     }
 
+    if export_settings.scene_linear {
+        // Linear Rec.709 values straight from the pipeline; a watermark is display
+        // content and is left to the compositing application.
+        return Ok(DynamicImage::ImageRgb32F(final_image.to_rgb32f()));
+    }
+
     if let Some(watermark_settings) = &export_settings.watermark {
         apply_watermark(&mut final_image, watermark_settings)?;
     }
@@ -1160,6 +1672,107 @@ fn process_image_for_export(
     Ok(final_image)
 }
 
+/// Scene-linear exports hold unclamped float data, which only EXR and TIFF can store.
+/// EXR is scene-linear by definition, so it always takes that path.
+fn resolve_scene_linear_format(
+    export_settings: &mut ExportSettings,
+    output_format: &str,
+) -> Result<(), String> {
+    match output_format.to_lowercase().as_str() {
+        "exr" => export_settings.scene_linear = true,
+        "tiff" => {}
+        _ if export_settings.scene_linear => {
+            return Err(format!(
+                "Scene-linear export requires EXR or TIFF output, not {}",
+                output_format
+            ));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Writes `image` as scene-linear EXR. Float images come from the scene-linear path and
+/// are stored as they are; integer images are display-referred and decoded from sRGB.
+fn encode_exr(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    use exr::prelude::WritableImage;
+
+    let mut rgb = image.to_rgb32f();
+    if !matches!(
+        image,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    ) {
+        rgb.par_iter_mut()
+            .for_each(|v| *v = crate::color_profile::srgb_to_linear(*v));
+    }
+    let (width, height) = rgb.dimensions();
+    let exr_image = exr::prelude::Image::from_channels(
+        (width as usize, height as usize),
+        exr::prelude::SpecificChannels::rgb(|pos: exr::prelude::Vec2<usize>| {
+            let pixel = rgb.get_pixel(pos.x() as u32, pos.y() as u32);
+            (pixel[0], pixel[1], pixel[2])
+        }),
+    );
+
+    let mut cursor = Cursor::new(Vec::new());
+    exr_image
+        .write()
+        .to_buffered(&mut cursor)
+        .map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
+
+/// Encodes `image` and writes it to `output_path`. TIFF output is streamed straight into
+/// the file since no metadata is injected for it; other formats are encoded in memory so
+/// their metadata can be written post-encode. Returns non-fatal warnings about the output,
+/// e.g. metadata that couldn't be written.
+fn write_image_to_file(
+    image: &DynamicImage,
+    output_path: &Path,
+    output_format: &str,
+    jpeg_quality: u8,
+    metadata_source_path: &str,
+    keep_metadata: bool,
+    strip_gps: bool,
+) -> Result<Vec<String>, String> {
+    if output_format.to_lowercase() == "tiff" {
+        let file = fs::File::create(output_path).map_err(|e| e.to_string())?;
+        let mut writer = std::io::BufWriter::new(file);
+        image
+            .write_to(&mut writer, image::ImageFormat::Tiff)
+            .map_err(|e| e.to_string())?;
+        writer.flush().map_err(|e| e.to_string())?;
+        return Ok(Vec::new());
+    }
+
+    let mut image_bytes = encode_image_to_bytes(image, output_format, jpeg_quality)?;
+    let warnings = write_image_with_metadata(
+        &mut image_bytes,
+        metadata_source_path,
+        output_format,
+        keep_metadata,
+        strip_gps,
+    )?;
+    fs::write(output_path, image_bytes).map_err(|e| e.to_string())?;
+    Ok(warnings)
+}
+
+const EMBEDDED_PREVIEW_EXPORT_WARNING: &str =
+    "The RAW data could not be decoded; exported from the camera's embedded preview instead.";
+
+fn emit_export_warnings(app_handle: &tauri::AppHandle, path: &str, warnings: &[String]) {
+    for warning in warnings {
+        log::warn!("Export warning for {}: {}", path, warning);
+        let _ = app_handle.emit(
+            "export-warning",
+            serde_json::json!({ "path": path, "message": warning }),
+        );
+    }
+}
+
+/// Formats `encode_image_to_bytes` can write, by the ids the export panels use.
+const EXPORT_FORMATS: &[&str] = &["jpeg", "png", "tiff", "exr"];
+
 fn encode_image_to_bytes(
     image: &DynamicImage,
     output_format: &str,
@@ -1192,6 +1805,7 @@ fn encode_image_to_bytes(
                 .write_to(&mut cursor, image::ImageFormat::Tiff)
                 .map_err(|e| e.to_string())?;
         }
+        "exr" => return encode_exr(image),
         _ => return Err(format!("Unsupported file format: {}", output_format)),
     };
     Ok(image_bytes)
@@ -1202,16 +1816,28 @@ async fn export_image(
     original_path: String,
     output_path: String,
     js_adjustments: Value,
-    export_settings: ExportSettings,
+    mut export_settings: ExportSettings,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     if state.export_task_handle.lock().unwrap().is_some() {
-        return Err("An export is already in progress.".to_string());
+        return Err(AppError::Busy("An export is already in progress.".to_string()));
     }
+    let output_extension = Path::new(&output_path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    resolve_scene_linear_format(&mut export_settings, output_extension).map_err(AppError::Other)?;
 
-    let context = get_or_init_gpu_context(&state)?;
-    let (original_image_data, is_raw) = get_full_image_for_processing(&state)?;
+    let context = get_or_init_gpu_context(&state).map_err(AppError::Gpu)?;
+    let (original_image_data, is_raw) =
+        get_full_image_for_processing(&state).map_err(|_| AppError::NoImageLoaded)?;
+    let is_embedded_preview = state
+        .original_image
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(false, |loaded_image| loaded_image.is_embedded_preview);
     let context = Arc::new(context);
 
     let task = tokio::spawn(async move {
@@ -1230,6 +1856,7 @@ async fn export_image(
                 &export_settings,
                 &context,
                 &state,
+                &app_handle,
                 is_raw,
             )?;
 
@@ -1240,18 +1867,19 @@ async fn export_image(
                 .unwrap_or("")
                 .to_lowercase();
 
-            let mut image_bytes =
-                encode_image_to_bytes(&final_image, &extension, export_settings.jpeg_quality)?;
-
-            write_image_with_metadata(
-                &mut image_bytes,
-                &source_path_str,
+            let mut warnings = write_image_to_file(
+                &final_image,
+                output_path_obj,
                 &extension,
+                export_settings.jpeg_quality,
+                &source_path_str,
                 export_settings.keep_metadata,
                 export_settings.strip_gps,
             )?;
-
-            fs::write(&output_path, image_bytes).map_err(|e| e.to_string())?;
+            if is_embedded_preview {
+                warnings.insert(0, EMBEDDED_PREVIEW_EXPORT_WARNING.to_string());
+            }
+            emit_export_warnings(&app_handle, &original_path, &warnings);
 
             Ok(())
         })();
@@ -1273,26 +1901,249 @@ async fn export_image(
     Ok(())
 }
 
+fn merge_adjustment_overrides(adjustments: &mut Value, overrides: &Value) {
+    if adjustments.is_null() {
+        *adjustments = serde_json::json!({});
+    }
+    if let (Some(target_map), Some(override_map)) =
+        (adjustments.as_object_mut(), overrides.as_object())
+    {
+        for (k, v) in override_map {
+            target_map.insert(k.clone(), v.clone());
+        }
+    }
+}
+
+/// Exports every image under `root_path` matching `filter_criteria` (plus any explicit
+/// `paths`), so "export my picks" doesn't need the frontend to assemble the list.
+#[tauri::command]
+async fn batch_export_filtered_images(
+    output_folder: String,
+    root_path: String,
+    filter_criteria: file_management::FilterCriteria,
+    paths: Option<Vec<String>>,
+    export_settings: ExportSettings,
+    output_format: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize, String> {
+    let handle = app_handle.clone();
+    let resolved = tauri::async_runtime::spawn_blocking(move || {
+        file_management::resolve_filtered_image_paths(root_path, &filter_criteria, handle)
+    })
+    .await
+    .map_err(|e| format!("Failed to resolve images for export: {}", e))??;
+
+    let mut seen = HashSet::new();
+    let export_paths: Vec<String> = paths
+        .unwrap_or_default()
+        .into_iter()
+        .chain(resolved)
+        .filter(|path| seen.insert(path.clone()))
+        .collect();
+    let count = export_paths.len();
+
+    let _ = app_handle.emit("batch-export-resolved", serde_json::json!({ "count": count }));
+    if count == 0 {
+        return Ok(0);
+    }
+
+    batch_export_images(
+        output_folder,
+        export_paths,
+        export_settings,
+        output_format,
+        None,
+        None,
+        state,
+        app_handle,
+    )
+    .await?;
+    Ok(count)
+}
+
+/// Worker threads for batch export. `exportThreadCount` of 0 (or unset) means auto,
+/// which keeps half the cores free for the UI and caps at 4; explicit values are
+/// clamped to the available parallelism.
+fn export_thread_count(app_handle: &tauri::AppHandle, available_cores: usize) -> usize {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    match settings.export_thread_count {
+        Some(count) if count > 0 => (count as usize).clamp(1, available_cores),
+        _ => (available_cores / 2).clamp(1, 4),
+    }
+}
+
+/// Capture date used by filename templates: EXIF DateTimeOriginal when present,
+/// otherwise the file's creation time.
+fn export_file_date(original_path: &Path) -> DateTime<Utc> {
+    let mut date = None;
+    if let Ok(file) = std::fs::File::open(original_path) {
+        let mut bufreader = std::io::BufReader::new(&file);
+        let exifreader = exif::Reader::new();
+        if let Ok(exif_obj) = exifreader.read_from_container(&mut bufreader) {
+            if let Some(field) = exif_obj.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY) {
+                let s = field.display_value().to_string().replace("\"", "");
+                if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s.trim(), "%Y:%m:%d %H:%M:%S") {
+                    date = Some(DateTime::from_naive_utc_and_offset(dt, Utc));
+                }
+            }
+        }
+    }
+
+    date.unwrap_or_else(|| {
+        fs::metadata(original_path)
+            .ok()
+            .and_then(|m| m.created().ok())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(Utc::now)
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportValidationEntry {
+    path: String,
+    output_path: Option<String>,
+    ok: bool,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// Dry run of `batch_export_images`: reports, per path, the problems that would make
+/// the export fail or overwrite files, without decoding or processing any pixels.
+#[tauri::command]
+async fn validate_batch_export(
+    output_folder: String,
+    paths: Vec<String>,
+    mut export_settings: ExportSettings,
+    output_format: String,
+) -> Result<Vec<ExportValidationEntry>, String> {
+    tokio::task::spawn_blocking(move || {
+        let output_folder_path = Path::new(&output_folder);
+        let mut shared_errors = Vec::new();
+
+        if !matches!(
+            output_format.to_lowercase().as_str(),
+            "jpg" | "jpeg" | "png" | "tiff" | "exr"
+        ) {
+            shared_errors.push(format!("Unsupported output format: {}", output_format));
+        } else if let Err(e) = resolve_scene_linear_format(&mut export_settings, &output_format) {
+            shared_errors.push(e);
+        }
+
+        if !output_folder_path.is_dir() {
+            shared_errors.push(format!(
+                "Destination folder does not exist: {}",
+                output_folder
+            ));
+        } else if let Err(e) = NamedTempFile::new_in(output_folder_path) {
+            shared_errors.push(format!("Destination folder is not writable: {}", e));
+        }
+
+        let filename_template = export_settings
+            .filename_template
+            .as_deref()
+            .unwrap_or("{original_filename}_edited");
+        let total_paths = paths.len();
+
+        let mut entries: Vec<ExportValidationEntry> = paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let (source_path, sidecar_path) = parse_virtual_path(path);
+                let source_path_str = source_path.to_string_lossy().to_string();
+                let mut errors = shared_errors.clone();
+                let mut warnings = Vec::new();
+                let mut output_path = None;
+
+                if !source_path.exists() {
+                    errors.push("Source file is missing".to_string());
+                } else if let Err(e) = fs::File::open(&source_path) {
+                    errors.push(format!("Source file is not readable: {}", e));
+                } else {
+                    if !is_supported_image_file(&source_path_str) {
+                        errors.push("Source format is not supported".to_string());
+                    }
+
+                    let new_stem = crate::file_management::generate_filename_from_template(
+                        filename_template,
+                        &source_path,
+                        index + 1,
+                        total_paths,
+                        &export_file_date(&source_path),
+                    );
+                    let candidate =
+                        output_folder_path.join(format!("{}.{}", new_stem, output_format));
+                    if candidate == source_path {
+                        errors.push("Export would overwrite the source file".to_string());
+                    } else if candidate.exists() {
+                        warnings.push("An existing file will be overwritten".to_string());
+                    }
+                    output_path = Some(candidate.to_string_lossy().to_string());
+                }
+
+                if sidecar_path.exists() {
+                    if let Err(e) = fs::read_to_string(&sidecar_path) {
+                        errors.push(format!("Failed to read sidecar: {}", e));
+                    }
+                }
+
+                ExportValidationEntry {
+                    path: path.clone(),
+                    output_path,
+                    ok: false,
+                    errors,
+                    warnings,
+                }
+            })
+            .collect();
+
+        let mut output_counts: HashMap<String, usize> = HashMap::new();
+        for entry in &entries {
+            if let Some(output_path) = &entry.output_path {
+                *output_counts.entry(output_path.clone()).or_insert(0) += 1;
+            }
+        }
+        for entry in &mut entries {
+            if let Some(output_path) = &entry.output_path {
+                if output_counts[output_path] > 1 {
+                    entry.errors.push(
+                        "Filename template produces the same name for several images".to_string(),
+                    );
+                }
+            }
+            entry.ok = entry.errors.is_empty();
+        }
+
+        entries
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn batch_export_images(
     output_folder: String,
     paths: Vec<String>,
-    export_settings: ExportSettings,
+    mut export_settings: ExportSettings,
     output_format: String,
+    override_adjustments: Option<Value>,
+    per_path_overrides: Option<HashMap<String, Value>>,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     if state.export_task_handle.lock().unwrap().is_some() {
         return Err("An export is already in progress.".to_string());
     }
+    resolve_scene_linear_format(&mut export_settings, &output_format)?;
 
     let context = get_or_init_gpu_context(&state)?;
     let context = Arc::new(context);
     let progress_counter = Arc::new(AtomicUsize::new(0));
 
     let available_cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
-    let num_threads = (available_cores / 2).clamp(1, 4); 
-    
+    let num_threads = export_thread_count(&app_handle, available_cores);
+
     log::info!("Starting batch export. System cores: {}, Export threads: {}", available_cores, num_threads);
 
     let task = tokio::spawn(async move {
@@ -1300,7 +2151,6 @@ async fn batch_export_images(
         let output_folder_path = std::path::Path::new(&output_folder);
         let total_paths = paths.len();
         let settings = load_settings(app_handle.clone()).unwrap_or_default();
-        let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
 
         let pool_result = rayon::ThreadPoolBuilder::new()
             .num_threads(num_threads)
@@ -1350,16 +2200,25 @@ async fn batch_export_images(
                         } else {
                             ImageMetadata::default()
                         };
-                        let js_adjustments = metadata.adjustments;
-                        let is_raw = is_raw_file(&source_path_str);
+                        let mut js_adjustments = metadata.adjustments;
+                        if let Some(overrides) = &override_adjustments {
+                            merge_adjustment_overrides(&mut js_adjustments, overrides);
+                        }
+                        if let Some(overrides) = per_path_overrides
+                            .as_ref()
+                            .and_then(|map| map.get(image_path_str))
+                        {
+                            merge_adjustment_overrides(&mut js_adjustments, overrides);
+                        }
+                        let raw_params = settings.raw_develop_params_for(&source_path);
 
-                        let base_image = match read_file_mapped(Path::new(&source_path_str)) {
+                        let decoded = match read_file_mapped(Path::new(&source_path_str)) {
                             Ok(mmap) => load_and_composite(
                                 &mmap,
                                 &source_path_str,
                                 &js_adjustments,
                                 false,
-                                highlight_compression,
+                                raw_params,
                             )
                             .map_err(|e| format!("Failed to load image from mmap: {}", e))?,
                             Err(e) => {
@@ -1376,11 +2235,14 @@ async fn batch_export_images(
                                     &source_path_str,
                                     &js_adjustments,
                                     false,
-                                    highlight_compression,
+                                    raw_params,
                                 )
                                 .map_err(|e| format!("Failed to load image from bytes: {}", e))?
                             }
                         };
+                        let is_embedded_preview = decoded.is_embedded_preview;
+                        let is_raw = is_raw_file(&source_path_str) && !is_embedded_preview;
+                        let base_image = decoded.image;
 
                         let final_image = process_image_for_export(
                             &source_path_str,
@@ -1389,34 +2251,13 @@ async fn batch_export_images(
                             &export_settings,
                             &context,
                             &state,
+                            &app_handle,
                             is_raw,
                         )?;
 
                         let original_path = std::path::Path::new(&source_path_str);
 
-                        let file_date: DateTime<Utc> = {
-                            let mut date = None;
-                            if let Ok(file) = std::fs::File::open(original_path) {
-                                let mut bufreader = std::io::BufReader::new(&file);
-                                let exifreader = exif::Reader::new();
-                                if let Ok(exif_obj) = exifreader.read_from_container(&mut bufreader) {
-                                    if let Some(field) = exif_obj.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY) {
-                                        let s = field.display_value().to_string().replace("\"", "");
-                                        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s.trim(), "%Y:%m:%d %H:%M:%S") {
-                                            date = Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            date.unwrap_or_else(|| {
-                                fs::metadata(original_path)
-                                    .ok()
-                                    .and_then(|m| m.created().ok())
-                                    .map(DateTime::<Utc>::from)
-                                    .unwrap_or_else(Utc::now)
-                            })
-                        };
+                        let file_date = export_file_date(original_path);
 
                         let filename_template = export_settings
                             .filename_template
@@ -1432,22 +2273,20 @@ async fn batch_export_images(
                         let new_filename = format!("{}.{}", new_stem, output_format);
                         let output_path = output_folder_path.join(new_filename);
 
-                        let mut image_bytes = encode_image_to_bytes(
+                        let mut warnings = write_image_to_file(
                             &final_image,
+                            &output_path,
                             &output_format,
                             export_settings.jpeg_quality,
-                        )?;
-
-                        write_image_with_metadata(
-                            &mut image_bytes,
                             &source_path_str,
-                            &output_format,
                             export_settings.keep_metadata,
                             export_settings.strip_gps,
-                        )?;
-
-                        fs::write(&output_path, image_bytes)
-                            .map_err(|e| format!("Failed to write output: {}", e))?;
+                        )
+                        .map_err(|e| format!("Failed to write output: {}", e))?;
+                        if is_embedded_preview {
+                            warnings.insert(0, EMBEDDED_PREVIEW_EXPORT_WARNING.to_string());
+                        }
+                        emit_export_warnings(&app_handle, image_path_str, &warnings);
 
                         Ok(())
                     })();
@@ -1519,17 +2358,18 @@ async fn estimate_export_size(
         .unwrap()
         .clone()
         .ok_or("No original image loaded")?;
-    let is_raw = loaded_image.is_raw;
+    let is_raw = loaded_image.develops_as_raw();
 
     // HYDRATE
     let mut adjustments_clone = js_adjustments.clone();
     hydrate_adjustments(&state, &mut adjustments_clone);
 
     let new_transform_hash = calculate_transform_hash(&adjustments_clone);
+    let preview_dim = get_editor_preview_dim(&app_handle);
     let cached_preview_lock = state.cached_preview.lock().unwrap();
 
     let (preview_image, scale, unscaled_crop_offset) = if let Some(cached) = &*cached_preview_lock {
-        if cached.transform_hash == new_transform_hash {
+        if cached.transform_hash == new_transform_hash && cached.preview_dim == preview_dim {
             (
                 cached.image.clone(),
                 cached.scale,
@@ -1537,14 +2377,26 @@ async fn estimate_export_size(
             )
         } else {
             drop(cached_preview_lock);
-            let (base, scale, offset) =
-                generate_transformed_preview(&loaded_image, &adjustments_clone, &app_handle)?;
+            let (base, scale, offset) = generate_transformed_preview(
+                &state,
+                &app_handle,
+                &loaded_image,
+                &adjustments_clone,
+                preview_dim,
+                get_preview_downscale_quality(&app_handle),
+            )?;
             (base, scale, offset)
         }
     } else {
         drop(cached_preview_lock);
-        let (base, scale, offset) =
-            generate_transformed_preview(&loaded_image, &adjustments_clone, &app_handle)?;
+        let (base, scale, offset) = generate_transformed_preview(
+            &state,
+            &app_handle,
+            &loaded_image,
+            &adjustments_clone,
+            preview_dim,
+            get_preview_downscale_quality(&app_handle),
+        )?;
         (base, scale, offset)
     };
 
@@ -1561,10 +2413,24 @@ async fn estimate_export_size(
 
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
-        .filter_map(|def| generate_mask_bitmap(def, img_w, img_h, scale, scaled_crop_offset))
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                img_w,
+                img_h,
+                scale,
+                scaled_crop_offset,
+                Some(RangeSource {
+                    image: &preview_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
         .collect();
 
-    let all_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    let mut all_adjustments = get_all_adjustments_from_json(&adjustments_clone, is_raw);
+    all_adjustments.global.grain_scale = scale;
+    all_adjustments.global.grain_seed = grain_seed(&loaded_image.path, &adjustments_clone);
     let lut_path = adjustments_clone["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
     let unique_hash = calculate_full_job_hash(&loaded_image.path, &adjustments_clone).wrapping_add(1);
@@ -1572,6 +2438,7 @@ async fn estimate_export_size(
     let processed_preview = process_and_get_dynamic_image(
         &context,
         &state,
+        &app_handle,
         &preview_image,
         unique_hash,
         all_adjustments,
@@ -1676,7 +2543,6 @@ async fn estimate_batch_export_size(
     let first_path = &paths[0];
     let (source_path, sidecar_path) = parse_virtual_path(first_path);
     let source_path_str = source_path.to_string_lossy().to_string();
-    let is_raw = is_raw_file(&source_path_str);
 
     let metadata: ImageMetadata = if sidecar_path.exists() {
         let file_content = fs::read_to_string(sidecar_path).map_err(|e| e.to_string())?;
@@ -1687,12 +2553,12 @@ async fn estimate_batch_export_size(
     let js_adjustments = metadata.adjustments;
 
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
+    let raw_params = settings.raw_develop_params_for(Path::new(&source_path_str));
 
     const ESTIMATE_DIM: u32 = 1280;
 
-    let original_image = match read_file_mapped(Path::new(&source_path_str)) {
-        Ok(mmap) => load_base_image_from_bytes(&mmap, &source_path_str, true, highlight_compression)
+    let decoded = match read_file_mapped(Path::new(&source_path_str)) {
+        Ok(mmap) => decode_base_image_from_bytes(&mmap, &source_path_str, true, raw_params)
             .map_err(|e| e.to_string())?,
         Err(e) => {
             log::warn!(
@@ -1701,12 +2567,15 @@ async fn estimate_batch_export_size(
                 e
             );
             let bytes = fs::read(&source_path_str).map_err(|io_err| io_err.to_string())?;
-            load_base_image_from_bytes(&bytes, &source_path_str, true, highlight_compression)
+            decode_base_image_from_bytes(&bytes, &source_path_str, true, raw_params)
                 .map_err(|e| e.to_string())?
         }
     };
+    let is_raw = is_raw_file(&source_path_str) && !decoded.is_embedded_preview;
+    let original_image = decoded.image;
 
     let base_image_preview = downscale_f32_image(&original_image, ESTIMATE_DIM, ESTIMATE_DIM);
+    let preview_scale = base_image_preview.width() as f32 / original_image.width().max(1) as f32;
 
     let (transformed_preview, unscaled_crop_offset) =
         apply_all_transformations(&base_image_preview, &js_adjustments);
@@ -1720,12 +2589,24 @@ async fn estimate_batch_export_size(
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
         .filter_map(|def| {
-            generate_mask_bitmap(def, preview_w, preview_h, 1.0, unscaled_crop_offset)
+            generate_mask_bitmap(
+                def,
+                preview_w,
+                preview_h,
+                1.0,
+                unscaled_crop_offset,
+                Some(RangeSource {
+                    image: &transformed_preview,
+                    is_linear: is_raw,
+                }),
+            )
         })
         .collect();
 
     let mut all_adjustments = get_all_adjustments_from_json(&js_adjustments, is_raw);
     all_adjustments.global.show_clipping = 0;
+    all_adjustments.global.grain_scale = preview_scale;
+    all_adjustments.global.grain_seed = grain_seed(first_path, &js_adjustments);
 
     let lut_path = js_adjustments["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
@@ -1735,6 +2616,7 @@ async fn estimate_batch_export_size(
     let processed_preview = process_and_get_dynamic_image(
         &context,
         &state,
+        &app_handle,
         &transformed_preview,
         unique_hash,
         all_adjustments,
@@ -1825,27 +2707,177 @@ async fn estimate_batch_export_size(
     Ok(single_image_extrapolated_size * paths.len())
 }
 
+fn exif_rational_to_ur64(val: &exif::Rational) -> little_exif::rational::uR64 {
+    little_exif::rational::uR64 {
+        nominator: val.num,
+        denominator: val.denom,
+    }
+}
+
+fn exif_field_string(field: &exif::Field) -> String {
+    match &field.value {
+        exif::Value::Ascii(vec) => vec
+            .iter()
+            .map(|v| String::from_utf8_lossy(v).trim_matches(char::from(0)).to_string())
+            .collect::<Vec<String>>()
+            .join(" "),
+        _ => field.display_value().to_string().replace("\"", "").trim().to_string(),
+    }
+}
+
+fn append_gps_tags(exif_obj: &exif::Exif, metadata: &mut Metadata) {
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY) {
+        if let exif::Value::Rational(v) = &f.value {
+            if v.len() >= 3 {
+                metadata.set_tag(ExifTag::GPSLatitude(vec![
+                    exif_rational_to_ur64(&v[0]),
+                    exif_rational_to_ur64(&v[1]),
+                    exif_rational_to_ur64(&v[2]),
+                ]));
+            }
+        }
+    }
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSLatitudeRef, exif::In::PRIMARY) {
+        metadata.set_tag(ExifTag::GPSLatitudeRef(exif_field_string(f)));
+    }
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSLongitude, exif::In::PRIMARY) {
+        if let exif::Value::Rational(v) = &f.value {
+            if v.len() >= 3 {
+                metadata.set_tag(ExifTag::GPSLongitude(vec![
+                    exif_rational_to_ur64(&v[0]),
+                    exif_rational_to_ur64(&v[1]),
+                    exif_rational_to_ur64(&v[2]),
+                ]));
+            }
+        }
+    }
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSLongitudeRef, exif::In::PRIMARY) {
+        metadata.set_tag(ExifTag::GPSLongitudeRef(exif_field_string(f)));
+    }
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSAltitude, exif::In::PRIMARY) {
+        if let exif::Value::Rational(v) = &f.value {
+            if !v.is_empty() {
+                metadata.set_tag(ExifTag::GPSAltitude(vec![exif_rational_to_ur64(&v[0])]));
+            }
+        }
+    }
+    if let Some(f) = exif_obj.get_field(exif::Tag::GPSAltitudeRef, exif::In::PRIMARY) {
+        if let Some(val) = f.value.get_uint(0) {
+            metadata.set_tag(ExifTag::GPSAltitudeRef(vec![val as u8]));
+        }
+    }
+}
+
+/// Removes EXIF, XMP, text and comment blocks from encoded JPEG/PNG data, keeping any
+/// embedded ICC profile. The encoders don't normally emit any, but a stripped export must
+/// be guaranteed clean.
+fn strip_embedded_metadata(image_bytes: &mut Vec<u8>, output_format: &str) {
+    let stripped = match output_format.to_lowercase().as_str() {
+        "jpg" | "jpeg" => strip_jpeg_metadata(image_bytes),
+        "png" => strip_png_metadata(image_bytes),
+        _ => None,
+    };
+    if let Some(stripped) = stripped {
+        *image_bytes = stripped;
+    }
+}
+
+fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    const ICC_PROFILE_ID: &[u8] = b"ICC_PROFILE\0";
+
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] != 0xD8 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    let mut pos = 2;
+
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        // Start of scan: everything after is entropy-coded image data
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[pos..]);
+            return Some(out);
+        }
+        let segment_len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let segment_end = pos + 2 + segment_len;
+        if segment_end > bytes.len() {
+            return None;
+        }
+        // APP1-APP15 carry EXIF/XMP/thumbnails, 0xFE is a comment. APP0 (JFIF) and the
+        // APP2 ICC profile are kept so colours still render correctly.
+        let is_icc_profile =
+            marker == 0xE2 && bytes[pos + 4..segment_end].starts_with(ICC_PROFILE_ID);
+        let is_metadata = ((0xE1..=0xEF).contains(&marker) && !is_icc_profile) || marker == 0xFE;
+        if !is_metadata {
+            out.extend_from_slice(&bytes[pos..segment_end]);
+        }
+        pos = segment_end;
+    }
+
+    None
+}
+
+fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    const METADATA_CHUNKS: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+    if bytes.len() < 8 || bytes[..8] != PNG_SIGNATURE {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..8]);
+    let mut pos = 8;
+
+    while pos + 12 <= bytes.len() {
+        let data_len =
+            u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+                as usize;
+        let chunk_end = pos + 12 + data_len;
+        if chunk_end > bytes.len() {
+            return None;
+        }
+        let chunk_type = &bytes[pos + 4..pos + 8];
+        if !METADATA_CHUNKS.iter().any(|t| t.as_slice() == chunk_type) {
+            out.extend_from_slice(&bytes[pos..chunk_end]);
+        }
+        pos = chunk_end;
+    }
+
+    (pos == bytes.len()).then_some(out)
+}
+
 fn write_image_with_metadata(
     image_bytes: &mut Vec<u8>,
     original_path_str: &str,
     output_format: &str,
     keep_metadata: bool,
     strip_gps: bool,
-) -> Result<(), String> {
-    if !keep_metadata || output_format.to_lowercase() == "tiff" {
+) -> Result<Vec<String>, String> {
+    if !keep_metadata {
+        strip_embedded_metadata(image_bytes, output_format);
+        return Ok(Vec::new());
+    }
+
+    if matches!(output_format.to_lowercase().as_str(), "tiff" | "exr") {
         // FIXME: temporary solution until I find a way to write metadata to TIFF
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     let original_path = std::path::Path::new(original_path_str);
     if !original_path.exists() {
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     // Skip TIFF sources to avoid potential tag corruption issues
     let original_ext = original_path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
     if original_ext == "tiff" || original_ext == "tif" {
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     let file_type = match output_format.to_lowercase().as_str() {
@@ -1854,7 +2886,7 @@ fn write_image_with_metadata(
             as_zTXt_chunk: true,
         },
         "tiff" => FileExtension::TIFF,
-        _ => return Ok(()),
+        _ => return Ok(Vec::new()),
     };
 
     let mut metadata = Metadata::new();
@@ -1977,36 +3009,7 @@ fn write_image_with_metadata(
             }
 
             if !strip_gps {
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY) {
-                        if let exif::Value::Rational(v) = &f.value {
-                            if v.len() >= 3 {
-                                metadata.set_tag(ExifTag::GPSLatitude(vec![to_ur64(&v[0]), to_ur64(&v[1]), to_ur64(&v[2])]));
-                            }
-                        }
-                }
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSLatitudeRef, exif::In::PRIMARY) {
-                    metadata.set_tag(ExifTag::GPSLatitudeRef(get_string_val(f)));
-                }
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSLongitude, exif::In::PRIMARY) {
-                        if let exif::Value::Rational(v) = &f.value {
-                            if v.len() >= 3 {
-                                metadata.set_tag(ExifTag::GPSLongitude(vec![to_ur64(&v[0]), to_ur64(&v[1]), to_ur64(&v[2])]));
-                            }
-                        }
-                }
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSLongitudeRef, exif::In::PRIMARY) {
-                    metadata.set_tag(ExifTag::GPSLongitudeRef(get_string_val(f)));
-                }
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSAltitude, exif::In::PRIMARY) {
-                    if let exif::Value::Rational(v) = &f.value {
-                        if !v.is_empty() { metadata.set_tag(ExifTag::GPSAltitude(vec![to_ur64(&v[0])])); }
-                    }
-                }
-                if let Some(f) = exif_obj.get_field(exif::Tag::GPSAltitudeRef, exif::In::PRIMARY) {
-                        if let Some(val) = f.value.get_uint(0) {
-                            metadata.set_tag(ExifTag::GPSAltitudeRef(vec![val as u8]));
-                        }
-                }
+                append_gps_tags(&exif_obj, &mut metadata);
             }
         }
     }
@@ -2020,69 +3023,417 @@ fn write_image_with_metadata(
         metadata.write_to_vec(image_bytes, file_type)
     }));
 
+    let mut warnings = Vec::new();
     match write_result {
         Ok(Ok(_)) => {},
-        Ok(Err(e)) => log::warn!("Failed to write metadata: {}", e),
-        Err(_) => log::error!("Recovered from little_exif library panic. Saving image without metadata."),
+        Ok(Err(e)) => {
+            log::warn!("Failed to write metadata: {}", e);
+            warnings.push(format!("Metadata could not be written: {}", e));
+        }
+        Err(_) => {
+            log::error!("Recovered from little_exif library panic. Saving image without metadata.");
+            warnings.push("Metadata could not be written; the image was saved without it.".to_string());
+        }
     }
 
-    Ok(())
+    Ok(warnings)
 }
 
-#[tauri::command]
-fn generate_mask_overlay(
-    mask_def: MaskDefinition,
-    width: u32,
-    height: u32,
-    scale: f32,
-    crop_offset: (f32, f32),
-) -> Result<String, String> {
-    let scaled_crop_offset = (crop_offset.0 * scale, crop_offset.1 * scale);
+fn read_exif_from_path(path: &Path) -> Option<exif::Exif> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut bufreader = std::io::BufReader::new(&file);
+    exif::Reader::new().read_from_container(&mut bufreader).ok()
+}
 
-    if let Some(gray_mask) =
-        generate_mask_bitmap(&mask_def, width, height, scale, scaled_crop_offset)
-    {
-        let mut rgba_mask = RgbaImage::new(width, height);
-        for (x, y, pixel) in gray_mask.enumerate_pixels() {
-            let intensity = pixel[0];
-            let alpha = (intensity as f32 * 0.5) as u8;
-            rgba_mask.put_pixel(x, y, Rgba([255, 0, 0, alpha]));
+/// Formats an EXIF GPS coordinate the way XMP expects it, e.g. "47,30.1234N".
+fn format_xmp_gps_coordinate(values: &[exif::Rational], reference: &str) -> Option<String> {
+    if values.len() < 3 {
+        return None;
+    }
+    let degrees = values[0].to_f64();
+    let minutes = values[1].to_f64() + values[2].to_f64() / 60.0;
+    Some(format!("{},{:.6}{}", degrees.trunc() as i64, minutes, reference))
+}
+
+fn build_xmp_metadata_attributes(
+    exif_obj: &exif::Exif,
+    copy_gps: bool,
+    copy_artist: bool,
+    copy_copyright: bool,
+) -> Vec<(&'static str, String)> {
+    let mut attributes = Vec::new();
+    let field = |tag| exif_obj.get_field(tag, exif::In::PRIMARY);
+
+    if copy_gps {
+        for (tag, ref_tag, name) in [
+            (exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef, "exif:GPSLatitude"),
+            (exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef, "exif:GPSLongitude"),
+        ] {
+            let reference = field(ref_tag).map(exif_field_string).unwrap_or_default();
+            if let Some(exif::Value::Rational(v)) = field(tag).map(|f| &f.value) {
+                if let Some(value) = format_xmp_gps_coordinate(v, &reference) {
+                    attributes.push((name, value));
+                }
+            }
+        }
+        if let Some(exif::Value::Rational(v)) = field(exif::Tag::GPSAltitude).map(|f| &f.value) {
+            if let Some(altitude) = v.first() {
+                attributes.push(("exif:GPSAltitude", format!("{}/{}", altitude.num, altitude.denom)));
+            }
+        }
+        if let Some(val) = field(exif::Tag::GPSAltitudeRef).and_then(|f| f.value.get_uint(0)) {
+            attributes.push(("exif:GPSAltitudeRef", val.to_string()));
         }
+    }
+    if copy_artist {
+        if let Some(f) = field(exif::Tag::Artist) {
+            attributes.push(("tiff:Artist", exif_field_string(f)));
+        }
+    }
+    if copy_copyright {
+        if let Some(f) = field(exif::Tag::Copyright) {
+            attributes.push(("tiff:Copyright", exif_field_string(f)));
+        }
+    }
 
-        let mut buf = Cursor::new(Vec::new());
-        rgba_mask
-            .write_to(&mut buf, ImageFormat::Png)
-            .map_err(|e| e.to_string())?;
+    attributes
+}
 
-        let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
-        let data_url = format!("data:image/png;base64,{}", base64_str);
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CopyMetadataResult {
+    updated: usize,
+    skipped: Vec<String>,
+    errors: Vec<String>,
+}
 
-        Ok(data_url)
-    } else {
-        Ok("".to_string())
+/// Writes `bytes` to a temporary file next to `path` and renames it over `path`, so an
+/// interrupted write never leaves a truncated original behind.
+fn replace_file_contents(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+    let mut temp_file = NamedTempFile::new_in(parent).map_err(|e| e.to_string())?;
+    temp_file.write_all(bytes).map_err(|e| e.to_string())?;
+    temp_file.as_file().sync_all().map_err(|e| e.to_string())?;
+    if let Ok(original) = fs::metadata(path) {
+        let _ = fs::set_permissions(temp_file.path(), original.permissions());
     }
+    temp_file.persist(path).map_err(|e| e.error.to_string())?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn generate_ai_foreground_mask(
-    rotation: f32,
-    flip_horizontal: bool,
-    flip_vertical: bool,
-    orientation_steps: u8,
-    state: tauri::State<'_, AppState>,
-    app_handle: tauri::AppHandle,
-) -> Result<AiForegroundMaskParameters, String> {
-    let models = get_or_init_ai_models(&app_handle, &state.ai_state, &state.ai_init_lock)
-        .await
-        .map_err(|e| e.to_string())?;
+fn copy_metadata_to_paths(
+    source_path: String,
+    target_paths: Vec<String>,
+    fields: Vec<String>,
+    force: Option<bool>,
+) -> Result<CopyMetadataResult, String> {
+    let force = force.unwrap_or(false);
+    let mut copy_gps = false;
+    let mut copy_artist = false;
+    let mut copy_copyright = false;
+    for field in &fields {
+        match field.to_lowercase().as_str() {
+            "gps" => copy_gps = true,
+            "artist" => copy_artist = true,
+            "copyright" => copy_copyright = true,
+            other => return Err(format!("Unsupported metadata field: {}", other)),
+        }
+    }
 
-    let (full_image, _) = get_full_image_for_processing(&state)?;
-    let full_mask_image =
-        run_u2netp_model(&full_image, &models.u2netp).map_err(|e| e.to_string())?;
-    let base64_data = encode_to_base64_png(&full_mask_image)?;
+    let (source, _) = parse_virtual_path(&source_path);
+    let source_exif = read_exif_from_path(&source)
+        .ok_or_else(|| "The source image has no readable EXIF data.".to_string())?;
+    let source_has_gps = source_exif
+        .get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY)
+        .is_some();
+    if copy_gps && !source_has_gps {
+        return Err("The source image does not contain GPS data.".to_string());
+    }
 
-    Ok(AiForegroundMaskParameters {
-        mask_data_base64: Some(base64_data),
+    let mut result = CopyMetadataResult {
+        updated: 0,
+        skipped: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for target_path_str in target_paths {
+        let (target_path, _) = parse_virtual_path(&target_path_str);
+        if target_path == source {
+            continue;
+        }
+
+        let extension = target_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let writes_in_place = matches!(extension.as_str(), "jpg" | "jpeg" | "png" | "webp");
+
+        let target_has_gps = read_exif_from_path(&target_path)
+            .map(|e| e.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY).is_some())
+            .unwrap_or(false)
+            || (!writes_in_place
+                && xmp_sidecar::read_xmp_sidecar_attribute(&target_path, "exif:GPSLatitude")
+                    .is_some());
+        let target_copy_gps = copy_gps && (force || !target_has_gps);
+
+        if !target_copy_gps && !copy_artist && !copy_copyright {
+            result.skipped.push(target_path_str);
+            continue;
+        }
+
+        let write_result: Result<(), String> = if writes_in_place {
+            let file_type = match extension.as_str() {
+                "png" => FileExtension::PNG { as_zTXt_chunk: true },
+                "webp" => FileExtension::WEBP,
+                _ => FileExtension::JPEG,
+            };
+            // Writing an empty block would wipe whatever EXIF couldn't be parsed, so skip those files
+            let read_result = std::panic::catch_unwind(|| Metadata::new_from_path(&target_path));
+            let mut metadata = match read_result {
+                Ok(Ok(metadata)) => metadata,
+                Ok(Err(e)) => {
+                    result.errors.push(format!(
+                        "{}: existing metadata could not be read, file left unchanged ({})",
+                        target_path_str, e
+                    ));
+                    continue;
+                }
+                Err(_) => {
+                    result.errors.push(format!(
+                        "{}: existing metadata could not be read, file left unchanged",
+                        target_path_str
+                    ));
+                    continue;
+                }
+            };
+            if target_copy_gps {
+                append_gps_tags(&source_exif, &mut metadata);
+            }
+            if copy_artist {
+                if let Some(f) = source_exif.get_field(exif::Tag::Artist, exif::In::PRIMARY) {
+                    metadata.set_tag(ExifTag::Artist(exif_field_string(f)));
+                }
+            }
+            if copy_copyright {
+                if let Some(f) = source_exif.get_field(exif::Tag::Copyright, exif::In::PRIMARY) {
+                    metadata.set_tag(ExifTag::Copyright(exif_field_string(f)));
+                }
+            }
+
+            // See write_image_with_metadata for why little_exif writes are guarded against panics
+            fs::read(&target_path)
+                .map_err(|e| e.to_string())
+                .and_then(|mut bytes| {
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        metadata.write_to_vec(&mut bytes, file_type)
+                    }))
+                    .map_err(|_| "Recovered from little_exif library panic".to_string())?
+                    .map_err(|e| e.to_string())?;
+                    replace_file_contents(&target_path, &bytes)
+                })
+        } else {
+            let attributes = build_xmp_metadata_attributes(
+                &source_exif,
+                target_copy_gps,
+                copy_artist,
+                copy_copyright,
+            );
+            xmp_sidecar::update_xmp_sidecar(&target_path, &attributes)
+        };
+
+        match write_result {
+            Ok(()) => result.updated += 1,
+            Err(e) => result.errors.push(format!("{}: {}", target_path_str, e)),
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+fn generate_mask_overlay(
+    mask_def: MaskDefinition,
+    width: u32,
+    height: u32,
+    scale: f32,
+    crop_offset: (f32, f32),
+    adjustments: serde_json::Value,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    let scaled_crop_offset = (crop_offset.0 * scale, crop_offset.1 * scale);
+
+    // Range refinement needs the pixels under the mask, and the cached preview has the
+    // same framing only if it was rendered for the current transform
+    let range_source = if mask_def.range_mask.is_some() {
+        let mut adjustments = adjustments;
+        hydrate_adjustments(&state, &mut adjustments);
+        let transform_hash = calculate_transform_hash(&adjustments);
+        let is_raw = state
+            .original_image
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|loaded| loaded.develops_as_raw());
+        let source = state
+            .cached_preview
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.transform_hash == transform_hash)
+            .map(|cached| cached.small_image.clone())
+            .ok_or("The preview for the current crop and rotation is not rendered yet")?;
+        Some((source, is_raw))
+    } else {
+        None
+    };
+
+    if let Some(gray_mask) = generate_mask_bitmap(
+        &mask_def,
+        width,
+        height,
+        scale,
+        scaled_crop_offset,
+        range_source.as_ref().map(|(image, is_raw)| RangeSource {
+            image,
+            is_linear: *is_raw,
+        }),
+    ) {
+        let mut rgba_mask = RgbaImage::new(width, height);
+        for (x, y, pixel) in gray_mask.enumerate_pixels() {
+            let intensity = pixel[0];
+            let alpha = (intensity as f32 * 0.5) as u8;
+            rgba_mask.put_pixel(x, y, Rgba([255, 0, 0, alpha]));
+        }
+
+        let mut buf = Cursor::new(Vec::new());
+        rgba_mask
+            .write_to(&mut buf, ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+
+        let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
+        let data_url = format!("data:image/png;base64,{}", base64_str);
+
+        Ok(data_url)
+    } else {
+        Ok("".to_string())
+    }
+}
+
+#[tauri::command]
+fn generate_clipping_overlay(
+    highlight_threshold: Option<u8>,
+    shadow_threshold: Option<u8>,
+    tolerance: Option<u8>,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    let preview_lock = state.last_processed_preview.lock().unwrap();
+    let preview = preview_lock
+        .as_ref()
+        .ok_or("No processed preview available for clipping overlay")?;
+
+    let tolerance = tolerance.unwrap_or(2);
+    let highlight_limit = highlight_threshold.unwrap_or(255).saturating_sub(tolerance);
+    let shadow_limit = shadow_threshold.unwrap_or(0).saturating_add(tolerance);
+
+    let (width, height) = preview.dimensions();
+    let mut overlay = RgbaImage::new(width, height);
+    for (x, y, pixel) in preview.enumerate_pixels() {
+        let [r, g, b] = pixel.0;
+        if r.max(g).max(b) >= highlight_limit {
+            overlay.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else if r.max(g).max(b) <= shadow_limit {
+            overlay.put_pixel(x, y, Rgba([0, 0, 255, 255]));
+        }
+    }
+    drop(preview_lock);
+
+    let mut buf = Cursor::new(Vec::new());
+    overlay
+        .write_to(&mut buf, ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+
+    let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
+    Ok(format!("data:image/png;base64,{}", base64_str))
+}
+
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some([r, g, b])
+}
+
+#[tauri::command]
+fn generate_focus_peaking_overlay(
+    sensitivity: Option<f32>,
+    color: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    let preview_lock = state.last_processed_preview.lock().unwrap();
+    let preview = preview_lock
+        .as_ref()
+        .ok_or("No processed preview available for focus peaking")?;
+    let luma = DynamicImage::ImageRgb8(preview.clone()).to_luma8();
+    drop(preview_lock);
+
+    let peak_color = color
+        .as_deref()
+        .and_then(parse_hex_color)
+        .unwrap_or([255, 0, 0]);
+
+    // Sensitivity 0-100 maps onto a Sobel magnitude threshold; higher means more edges
+    let sensitivity = sensitivity.unwrap_or(50.0).clamp(0.0, 100.0) / 100.0;
+    let threshold = (400.0 - sensitivity * 360.0) as u16;
+
+    // A light blur keeps sensor noise from lighting up flat, out-of-focus areas
+    let smoothed = imageproc::filter::gaussian_blur_f32(&luma, 0.8);
+    let gradients = imageproc::gradients::sobel_gradients(&smoothed);
+
+    let (width, height) = gradients.dimensions();
+    let mut overlay = RgbaImage::new(width, height);
+    for (x, y, magnitude) in gradients.enumerate_pixels() {
+        if magnitude[0] >= threshold {
+            overlay.put_pixel(x, y, Rgba([peak_color[0], peak_color[1], peak_color[2], 255]));
+        }
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    overlay
+        .write_to(&mut buf, ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+
+    let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
+    Ok(format!("data:image/png;base64,{}", base64_str))
+}
+
+#[tauri::command]
+async fn generate_ai_foreground_mask(
+    rotation: f32,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    orientation_steps: u8,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<AiForegroundMaskParameters, String> {
+    let models = get_or_init_ai_models(&app_handle, &state.ai_state, &state.ai_init_lock)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (full_image, _) = get_full_image_for_processing(&state)?;
+    let full_mask_image =
+        run_u2netp_model(&full_image, &models.u2netp).map_err(|e| e.to_string())?;
+    let base64_data = encode_to_base64_png(&full_mask_image)?;
+
+    Ok(AiForegroundMaskParameters {
+        mask_data_base64: Some(base64_data),
         rotation: Some(rotation),
         flip_horizontal: Some(flip_horizontal),
         flip_vertical: Some(flip_vertical),
@@ -2261,6 +3612,7 @@ async fn generate_ai_subject_mask(
 fn generate_preset_preview(
     js_adjustments: serde_json::Value,
     state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<Response, String> {
     let context = get_or_init_gpu_context(&state)?;
 
@@ -2272,7 +3624,7 @@ fn generate_preset_preview(
         .ok_or("No original image loaded for preset preview")?;
     let original_image = loaded_image.image;
     let path = loaded_image.path;
-    let is_raw = loaded_image.is_raw;
+    let is_raw = loaded_image.develops_as_raw();
     let unique_hash = calculate_full_job_hash(&path, &js_adjustments);
 
     const PRESET_PREVIEW_DIM: u32 = 200;
@@ -2289,16 +3641,30 @@ fn generate_preset_preview(
 
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
-        .filter_map(|def| generate_mask_bitmap(def, img_w, img_h, 1.0, unscaled_crop_offset))
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                img_w,
+                img_h,
+                1.0,
+                unscaled_crop_offset,
+                Some(RangeSource {
+                    image: &transformed_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
         .collect();
 
-    let all_adjustments = get_all_adjustments_from_json(&js_adjustments, is_raw);
+    let mut all_adjustments = get_all_adjustments_from_json(&js_adjustments, is_raw);
+    all_adjustments.global.grain_seed = grain_seed(&path, &js_adjustments);
     let lut_path = js_adjustments["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
 
     let processed_image = process_and_get_dynamic_image(
         &context,
         &state,
+        &app_handle,
         &transformed_image,
         unique_hash,
         all_adjustments,
@@ -2387,9 +3753,11 @@ async fn invoke_generative_replace_with_mask_def(
         opacity: 100.0,
         adjustments: serde_json::Value::Null,
         sub_masks: patch_definition.sub_masks,
+        range_mask: None,
     };
 
-    let mask_bitmap = generate_mask_bitmap(&mask_def_for_generation, img_w, img_h, 1.0, (0.0, 0.0))
+    let mask_bitmap =
+        generate_mask_bitmap(&mask_def_for_generation, img_w, img_h, 1.0, (0.0, 0.0), None)
         .ok_or("Failed to generate mask bitmap for AI replace")?;
 
     let patch_rgba = if use_fast_inpaint {
@@ -2529,28 +3897,185 @@ fn get_supported_file_types() -> Result<serde_json::Value, String> {
     }))
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GpuCapabilities {
+    adapter_name: String,
+    backend: String,
+    device_type: String,
+    driver: String,
+    max_texture_dimension: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Capabilities {
+    app_version: String,
+    platform: &'static str,
+    export_formats: Vec<&'static str>,
+    gpu: Option<GpuCapabilities>,
+    gpu_error: Option<String>,
+    onnx_runtime_available: bool,
+    ai_models: Vec<ai_processing::AiModelStatus>,
+}
+
+/// Reports what this build can actually do on this machine, so the frontend can hide
+/// options that would only fail when used. Initializes the GPU context if needed.
+#[tauri::command]
+async fn get_capabilities(app_handle: tauri::AppHandle) -> Result<Capabilities, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let (gpu, gpu_error) = match get_or_init_gpu_context(&state) {
+            Ok(context) => (
+                Some(GpuCapabilities {
+                    adapter_name: context.adapter_info.name.clone(),
+                    backend: format!("{:?}", context.adapter_info.backend),
+                    device_type: format!("{:?}", context.adapter_info.device_type),
+                    driver: context.adapter_info.driver.clone(),
+                    max_texture_dimension: context.limits.max_texture_dimension_2d,
+                }),
+                None,
+            ),
+            Err(e) => (None, Some(e)),
+        };
+
+        // The runtime is loaded dynamically from the path set at startup
+        let onnx_runtime_available = std::env::var_os("ORT_DYLIB_PATH")
+            .map_or(false, |path| Path::new(&path).is_file());
+
+        Ok(Capabilities {
+            app_version: app_handle.package_info().version.to_string(),
+            platform: std::env::consts::OS,
+            export_formats: EXPORT_FORMATS.to_vec(),
+            gpu,
+            gpu_error,
+            onnx_runtime_available,
+            ai_models: ai_processing::installed_models(&app_handle),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CommunityManifestCacheInfo {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommunityPresetsResult {
+    presets: Vec<CommunityPreset>,
+    /// Set only when GitHub couldn't be reached and a possibly stale manifest is returned.
+    is_cached: bool,
+}
+
+fn read_cached_community_manifest(cache_dir: &Path) -> Option<Vec<CommunityPreset>> {
+    let content = fs::read_to_string(cache_dir.join("manifest.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
 #[tauri::command]
-async fn fetch_community_presets() -> Result<Vec<CommunityPreset>, String> {
+async fn fetch_community_presets(
+    app_handle: tauri::AppHandle,
+) -> Result<CommunityPresetsResult, String> {
     let client = reqwest::Client::new();
     let url = "https://raw.githubusercontent.com/CyberTimon/RapidRAW-Presets/main/manifest.json";
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "RapidRAW-App")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch manifest from GitHub: {}", e))?;
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("community_presets");
+    let cached_presets = read_cached_community_manifest(&cache_dir);
+    let cache_info: CommunityManifestCacheInfo = if cached_presets.is_some() {
+        fs::read_to_string(cache_dir.join("manifest_info.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    } else {
+        CommunityManifestCacheInfo::default()
+    };
+
+    let mut request = client.get(url).header("User-Agent", "RapidRAW-App");
+    if let Some(etag) = &cache_info.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &cache_info.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return match cached_presets {
+                Some(presets) => {
+                    log::warn!("Failed to fetch community presets, using cached manifest: {}", e);
+                    Ok(CommunityPresetsResult {
+                        presets,
+                        is_cached: true,
+                    })
+                }
+                None => Err(format!("Failed to fetch manifest from GitHub: {}", e)),
+            };
+        }
+    };
+
+    // A 304 confirms the cached manifest is current, so it isn't an offline result
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(presets) = cached_presets {
+            return Ok(CommunityPresetsResult {
+                presets,
+                is_cached: false,
+            });
+        }
+    }
 
     if !response.status().is_success() {
-        return Err(format!("GitHub returned an error: {}", response.status()));
+        return match cached_presets {
+            Some(presets) => Ok(CommunityPresetsResult {
+                presets,
+                is_cached: true,
+            }),
+            None => Err(format!("GitHub returned an error: {}", response.status())),
+        };
     }
 
-    let presets: Vec<CommunityPreset> = response
-        .json()
+    let header_value = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let new_cache_info = CommunityManifestCacheInfo {
+        etag: header_value(reqwest::header::ETAG),
+        last_modified: header_value(reqwest::header::LAST_MODIFIED),
+    };
+
+    let body = response
+        .text()
         .await
+        .map_err(|e| format!("Failed to read manifest.json: {}", e))?;
+    let presets: Vec<CommunityPreset> = serde_json::from_str(&body)
         .map_err(|e| format!("Failed to parse manifest.json: {}", e))?;
 
-    Ok(presets)
+    let cache_result = fs::create_dir_all(&cache_dir)
+        .and_then(|_| fs::write(cache_dir.join("manifest.json"), &body))
+        .and_then(|_| {
+            let info = serde_json::to_string(&new_cache_info).unwrap_or_default();
+            fs::write(cache_dir.join("manifest_info.json"), info)
+        });
+    if let Err(e) = cache_result {
+        log::warn!("Failed to cache community presets manifest: {}", e);
+    }
+
+    Ok(CommunityPresetsResult {
+        presets,
+        is_cached: false,
+    })
 }
 
 #[tauri::command]
@@ -2560,93 +4085,174 @@ async fn generate_all_community_previews(
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<HashMap<String, Vec<u8>>, String> {
+    let cancel_token = Arc::new(AtomicBool::new(false));
+    {
+        let mut current_token = state.community_preview_cancellation_token.lock().unwrap();
+        current_token.store(true, Ordering::SeqCst);
+        *current_token = cancel_token.clone();
+    }
+
     let context = crate::image_processing::get_or_init_gpu_context(&state)?;
     let mut results: HashMap<String, Vec<u8>> = HashMap::new();
+    let total_presets = presets.len();
 
     const TILE_DIM: u32 = 360;
     const PROCESSING_DIM: u32 = TILE_DIM * 2;
 
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
+    let raw_params = settings.raw_develop_params();
 
-    let mut base_thumbnails: Vec<(DynamicImage, bool)> = Vec::new();
+    let mut source_hasher = DefaultHasher::new();
+    TILE_DIM.hash(&mut source_hasher);
+    format!("{:?}", raw_params).hash(&mut source_hasher);
     for image_path in image_paths.iter() {
         let (source_path, _) = parse_virtual_path(image_path);
-        let source_path_str = source_path.to_string_lossy().to_string();
-        let image_bytes = fs::read(&source_path).map_err(|e| e.to_string())?;
-        let original_image =
-            crate::image_loader::load_base_image_from_bytes(&image_bytes, &source_path_str, true, highlight_compression )
-                .map_err(|e| e.to_string())?;
-        let is_raw = is_raw_file(&source_path_str);
-        base_thumbnails.push((
-            downscale_f32_image(&original_image, PROCESSING_DIM, PROCESSING_DIM),
-            is_raw,
-        ));
+        source_path.hash(&mut source_hasher);
+        if let Ok(metadata) = fs::metadata(&source_path) {
+            metadata.len().hash(&mut source_hasher);
+            if let Ok(modified) = metadata.modified() {
+                modified.hash(&mut source_hasher);
+            }
+        }
     }
+    let source_set_hash = source_hasher.finish();
+    let cache_dir =
+        file_management::get_community_preview_cache_dir(&app_handle, source_set_hash).ok();
+
+    let mut base_thumbnails: Vec<(DynamicImage, bool)> = Vec::new();
+
+    for (preset_index, preset) in presets.iter().enumerate() {
+        // Previews finished so far are already in the disk cache, so returning them is
+        // enough for the next run to pick up where this one stopped.
+        if cancel_token.load(Ordering::SeqCst) {
+            log::info!(
+                "Community preview generation cancelled after {}/{} presets.",
+                preset_index,
+                total_presets
+            );
+            return Ok(results);
+        }
+        let _ = app_handle.emit(
+            "community-preview-progress",
+            serde_json::json!({
+                "current": preset_index + 1,
+                "total": total_presets,
+                "name": preset.name
+            }),
+        );
 
-    for preset in presets.iter() {
-        let mut processed_tiles: Vec<RgbImage> = Vec::new();
         let js_adjustments = &preset.adjustments;
 
         let mut preset_hasher = DefaultHasher::new();
         preset.name.hash(&mut preset_hasher);
         let preset_hash = preset_hasher.finish();
 
-        for (i, (base_image, is_raw)) in base_thumbnails.iter().enumerate() {
-            let (transformed_image, unscaled_crop_offset) =
-                crate::apply_all_transformations(&base_image, &js_adjustments);
-            let (img_w, img_h) = transformed_image.dimensions();
+        let mut cache_hasher = DefaultHasher::new();
+        preset_hash.hash(&mut cache_hasher);
+        js_adjustments.to_string().hash(&mut cache_hasher);
+        let cache_file = cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}.jpg", cache_hasher.finish())));
 
-            let mask_definitions: Vec<MaskDefinition> = js_adjustments
-                .get("masks")
-                .and_then(|m| serde_json::from_value(m.clone()).ok())
-                .unwrap_or_else(Vec::new);
+        if let Some(bytes) = cache_file.as_ref().and_then(|file| fs::read(file).ok()) {
+            results.insert(preset.name.clone(), bytes);
+            continue;
+        }
 
-            let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
-                .iter()
-                .filter_map(|def| {
-                    generate_mask_bitmap(def, img_w, img_h, 1.0, unscaled_crop_offset)
+        if base_thumbnails.is_empty() {
+            base_thumbnails = image_paths
+                .par_iter()
+                .map(|image_path| {
+                    let (source_path, _) = parse_virtual_path(image_path);
+                    let source_path_str = source_path.to_string_lossy().to_string();
+                    let image_bytes = fs::read(&source_path).map_err(|e| e.to_string())?;
+                    let original_image = crate::image_loader::load_base_image_from_bytes(
+                        &image_bytes,
+                        &source_path_str,
+                        true,
+                        raw_params,
+                    )
+                    .map_err(|e| e.to_string())?;
+                    let is_raw = is_raw_file(&source_path_str);
+                    Ok((
+                        downscale_f32_image(&original_image, PROCESSING_DIM, PROCESSING_DIM),
+                        is_raw,
+                    ))
                 })
-                .collect();
-
-            let all_adjustments = get_all_adjustments_from_json(&js_adjustments, *is_raw);
-            let lut_path = js_adjustments["lutPath"].as_str();
-            let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
-
-            let unique_hash = preset_hash.wrapping_add(i as u64);
+                .collect::<Result<Vec<_>, String>>()?;
+        }
 
-            let processed_image_dynamic = crate::image_processing::process_and_get_dynamic_image(
-                &context,
-                &state,
-                &transformed_image,
-                unique_hash,
-                all_adjustments,
-                &mask_bitmaps,
-                lut,
-                "generate_all_community_previews",
-            )?;
+        // The GPU processor serializes its own dispatches; running the tiles in parallel
+        // overlaps the CPU-side transforms, masks and resizing with them.
+        let mut processed_tiles = base_thumbnails
+            .par_iter()
+            .enumerate()
+            .map(|(i, (base_image, is_raw))| -> Result<RgbImage, String> {
+                let (transformed_image, unscaled_crop_offset) =
+                    crate::apply_all_transformations(&base_image, &js_adjustments);
+                let (img_w, img_h) = transformed_image.dimensions();
+
+                let mask_definitions: Vec<MaskDefinition> = js_adjustments
+                    .get("masks")
+                    .and_then(|m| serde_json::from_value(m.clone()).ok())
+                    .unwrap_or_else(Vec::new);
+
+                let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
+                    .iter()
+                    .filter_map(|def| {
+                        generate_mask_bitmap(
+                            def,
+                            img_w,
+                            img_h,
+                            1.0,
+                            unscaled_crop_offset,
+                            Some(RangeSource {
+                                image: &transformed_image,
+                                is_linear: *is_raw,
+                            }),
+                        )
+                    })
+                    .collect();
 
-            let processed_image = processed_image_dynamic.to_rgb8();
+                let all_adjustments = get_all_adjustments_from_json(&js_adjustments, *is_raw);
+                let lut_path = js_adjustments["lutPath"].as_str();
+                let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
 
-            let (proc_w, proc_h) = processed_image.dimensions();
-            let size = proc_w.min(proc_h);
-            let cropped_processed_image = image::imageops::crop_imm(
-                &processed_image,
-                (proc_w - size) / 2,
-                (proc_h - size) / 2,
-                size,
-                size,
-            )
-            .to_image();
+                let unique_hash = preset_hash.wrapping_add(i as u64);
 
-            let final_tile = image::imageops::resize(
-                &cropped_processed_image,
-                TILE_DIM,
-                TILE_DIM,
-                image::imageops::FilterType::Lanczos3,
-            );
-            processed_tiles.push(final_tile);
-        }
+                let processed_image_dynamic = crate::image_processing::process_and_get_dynamic_image(
+                    &context,
+                    &state,
+                    &app_handle,
+                    &transformed_image,
+                    unique_hash,
+                    all_adjustments,
+                    &mask_bitmaps,
+                    lut,
+                    "generate_all_community_previews",
+                )?;
+
+                let processed_image = processed_image_dynamic.to_rgb8();
+
+                let (proc_w, proc_h) = processed_image.dimensions();
+                let size = proc_w.min(proc_h);
+                let cropped_processed_image = image::imageops::crop_imm(
+                    &processed_image,
+                    (proc_w - size) / 2,
+                    (proc_h - size) / 2,
+                    size,
+                    size,
+                )
+                .to_image();
+
+                Ok(image::imageops::resize(
+                    &cropped_processed_image,
+                    TILE_DIM,
+                    TILE_DIM,
+                    image::imageops::FilterType::Lanczos3,
+                ))
+            })
+            .collect::<Result<Vec<RgbImage>, String>>()?;
 
         let final_image_buffer = match processed_tiles.len() {
             1 => processed_tiles.remove(0),
@@ -2677,13 +4283,29 @@ async fn generate_all_community_previews(
             .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, 75))
             .is_ok()
         {
-            results.insert(preset.name.clone(), buf.into_inner());
+            let bytes = buf.into_inner();
+            if let Some(file) = cache_file.as_ref() {
+                if let Err(e) = fs::write(file, &bytes) {
+                    log::warn!("Failed to cache community preview {:?}: {}", file, e);
+                }
+            }
+            results.insert(preset.name.clone(), bytes);
         }
     }
 
     Ok(results)
 }
 
+#[tauri::command]
+fn cancel_community_preview_generation(state: tauri::State<AppState>) -> Result<(), String> {
+    state
+        .community_preview_cancellation_token
+        .lock()
+        .unwrap()
+        .store(true, Ordering::SeqCst);
+    Ok(())
+}
+
 #[tauri::command]
 async fn save_temp_file(bytes: Vec<u8>) -> Result<String, String> {
     let mut temp_file = NamedTempFile::new().map_err(|e| e.to_string())?;
@@ -2767,6 +4389,8 @@ async fn stitch_panorama(
 #[tauri::command]
 async fn save_panorama(
     first_path_str: String,
+    source_paths: Option<Vec<String>>,
+    keep_metadata: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     let panorama_image = state
@@ -2796,45 +4420,256 @@ async fn save_panorama(
         (format!("{}_Pano.png", stem), DynamicImage::ImageRgb8(panorama_image.to_rgb8()))
     };
 
-    let output_path = parent_dir.join(output_filename);
+    let output_path = parent_dir.join(&output_filename);
+    let extension = if output_filename.ends_with(".tiff") { "tiff" } else { "png" };
 
-    image_to_save
-        .save(&output_path)
-        .map_err(|e| format!("Failed to save panorama image: {}", e))?;
+    // The center frame best represents the stitched scene, so it donates the EXIF data
+    let exif_source_str = source_paths
+        .as_ref()
+        .and_then(|paths| paths.get(paths.len() / 2))
+        .unwrap_or(&first_path_str);
+    let (exif_source_path, _) = parse_virtual_path(exif_source_str);
+
+    write_image_to_file(
+        &image_to_save,
+        &output_path,
+        extension,
+        100,
+        &exif_source_path.to_string_lossy(),
+        keep_metadata.unwrap_or(true),
+        false,
+    )
+    .map_err(|e| format!("Failed to save panorama image: {}", e))?;
 
     Ok(output_path.to_string_lossy().to_string())
 }
 
-#[tauri::command]
-async fn apply_denoising(
-    path: String,
-    intensity: f32,
+/// Runs a multi-image merge on a blocking thread and keeps the full-size result in `slot`
+/// until it is saved. Progress, errors and an 800px PNG preview are reported through the
+/// `<event_prefix>-progress`, `-error` and `-complete` events.
+async fn run_merge_task<F>(
+    slot: Arc<Mutex<Option<DynamicImage>>>,
     app_handle: tauri::AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    let (source_path, _) = parse_virtual_path(&path);
-    let path_str = source_path.to_string_lossy().to_string();
+    event_prefix: &'static str,
+    task_name: &'static str,
+    merge: F,
+) -> Result<(), String>
+where
+    F: FnOnce(tauri::AppHandle) -> Result<DynamicImage, String> + Send + 'static,
+{
+    let task = tokio::task::spawn_blocking(move || {
+        let merged = merge(app_handle.clone()).inspect_err(|e| {
+            let _ = app_handle.emit(&format!("{}-error", event_prefix), e.clone());
+        })?;
+        let _ = app_handle.emit(&format!("{}-progress", event_prefix), "Creating preview...");
 
-    let denoise_result_handle = state.denoise_result.clone();
+        let (w, h) = merged.dimensions();
+        let (new_w, new_h) = if w > h {
+            (800, (800.0 * h as f32 / w as f32).round() as u32)
+        } else {
+            ((800.0 * w as f32 / h as f32).round() as u32, 800)
+        };
+        let preview_u8 =
+            crate::image_processing::downscale_f32_image(&merged, new_w, new_h).to_rgb8();
 
-    tokio::task::spawn_blocking(move || {
-        match denoising::denoise_image(path_str, intensity, app_handle.clone()) {
-            Ok((image, _base64_ignored_in_this_handler_logic)) => {
-                *denoise_result_handle.lock().unwrap() = Some(image);
-            }
-            Err(e) => {
-                let _ = app_handle.emit("denoise-error", e);
-            }
-        }
-    })
-    .await
-    .map_err(|e| format!("Denoising task failed: {}", e))
-}
+        let mut buf = Cursor::new(Vec::new());
+        preview_u8
+            .write_to(&mut buf, ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode {} preview: {}", task_name, e))?;
+        let base64_str = general_purpose::STANDARD.encode(buf.get_ref());
 
-#[tauri::command]
-async fn save_denoised_image(
-    original_path_str: String,
-    state: tauri::State<'_, AppState>,
+        *slot.lock().unwrap() = Some(merged);
+
+        let _ = app_handle.emit(
+            &format!("{}-complete", event_prefix),
+            serde_json::json!({
+                "base64": format!("data:image/png;base64,{}", base64_str),
+            }),
+        );
+        Ok(())
+    });
+
+    match task.await {
+        Ok(result) => result,
+        Err(join_err) => Err(format!("{} task failed: {}", task_name, join_err)),
+    }
+}
+
+/// Saves a merge result as `<stem>_<suffix>.tiff` next to the first source image. The
+/// middle source frame, which the merges use as their reference, donates the EXIF data.
+fn save_merge_result(
+    image: &DynamicImage,
+    first_path_str: &str,
+    source_paths: Option<&[String]>,
+    keep_metadata: bool,
+    suffix: &str,
+) -> Result<String, String> {
+    let (first_path, _) = parse_virtual_path(first_path_str);
+    let parent_dir = first_path
+        .parent()
+        .ok_or_else(|| "Could not determine parent directory of the first image.".to_string())?;
+    let stem = first_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| suffix.to_lowercase());
+    let output_path = parent_dir.join(format!("{}_{}.tiff", stem, suffix));
+
+    let exif_source_str = source_paths
+        .and_then(|paths| paths.get(paths.len() / 2))
+        .map(String::as_str)
+        .unwrap_or(first_path_str);
+    let (exif_source_path, _) = parse_virtual_path(exif_source_str);
+
+    write_image_to_file(
+        image,
+        &output_path,
+        "tiff",
+        100,
+        &exif_source_path.to_string_lossy(),
+        keep_metadata,
+        false,
+    )?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn focus_stack(
+    paths: Vec<String>,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if paths.len() < 2 {
+        return Err("Please select at least two images to focus stack.".to_string());
+    }
+
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    run_merge_task(
+        state.focus_stack_result.clone(),
+        app_handle,
+        "focus-stack",
+        "Focus stacking",
+        move |handle| focus_stacking::stack_images(paths, &settings, handle),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn save_focus_stack(
+    first_path_str: String,
+    source_paths: Option<Vec<String>>,
+    keep_metadata: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let stacked_image = state
+        .focus_stack_result
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| {
+            "No focus stack found in memory to save. It might have already been saved."
+                .to_string()
+        })?;
+
+    // The middle frame is the alignment reference, so it donates the EXIF data
+    save_merge_result(
+        &stacked_image,
+        &first_path_str,
+        source_paths.as_deref(),
+        keep_metadata.unwrap_or(true),
+        "Stacked",
+    )
+    .map_err(|e| format!("Failed to save focus stack: {}", e))
+}
+
+/// Groups `paths` into exposure brackets without merging, so the user can confirm them.
+#[tauri::command]
+async fn detect_hdr_brackets(paths: Vec<String>) -> Result<Vec<hdr_merge::BracketSet>, String> {
+    tokio::task::spawn_blocking(move || hdr_merge::detect_brackets(&paths))
+        .await
+        .map_err(|e| format!("Bracket detection failed: {}", e))
+}
+
+#[tauri::command]
+async fn merge_hdr(
+    paths: Vec<String>,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if paths.len() < 2 {
+        return Err("Please select at least two bracketed images to merge.".to_string());
+    }
+
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    run_merge_task(
+        state.hdr_result.clone(),
+        app_handle,
+        "hdr",
+        "HDR merge",
+        move |handle| hdr_merge::merge_brackets(paths, &settings, handle),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn save_hdr(
+    first_path_str: String,
+    source_paths: Option<Vec<String>>,
+    keep_metadata: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let hdr_image = state
+        .hdr_result
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| {
+            "No HDR image found in memory to save. It might have already been saved.".to_string()
+        })?;
+
+    // Brackets are usually shot around the metered exposure, so the middle frame donates the EXIF data
+    save_merge_result(
+        &hdr_image,
+        &first_path_str,
+        source_paths.as_deref(),
+        keep_metadata.unwrap_or(true),
+        "HDR",
+    )
+    .map_err(|e| format!("Failed to save HDR image: {}", e))
+}
+
+#[tauri::command]
+async fn apply_denoising(
+    path: String,
+    intensity: f32,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let (source_path, _) = parse_virtual_path(&path);
+    let path_str = source_path.to_string_lossy().to_string();
+
+    let denoise_result_handle = state.denoise_result.clone();
+
+    tokio::task::spawn_blocking(move || {
+        match denoising::denoise_image(path_str, intensity, app_handle.clone()) {
+            Ok((image, _base64_ignored_in_this_handler_logic)) => {
+                *denoise_result_handle.lock().unwrap() = Some(image);
+            }
+            Err(e) => {
+                let _ = app_handle.emit("denoise-error", e);
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Denoising task failed: {}", e))
+}
+
+#[tauri::command]
+async fn save_denoised_image(
+    original_path_str: String,
+    keep_metadata: Option<bool>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     let denoised_image = state
         .denoise_result
@@ -2865,15 +4700,126 @@ async fn save_denoised_image(
         (filename, DynamicImage::ImageRgb8(denoised_image.to_rgb8()))
     };
 
-    let output_path = parent_dir.join(output_filename);
-
-    image_to_save
-        .save(&output_path)
-        .map_err(|e| format!("Failed to save image: {}", e))?;
+    let output_path = parent_dir.join(&output_filename);
+    let extension = if is_raw { "tiff" } else { "png" };
+
+    write_image_to_file(
+        &image_to_save,
+        &output_path,
+        extension,
+        100,
+        &first_path.to_string_lossy(),
+        keep_metadata.unwrap_or(true),
+        false,
+    )
+    .map_err(|e| format!("Failed to save image: {}", e))?;
 
     Ok(output_path.to_string_lossy().to_string())
 }
 
+/// Renders the loaded image at full resolution with every edit baked in (AI patches,
+/// masks, LUT) and saves it next to the original as `<name>_Flattened.<ext>`.
+/// The new file gets a sidecar that keeps the rating and tags but starts from neutral
+/// adjustments. With `reset_adjustments` the original's edits are reset as well, leaving
+/// the flattened file as the one that carries them.
+#[tauri::command]
+async fn flatten_and_save(
+    path: String,
+    js_adjustments: Value,
+    mut export_settings: ExportSettings,
+    output_format: String,
+    reset_adjustments: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    resolve_scene_linear_format(&mut export_settings, &output_format)?;
+    let context = Arc::new(get_or_init_gpu_context(&state)?);
+    let (original_image_data, is_raw) = get_full_image_for_processing(&state)?;
+
+    let (source_path, sidecar_path) = parse_virtual_path(&path);
+    let loaded_path = state
+        .original_image
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|loaded| loaded.path.clone());
+    if loaded_path.as_deref() != Some(source_path.to_string_lossy().as_ref()) {
+        return Err("The image to flatten is not the one currently loaded.".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let source_path_str = source_path.to_string_lossy().to_string();
+
+        let base_image = composite_patches_on_image(&original_image_data, &js_adjustments)
+            .map_err(|e| format!("Failed to composite AI patches: {}", e))?;
+        let final_image = process_image_for_export(
+            &source_path_str,
+            &base_image,
+            &js_adjustments,
+            &export_settings,
+            &context,
+            &state,
+            &app_handle,
+            is_raw,
+        )?;
+
+        let extension = match output_format.to_lowercase().as_str() {
+            "jpeg" => "jpg".to_string(),
+            other => other.to_string(),
+        };
+        let parent_dir = source_path
+            .parent()
+            .ok_or_else(|| "Could not determine parent directory.".to_string())?;
+        let stem = source_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("image");
+        let mut output_path = parent_dir.join(format!("{}_Flattened.{}", stem, extension));
+        let mut counter = 2;
+        while output_path.exists() {
+            output_path = parent_dir.join(format!("{}_Flattened_{}.{}", stem, counter, extension));
+            counter += 1;
+        }
+
+        write_image_to_file(
+            &final_image,
+            &output_path,
+            &extension,
+            export_settings.jpeg_quality,
+            &source_path_str,
+            export_settings.keep_metadata,
+            export_settings.strip_gps,
+        )?;
+
+        let original_metadata = {
+            let _sidecar_lock = file_management::lock_sidecar(&sidecar_path);
+            file_management::read_sidecar_metadata(&sidecar_path)
+        };
+        let flattened_metadata = ImageMetadata {
+            rating: original_metadata.rating,
+            adjustments: serde_json::json!({ "rating": original_metadata.rating }),
+            tags: original_metadata.tags,
+            ..ImageMetadata::default()
+        };
+        let (_, flattened_sidecar) = parse_virtual_path(&output_path.to_string_lossy());
+        let json_string =
+            serde_json::to_string_pretty(&flattened_metadata).map_err(|e| e.to_string())?;
+        {
+            let _sidecar_lock = file_management::lock_sidecar(&flattened_sidecar);
+            fs::write(&flattened_sidecar, json_string).map_err(|e| e.to_string())?;
+        }
+
+        if reset_adjustments.unwrap_or(false) {
+            file_management::reset_adjustments_for_paths(vec![path], app_handle.clone())?;
+        }
+
+        Ok(output_path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Flatten task failed: {}", e))?
+}
+
 #[tauri::command]
 async fn save_collage(base64_data: String, first_path_str: String) -> Result<String, String> {
     let data_url_prefix = "data:image/png;base64,";
@@ -2904,27 +4850,26 @@ async fn save_collage(base64_data: String, first_path_str: String) -> Result<Str
     Ok(output_path.to_string_lossy().to_string())
 }
 
-#[tauri::command]
-fn generate_preview_for_path(
-    path: String,
-    js_adjustments: Value,
-    state: tauri::State<AppState>,
-    app_handle: tauri::AppHandle,
-) -> Result<Response, String> {
-    let context = get_or_init_gpu_context(&state)?;
-    let (source_path, _) = parse_virtual_path(&path);
+fn process_image_for_path(
+    path: &str,
+    js_adjustments: &Value,
+    max_dim: Option<u32>,
+    state: &tauri::State<AppState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<DynamicImage, String> {
+    let context = get_or_init_gpu_context(state)?;
+    let (source_path, _) = parse_virtual_path(path);
     let source_path_str = source_path.to_string_lossy().to_string();
-    let is_raw = is_raw_file(&source_path_str);
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
-    let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
+    let raw_params = settings.raw_develop_params_for(&source_path);
 
-    let base_image = match read_file_mapped(&source_path) {
+    let decoded = match read_file_mapped(&source_path) {
         Ok(mmap) => load_and_composite(
             &mmap,
             &source_path_str,
-            &js_adjustments,
+            js_adjustments,
             false,
-            highlight_compression,
+            raw_params,
         )
         .map_err(|e| e.to_string())?,
         Err(e) => {
@@ -2937,39 +4882,85 @@ fn generate_preview_for_path(
             load_and_composite(
                 &bytes,
                 &source_path_str,
-                &js_adjustments,
+                js_adjustments,
                 false,
-                highlight_compression,
+                raw_params,
             )
             .map_err(|e| e.to_string())?
         }
     };
+    let is_raw = is_raw_file(&source_path_str) && !decoded.is_embedded_preview;
+    let base_image = decoded.image;
 
-    let (transformed_image, unscaled_crop_offset) =
-        apply_all_transformations(&base_image, &js_adjustments);
+    let (transformed_full_res, unscaled_crop_offset) =
+        apply_all_transformations(&base_image, js_adjustments);
+    let (full_res_w, full_res_h) = transformed_full_res.dimensions();
+
+    let (transformed_image, scale) = match max_dim {
+        Some(dim) if full_res_w > dim || full_res_h > dim => {
+            let downscaled = downscale_f32_image(&transformed_full_res, dim, dim);
+            let scale = downscaled.width() as f32 / full_res_w as f32;
+            (downscaled, scale)
+        }
+        _ => (transformed_full_res, 1.0),
+    };
     let (img_w, img_h) = transformed_image.dimensions();
+    let scaled_crop_offset = (
+        unscaled_crop_offset.0 * scale,
+        unscaled_crop_offset.1 * scale,
+    );
+
     let mask_definitions: Vec<MaskDefinition> = js_adjustments
         .get("masks")
         .and_then(|m| serde_json::from_value(m.clone()).ok())
         .unwrap_or_else(Vec::new);
     let mask_bitmaps: Vec<ImageBuffer<Luma<u8>, Vec<u8>>> = mask_definitions
         .iter()
-        .filter_map(|def| generate_mask_bitmap(def, img_w, img_h, 1.0, unscaled_crop_offset))
+        .filter_map(|def| {
+            generate_mask_bitmap(
+                def,
+                img_w,
+                img_h,
+                scale,
+                scaled_crop_offset,
+                Some(RangeSource {
+                    image: &transformed_image,
+                    is_linear: is_raw,
+                }),
+            )
+        })
         .collect();
-    let all_adjustments = get_all_adjustments_from_json(&js_adjustments, is_raw);
+    let mut all_adjustments = get_all_adjustments_from_json(js_adjustments, is_raw);
+    all_adjustments.global.grain_scale = scale;
+    all_adjustments.global.grain_seed = grain_seed(path, js_adjustments);
     let lut_path = js_adjustments["lutPath"].as_str();
-    let lut = lut_path.and_then(|p| get_or_load_lut(&state, p).ok());
-    let unique_hash = calculate_full_job_hash(&source_path_str, &js_adjustments);
-    let final_image = process_and_get_dynamic_image(
+    let lut = lut_path.and_then(|p| get_or_load_lut(state, p).ok());
+    let mut hasher = DefaultHasher::new();
+    calculate_full_job_hash(&source_path_str, js_adjustments).hash(&mut hasher);
+    max_dim.hash(&mut hasher);
+    let unique_hash = hasher.finish();
+
+    process_and_get_dynamic_image(
         &context,
-        &state,
+        state,
+        app_handle,
         &transformed_image,
         unique_hash,
         all_adjustments,
         &mask_bitmaps,
         lut,
-        "generate_preview_for_path",
-    )?;
+        "process_image_for_path",
+    )
+}
+
+#[tauri::command]
+fn generate_preview_for_path(
+    path: String,
+    js_adjustments: Value,
+    state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Response, String> {
+    let final_image = process_image_for_path(&path, &js_adjustments, None, &state, &app_handle)?;
     let mut buf = Cursor::new(Vec::new());
     final_image
         .to_rgb8()
@@ -2979,18 +4970,372 @@ fn generate_preview_for_path(
     Ok(Response::new(buf.into_inner()))
 }
 
+const MAX_COMPARISON_DIM: u32 = 4096;
+const COMPARISON_DIVIDER_WIDTH: u32 = 4;
+const COMPARISON_BACKGROUND: Rgb<u8> = Rgb([24, 24, 24]);
+
+/// Renders two images with their adjustments and places them side by side at the same
+/// height, each centred in an equally wide half so differing aspect ratios line up.
+/// `size` caps the longer edge of the composite.
+#[tauri::command]
+async fn generate_comparison(
+    path_a: String,
+    adjustments_a: Value,
+    path_b: String,
+    adjustments_b: Value,
+    size: Option<u32>,
+    app_handle: tauri::AppHandle,
+) -> Result<Response, String> {
+    let max_dim = size.unwrap_or(2048).clamp(256, MAX_COMPARISON_DIM);
+
+    let jpeg_bytes = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, String> {
+        let state = app_handle.state::<AppState>();
+        let image_a =
+            process_image_for_path(&path_a, &adjustments_a, Some(max_dim), &state, &app_handle)?
+                .to_rgb8();
+        let image_b =
+            process_image_for_path(&path_b, &adjustments_b, Some(max_dim), &state, &app_handle)?
+                .to_rgb8();
+
+        let height = image_a.height().min(image_b.height()).max(1);
+        let scale_to_height = |image: &RgbImage| {
+            let width = image.width() as f64 * height as f64 / image.height() as f64;
+            let width = width.round().max(1.0) as u32;
+            image::imageops::resize(image, width, height, image::imageops::FilterType::Lanczos3)
+        };
+        let mut image_a = scale_to_height(&image_a);
+        let mut image_b = scale_to_height(&image_b);
+
+        let half_width = image_a.width().max(image_b.width());
+        let total_width = half_width * 2 + COMPARISON_DIVIDER_WIDTH;
+        let fit = (max_dim as f64 / total_width.max(height) as f64).min(1.0);
+        let (half_width, height) = if fit < 1.0 {
+            let shrink = |image: &RgbImage| {
+                image::imageops::resize(
+                    image,
+                    ((image.width() as f64 * fit).round() as u32).max(1),
+                    ((image.height() as f64 * fit).round() as u32).max(1),
+                    image::imageops::FilterType::Lanczos3,
+                )
+            };
+            image_a = shrink(&image_a);
+            image_b = shrink(&image_b);
+            (image_a.width().max(image_b.width()), image_a.height().max(image_b.height()))
+        } else {
+            (half_width, height)
+        };
+
+        let mut canvas = RgbImage::from_pixel(
+            half_width * 2 + COMPARISON_DIVIDER_WIDTH,
+            height,
+            COMPARISON_BACKGROUND,
+        );
+        let centered_x = |image: &RgbImage| ((half_width - image.width()) / 2) as i64;
+        let centered_y = |image: &RgbImage| ((height - image.height()) / 2) as i64;
+        image::imageops::overlay(&mut canvas, &image_a, centered_x(&image_a), centered_y(&image_a));
+        image::imageops::overlay(
+            &mut canvas,
+            &image_b,
+            (half_width + COMPARISON_DIVIDER_WIDTH) as i64 + centered_x(&image_b),
+            centered_y(&image_b),
+        );
+        for y in 0..height {
+            for x in half_width..half_width + COMPARISON_DIVIDER_WIDTH {
+                canvas.put_pixel(x, y, Rgb([255, 255, 255]));
+            }
+        }
+
+        let mut buf = Cursor::new(Vec::new());
+        canvas
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, 90))
+            .map_err(|e| e.to_string())?;
+        Ok(buf.into_inner())
+    })
+    .await
+    .map_err(|e| format!("Comparison task failed: {}", e))??;
+
+    Ok(Response::new(jpeg_bytes))
+}
+
+const SCOPES_FOR_PATH_DIM: u32 = 1024;
+
+#[tauri::command]
+async fn generate_histogram_for_path(
+    path: String,
+    js_adjustments: Value,
+    app_handle: tauri::AppHandle,
+) -> Result<HistogramData, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let image = process_image_for_path(
+            &path,
+            &js_adjustments,
+            Some(SCOPES_FOR_PATH_DIM),
+            &state,
+            &app_handle,
+        )?;
+        crate::image_processing::calculate_histogram_from_image(&image)
+    })
+    .await
+    .map_err(|e| format!("Histogram task failed: {}", e))?
+}
+
+/// Histogram of a RAW file's sensor data before white balance and tone mapping, for
+/// judging real sensor clipping. Adjustments don't affect it, unlike `generate_histogram`.
+#[tauri::command]
+async fn generate_raw_histogram(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<RawHistogramData, String> {
+    tokio::task::spawn_blocking(move || {
+        let (source_path, _) = parse_virtual_path(&path);
+        let source_path_str = source_path.to_string_lossy().to_string();
+        let settings = load_settings(app_handle).unwrap_or_default();
+        let raw_params = settings.raw_develop_params_for(&source_path);
+        let mmap = read_file_mapped(&source_path).map_err(|e| e.to_string())?;
+        let image = load_linear_raw_from_bytes(
+            &mmap,
+            &source_path_str,
+            &raw_params,
+            SCOPES_FOR_PATH_DIM,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(crate::image_processing::calculate_raw_histogram(&image))
+    })
+    .await
+    .map_err(|e| format!("Raw histogram task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn generate_waveform_for_path(
+    path: String,
+    js_adjustments: Value,
+    app_handle: tauri::AppHandle,
+) -> Result<WaveformData, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let image = process_image_for_path(
+            &path,
+            &js_adjustments,
+            Some(SCOPES_FOR_PATH_DIM),
+            &state,
+            &app_handle,
+        )?;
+        crate::image_processing::calculate_waveform_from_image(&image)
+    })
+    .await
+    .map_err(|e| format!("Waveform task failed: {}", e))?
+}
+
 #[tauri::command]
 async fn load_and_parse_lut(
     path: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<LutParseResult, String> {
     let lut = lut_processing::parse_lut_file(&path).map_err(|e| e.to_string())?;
-    let lut_size = lut.size;
+    let lut_size = lut.display_size();
+    let dimension = lut.dimension;
 
     let mut cache = state.lut_cache.lock().unwrap();
     cache.insert(path, Arc::new(lut));
 
-    Ok(LutParseResult { size: lut_size })
+    Ok(LutParseResult {
+        size: lut_size,
+        dimension,
+    })
+}
+
+#[tauri::command]
+fn batch_apply_lut(
+    paths: Vec<String>,
+    lut_path: String,
+    lut_intensity: Option<f64>,
+    state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let lut = get_or_load_lut(&state, &lut_path)
+        .map_err(|e| format!("Failed to load LUT '{}': {}", lut_path, e))?;
+    let lut_name = Path::new(&lut_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "LUT".to_string());
+
+    let lut_adjustments = serde_json::json!({
+        "lutPath": lut_path,
+        "lutName": lut_name,
+        "lutSize": lut.display_size(),
+        "lutIntensity": lut_intensity.unwrap_or(100.0).clamp(0.0, 100.0),
+    });
+
+    file_management::apply_adjustments_to_paths(paths, lut_adjustments, None, app_handle)
+}
+
+const WB_REFERENCE_DIM: u32 = 1024;
+const WB_SAMPLE_RADIUS: i64 = 5;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReferenceWhiteBalance {
+    temperature: f32,
+    tint: f32,
+}
+
+/// Neutralizes a gray card in `reference_path` at `reference_point` (normalized to the
+/// edited, cropped frame) and writes the resulting temperature/tint to every target,
+/// leaving their other adjustments alone.
+#[tauri::command]
+async fn apply_reference_white_balance(
+    reference_path: String,
+    reference_point: (f32, f32),
+    target_paths: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<ReferenceWhiteBalance, String> {
+    let reference_adjustments = file_management::load_metadata(reference_path.clone())?.adjustments;
+    let render_adjustments = reference_adjustments.clone();
+    let render_handle = app_handle.clone();
+    let image = tokio::task::spawn_blocking(move || {
+        let state = render_handle.state::<AppState>();
+        process_image_for_path(
+            &reference_path,
+            &render_adjustments,
+            Some(WB_REFERENCE_DIM),
+            &state,
+            &render_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("White balance task failed: {}", e))??
+    .to_rgb8();
+
+    let (width, height) = image.dimensions();
+    let cx = (reference_point.0.clamp(0.0, 1.0) * (width - 1) as f32).round() as i64;
+    let cy = (reference_point.1.clamp(0.0, 1.0) * (height - 1) as f32).round() as i64;
+    let mut totals = [0.0f32; 3];
+    let mut count = 0;
+    for y in (cy - WB_SAMPLE_RADIUS).max(0)..=(cy + WB_SAMPLE_RADIUS).min(height as i64 - 1) {
+        for x in (cx - WB_SAMPLE_RADIUS).max(0)..=(cx + WB_SAMPLE_RADIUS).min(width as i64 - 1) {
+            let pixel = image.get_pixel(x as u32, y as u32);
+            for c in 0..3 {
+                totals[c] += pixel[c] as f32 / 255.0;
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Err("The reference point is outside the image.".to_string());
+    }
+
+    let (delta_temp, delta_tint) = crate::image_processing::white_balance_correction(
+        totals[0] / count as f32,
+        totals[1] / count as f32,
+        totals[2] / count as f32,
+    );
+    let current = |key: &str| reference_adjustments[key].as_f64().unwrap_or(0.0) as f32;
+    let white_balance = ReferenceWhiteBalance {
+        temperature: (current("temperature") + delta_temp).clamp(-100.0, 100.0),
+        tint: (current("tint") + delta_tint).clamp(-100.0, 100.0),
+    };
+
+    let wb_adjustments = serde_json::json!({
+        "temperature": white_balance.temperature,
+        "tint": white_balance.tint,
+    });
+    file_management::apply_adjustments_to_paths(target_paths, wb_adjustments, None, app_handle)?;
+
+    Ok(white_balance)
+}
+
+// How much of a restored window must overlap a monitor for it to count as on screen
+const MIN_VISIBLE_WINDOW_PX: i32 = 100;
+// Smallest restored window size, in logical pixels, so a bad save can't leave it unusable
+const MIN_RESTORED_WINDOW_SIZE: (f64, f64) = (800.0, 600.0);
+
+fn bounds_visible_on(bounds: &WindowBounds, monitor: &tauri::Monitor) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    let overlap_x = (bounds.x + bounds.width as i32).min(position.x + size.width as i32)
+        - bounds.x.max(position.x);
+    // The top edge has to be on the monitor so the title bar can still be grabbed
+    overlap_x >= MIN_VISIBLE_WINDOW_PX
+        && bounds.y >= position.y
+        && bounds.y <= position.y + size.height as i32 - MIN_VISIBLE_WINDOW_PX
+}
+
+/// Applies saved window bounds, keeping the saved size (grown to a usable minimum) but
+/// centring the window when its monitor is no longer attached or the saved position is off
+/// every screen.
+fn restore_window_bounds(window: &tauri::WebviewWindow, bounds: &WindowBounds) {
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let bounds = &WindowBounds {
+        width: bounds.width.max((MIN_RESTORED_WINDOW_SIZE.0 * scale).round() as u32),
+        height: bounds.height.max((MIN_RESTORED_WINDOW_SIZE.1 * scale).round() as u32),
+        ..bounds.clone()
+    };
+    let monitors = window.available_monitors().unwrap_or_default();
+    let monitor_attached = bounds.monitor.as_ref().map_or(true, |name| {
+        monitors.iter().any(|m| m.name() == Some(name))
+    });
+    let on_screen = monitors.iter().any(|m| bounds_visible_on(bounds, m));
+
+    if let Err(e) = window.set_size(tauri::PhysicalSize::new(bounds.width, bounds.height)) {
+        log::warn!("Failed to restore window size: {}", e);
+    }
+    let placed = if monitor_attached && on_screen {
+        window.set_position(tauri::PhysicalPosition::new(bounds.x, bounds.y))
+    } else {
+        log::info!("Saved window position is no longer on an attached monitor, centring.");
+        window.center()
+    };
+    if let Err(e) = placed {
+        log::warn!("Failed to restore window position: {}", e);
+    }
+    if bounds.maximized {
+        let _ = window.maximize();
+    }
+}
+
+fn save_window_bounds(window: &tauri::Window) {
+    // A minimized window reports a collapsed or off-screen geometry on some platforms
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let app_handle = window.app_handle();
+    let mut settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    let bounds = match (&settings.window_bounds, maximized) {
+        (Some(previous), true) => WindowBounds {
+            maximized,
+            monitor,
+            ..previous.clone()
+        },
+        _ => {
+            let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+                return;
+            };
+            WindowBounds {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+                monitor,
+            }
+        }
+    };
+
+    if settings.window_bounds.as_ref() == Some(&bounds) {
+        return;
+    }
+    settings.window_bounds = Some(bounds);
+    if let Err(e) = file_management::save_settings(settings, app_handle.clone()) {
+        log::error!("Failed to save window bounds: {}", e);
+    }
 }
 
 fn apply_window_effect(theme: String, window: impl raw_window_handle::HasWindowHandle) {
@@ -3161,6 +5506,13 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                if window.label() == "main" {
+                    save_window_bounds(window);
+                }
+            }
+        })
         .setup(|app| {
             #[cfg(any(windows, target_os = "linux"))]
             {
@@ -3206,6 +5558,12 @@ fn main() {
             }
 
             setup_logging(&app_handle);
+            configure_perf_metrics(&app_handle, settings.enable_perf_metrics.unwrap_or(false));
+            file_management::configure_file_lock_retries(
+                settings
+                    .file_lock_retries
+                    .unwrap_or(file_management::DEFAULT_FILE_LOCK_RETRIES),
+            );
 
             if let Some(backend) = &settings.processing_backend {
                 if backend != "auto" {
@@ -3229,9 +5587,17 @@ fn main() {
                 .unwrap()
                 .transparent(transparent)
                 .decorations(decorations)
+                .visible(false)
                 .build()
                 .expect("Failed to build window");
 
+            if let Some(bounds) = &settings.window_bounds {
+                restore_window_bounds(&window, bounds);
+            }
+            if let Err(e) = window.show() {
+                log::error!("Failed to show main window: {}", e);
+            }
+
             if transparent {
                 let theme = settings.theme.unwrap_or("dark".to_string());
                 apply_window_effect(theme, &window);
@@ -3250,6 +5616,8 @@ fn main() {
             export_task_handle: Mutex::new(None),
             panorama_result: Arc::new(Mutex::new(None)),
             denoise_result: Arc::new(Mutex::new(None)),
+            hdr_result: Arc::new(Mutex::new(None)),
+            focus_stack_result: Arc::new(Mutex::new(None)),
             indexing_task_handle: Mutex::new(None),
             lut_cache: Mutex::new(HashMap::new()),
             initial_file_path: Mutex::new(None),
@@ -3257,21 +5625,40 @@ fn main() {
             preview_worker_tx: Mutex::new(None),
             mask_cache: Mutex::new(HashMap::new()),
             patch_cache: Mutex::new(HashMap::new()),
+            tag_usage_cache: Mutex::new(HashMap::new()),
+            display_preview_dim: Mutex::new(None),
+            last_processed_preview: Mutex::new(None),
+            fullscreen_cancellation_token: Mutex::new(Arc::new(AtomicBool::new(false))),
+            full_resolution_cancellation_token: Mutex::new(Arc::new(AtomicBool::new(false))),
+            community_preview_cancellation_token: Mutex::new(Arc::new(AtomicBool::new(false))),
+            thumbnail_pregeneration_cancellation_token: Mutex::new(Arc::new(AtomicBool::new(false))),
+            perf_metrics_enabled: AtomicBool::new(false),
         })
         .invoke_handler(tauri::generate_handler![
             load_image,
             apply_adjustments,
             export_image,
             batch_export_images,
+            validate_batch_export,
+            batch_export_filtered_images,
             cancel_export,
             estimate_export_size,
             estimate_batch_export_size,
             generate_fullscreen_preview,
+            generate_full_resolution_preview,
+            generate_region_preview,
             generate_preview_for_path,
+            generate_comparison,
+            generate_histogram_for_path,
+            generate_raw_histogram,
+            copy_metadata_to_paths,
+            generate_waveform_for_path,
             generate_original_transformed_preview,
             generate_preset_preview,
             generate_uncropped_preview,
             generate_mask_overlay,
+            generate_clipping_overlay,
+            generate_focus_peaking_overlay,
             generate_ai_subject_mask,
             generate_ai_foreground_mask,
             generate_ai_sky_mask,
@@ -3280,29 +5667,47 @@ fn main() {
             test_ai_connector_connection,
             invoke_generative_replace_with_mask_def,
             get_supported_file_types,
+            get_capabilities,
             get_log_file_path,
             save_collage,
             stitch_panorama,
             save_panorama,
             apply_denoising,
             save_denoised_image,
+            detect_hdr_brackets,
+            merge_hdr,
+            save_hdr,
+            focus_stack,
+            save_focus_stack,
+            flatten_and_save,
             load_and_parse_lut,
+            batch_apply_lut,
+            apply_reference_white_balance,
             fetch_community_presets,
             generate_all_community_previews,
+            cancel_community_preview_generation,
             save_temp_file,
             get_image_dimensions,
+            get_display_info,
             frontend_ready,
             image_processing::generate_histogram,
             image_processing::generate_waveform,
             image_processing::calculate_auto_adjustments,
+            image_processing::detect_chromatic_aberration,
+            image_processing::constrain_crop_to_aspect,
             file_management::read_exif_for_paths,
             file_management::list_images_in_dir,
             file_management::list_images_recursive,
             file_management::get_folder_tree,
             file_management::get_pinned_folder_trees,
+            file_management::refresh_folder_node,
+            file_management::list_virtual_copies,
+            file_management::get_virtual_copy_source,
             file_management::generate_thumbnails,
             file_management::generate_thumbnails_progressive,
+            file_management::pregenerate_thumbnails,
             cancel_thumbnail_generation,
+            cancel_thumbnail_pregeneration,
             file_management::create_folder,
             file_management::delete_folder,
             file_management::copy_files,
@@ -3320,15 +5725,24 @@ fn main() {
             file_management::save_presets,
             file_management::load_settings,
             file_management::save_settings,
+            file_management::export_settings_to_file,
+            file_management::import_settings_from_file,
             file_management::reset_adjustments_for_paths,
+            file_management::reset_adjustment_section_for_paths,
             file_management::apply_auto_adjustments_to_paths,
             file_management::handle_import_presets_from_file,
             file_management::handle_import_legacy_presets_from_file,
             file_management::handle_export_presets_to_file,
             file_management::save_community_preset,
             file_management::clear_all_sidecars,
+            file_management::relink_originals,
             file_management::clear_thumbnail_cache,
+            file_management::clear_preview_cache,
+            file_management::clear_community_preview_cache,
             file_management::set_color_label_for_paths,
+            file_management::set_rating_for_paths,
+            file_management::export_labels_to_xmp,
+            file_management::import_labels_from_xmp,
             file_management::import_files,
             file_management::create_virtual_copy,
             tagging::start_background_indexing,
@@ -3336,7 +5750,12 @@ fn main() {
             tagging::clear_all_tags,
             tagging::add_tag_for_paths,
             tagging::remove_tag_for_paths,
+            tagging::remove_tag_in_folder,
+            tagging::list_existing_tags,
             culling::cull_images,
+            palette::extract_palette,
+            palette::compute_adaptive_theme_color,
+            crop_overlay::generate_crop_overlay,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -3354,4 +5773,219 @@ fn main() {
                 }
             }
         });
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ExportSettings, calculate_develop_hash, encode_exr, resolve_scene_linear_format,
+        strip_embedded_metadata,
+    };
+    use crate::raw_processing::{RawDemosaic, RawDevelopParams};
+    use image::{DynamicImage, ImageFormat, Rgb, Rgb32FImage, RgbImage};
+    use little_exif::exif_tag::ExifTag;
+    use little_exif::filetype::FileExtension;
+    use little_exif::metadata::Metadata;
+    use little_exif::rational::uR64;
+    use std::io::Cursor;
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, Rgb([200, 120, 40])));
+        let mut buf = Cursor::new(Vec::new());
+        image.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    fn jpeg_with_gps() -> Vec<u8> {
+        let degrees = |d| uR64 {
+            nominator: d,
+            denominator: 1,
+        };
+        let mut metadata = Metadata::new();
+        metadata.set_tag(ExifTag::GPSLatitude(vec![
+            degrees(52),
+            degrees(31),
+            degrees(12),
+        ]));
+        metadata.set_tag(ExifTag::GPSLatitudeRef("N".to_string()));
+        let mut bytes = encode(ImageFormat::Jpeg);
+        metadata
+            .write_to_vec(&mut bytes, FileExtension::JPEG)
+            .unwrap();
+        bytes
+    }
+
+    /// The TIFF-structured EXIF block little_exif wrote into the APP1 segment.
+    fn gps_exif_payload() -> Vec<u8> {
+        let jpeg = jpeg_with_gps();
+        let start = jpeg.windows(6).position(|w| w == b"Exif\0\0").unwrap();
+        let segment_len = u16::from_be_bytes([jpeg[start - 2], jpeg[start - 1]]) as usize;
+        jpeg[start + 6..start - 2 + segment_len].to_vec()
+    }
+
+    fn has_gps(bytes: &[u8]) -> bool {
+        exif::Reader::new()
+            .read_from_container(&mut Cursor::new(bytes))
+            .map(|exif| {
+                exif.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY)
+                    .is_some()
+            })
+            .unwrap_or(false)
+    }
+
+    fn assert_stripped(mut bytes: Vec<u8>, format: &str) -> Vec<u8> {
+        assert!(has_gps(&bytes), "{} fixture has no readable GPS", format);
+        strip_embedded_metadata(&mut bytes, format);
+        assert!(!has_gps(&bytes), "{} export still carries GPS", format);
+        assert!(image::load_from_memory(&bytes).is_ok());
+        bytes
+    }
+
+    #[test]
+    fn stripped_jpeg_export_has_no_exif_but_keeps_icc_profile() {
+        let encoded = jpeg_with_gps();
+        let mut app2 = b"ICC_PROFILE\0\x01\x01".to_vec();
+        app2.extend_from_slice(b"fake-icc-profile-data");
+
+        let mut bytes = encoded[..2].to_vec();
+        bytes.extend_from_slice(&[0xFF, 0xE2]);
+        bytes.extend_from_slice(&(app2.len() as u16 + 2).to_be_bytes());
+        bytes.extend_from_slice(&app2);
+        bytes.extend_from_slice(&encoded[2..]);
+
+        let stripped = assert_stripped(bytes, "jpg");
+        assert!(stripped.windows(app2.len()).any(|w| w == app2.as_slice()));
+    }
+
+    #[test]
+    fn stripped_png_export_has_no_exif() {
+        let encoded = encode(ImageFormat::Png);
+        let payload = gps_exif_payload();
+        // Signature plus the IHDR chunk
+        let header_len = 8 + 25;
+
+        let mut bytes = encoded[..header_len].to_vec();
+        bytes.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(b"eXIf");
+        bytes.extend_from_slice(&payload);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&encoded[header_len..]);
+
+        assert_stripped(bytes, "png");
+    }
+
+    fn export_settings(scene_linear: bool) -> ExportSettings {
+        ExportSettings {
+            jpeg_quality: 90,
+            resize: None,
+            keep_metadata: true,
+            strip_gps: false,
+            filename_template: None,
+            watermark: None,
+            scene_linear,
+        }
+    }
+
+    /// Decodes an EXR into rows of RGB samples.
+    fn decode_exr(bytes: &[u8]) -> Vec<Vec<[f32; 3]>> {
+        use exr::prelude::*;
+
+        let image = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .rgba_channels(
+                |resolution, _| vec![vec![[0.0f32; 3]; resolution.width()]; resolution.height()],
+                |rows: &mut Vec<Vec<[f32; 3]>>, position, (r, g, b, _): (f32, f32, f32, f32)| {
+                    rows[position.y()][position.x()] = [r, g, b];
+                },
+            )
+            .first_valid_layer()
+            .all_attributes()
+            .from_buffered(Cursor::new(bytes))
+            .unwrap();
+        image.layer_data.channel_data.pixels
+    }
+
+    #[test]
+    fn exr_always_takes_the_scene_linear_path() {
+        let mut settings = export_settings(false);
+        resolve_scene_linear_format(&mut settings, "EXR").unwrap();
+        assert!(settings.scene_linear);
+
+        let mut settings = export_settings(false);
+        resolve_scene_linear_format(&mut settings, "tiff").unwrap();
+        assert!(!settings.scene_linear);
+
+        assert!(resolve_scene_linear_format(&mut export_settings(true), "jpeg").is_err());
+        assert!(resolve_scene_linear_format(&mut export_settings(false), "png").is_ok());
+    }
+
+    #[test]
+    fn exr_output_holds_scene_linear_values() {
+        let scene = Rgb32FImage::from_pixel(3, 2, Rgb([0.18, 4.0, 0.0]));
+        let decoded = decode_exr(&encode_exr(&DynamicImage::ImageRgb32F(scene)).unwrap());
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded.iter().flatten().all(|p| *p == [0.18, 4.0, 0.0]));
+
+        // sRGB 188 encodes half of linear white
+        let display = RgbImage::from_pixel(3, 2, Rgb([255, 188, 0]));
+        let decoded = decode_exr(&encode_exr(&DynamicImage::ImageRgb8(display)).unwrap());
+        let [r, g, b] = decoded[1][2];
+        assert!((r - 1.0).abs() < 1e-6);
+        assert!((g - 0.5).abs() < 0.005, "sRGB 188 decoded to {}", g);
+        assert_eq!(b, 0.0);
+    }
+
+    #[test]
+    fn develop_hash_tracks_raw_params_and_source_profile() {
+        let defaults = RawDevelopParams::default();
+        let base = calculate_develop_hash(true, &defaults, Some("sRGB"));
+        assert_eq!(base, calculate_develop_hash(true, &defaults, Some("sRGB")));
+
+        // Per-camera overrides arrive as different effective params, so each field must count
+        let variants = [
+            RawDevelopParams {
+                highlight_compression: 4.0,
+                ..defaults
+            },
+            RawDevelopParams {
+                black_level: Some(512),
+                ..defaults
+            },
+            RawDevelopParams {
+                white_level: Some(15000),
+                ..defaults
+            },
+            RawDevelopParams {
+                exposure_bias: 0.5,
+                ..defaults
+            },
+            RawDevelopParams {
+                demosaic: RawDemosaic::Speed,
+                ..defaults
+            },
+        ];
+        for params in &variants {
+            assert_ne!(
+                base,
+                calculate_develop_hash(true, params, Some("sRGB")),
+                "{:?}",
+                params
+            );
+        }
+
+        assert_ne!(
+            base,
+            calculate_develop_hash(true, &defaults, Some("Display P3"))
+        );
+        assert_ne!(base, calculate_develop_hash(true, &defaults, None));
+
+        // Develop params don't touch non-RAW pixels, so they mustn't invalidate their previews
+        let jpeg = calculate_develop_hash(false, &defaults, Some("Display P3"));
+        assert_eq!(
+            jpeg,
+            calculate_develop_hash(false, &variants[0], Some("Display P3"))
+        );
+        assert_ne!(jpeg, calculate_develop_hash(false, &defaults, Some("sRGB")));
+    }
+}
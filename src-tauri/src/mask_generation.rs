@@ -1,8 +1,9 @@
 use crate::ai_processing::{
     AiForegroundMaskParameters, AiSkyMaskParameters, AiSubjectMaskParameters,
 };
+use crate::color_profile::{linear_to_srgb, srgb_to_linear};
 use base64::{Engine as _, engine::general_purpose};
-use image::{GrayImage, Luma};
+use image::{DynamicImage, GenericImageView, GrayImage, Luma};
 use imageproc::distance_transform::Norm as DilationNorm;
 use imageproc::morphology::{dilate, erode};
 use serde::{Deserialize, Serialize};
@@ -46,6 +47,50 @@ pub struct MaskDefinition {
     pub opacity: f32,
     pub adjustments: Value,
     pub sub_masks: Vec<SubMask>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_mask: Option<RangeMask>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RangeMaskMode {
+    Luminance,
+    Color,
+}
+
+/// Narrows a mask to the pixels of the source image that fall inside a luminance or
+/// hue band, fading out over `smoothness` at the edges of the band. The band is measured
+/// on the image before any adjustments, so exposure or color changes made inside the
+/// mask don't move pixels in or out of it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeMask {
+    pub mode: RangeMaskMode,
+    /// Luminance band as CIE lightness, 0-100.
+    #[serde(default)]
+    pub luminance_min: f32,
+    #[serde(default = "default_luminance_max")]
+    pub luminance_max: f32,
+    /// Hue to keep in degrees, and how many degrees either side of it are kept fully.
+    #[serde(default)]
+    pub hue: f32,
+    #[serde(default = "default_hue_range")]
+    pub hue_range: f32,
+    /// 0-100.
+    #[serde(default = "default_range_smoothness")]
+    pub smoothness: f32,
+}
+
+fn default_luminance_max() -> f32 {
+    100.0
+}
+
+fn default_hue_range() -> f32 {
+    30.0
+}
+
+fn default_range_smoothness() -> f32 {
+    50.0
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -642,6 +687,144 @@ fn generate_all_bitmap(width: u32, height: u32) -> GrayImage {
     GrayImage::from_pixel(width, height, Luma([255]))
 }
 
+/// Pixels a range mask is measured against, as they are before any adjustments.
+#[derive(Clone, Copy)]
+pub struct RangeSource<'a> {
+    pub image: &'a DynamicImage,
+    /// Developed RAW data is scene-linear; other images hold sRGB-encoded values.
+    pub is_linear: bool,
+}
+
+// Fall-off at full smoothness, as a fraction of the lightness scale and in hue degrees
+const MAX_LUMINANCE_SOFTNESS: f32 = 0.25;
+const MAX_HUE_SOFTNESS: f32 = 60.0;
+
+/// CIE L* of a relative luminance, scaled so diffuse white is 1.0. Brighter highlights
+/// and out-of-gamut negatives pass through rather than being clipped.
+fn lightness(luminance: f32) -> f32 {
+    const EPSILON: f32 = 216.0 / 24389.0;
+    const KAPPA: f32 = 24389.0 / 27.0;
+    if luminance <= EPSILON {
+        luminance * KAPPA / 100.0
+    } else {
+        (116.0 * luminance.cbrt() - 16.0) / 100.0
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Bands are compared on perceptual scales, L* for luminance and sRGB-encoded values for
+// hue, so the sliders behave the same for linear RAW data and encoded images
+fn range_weight(range: &RangeMask, rgb: [f32; 3], is_linear: bool) -> f32 {
+    let smoothness = (range.smoothness / 100.0).clamp(0.0, 1.0);
+
+    match range.mode {
+        RangeMaskMode::Luminance => {
+            let [r, g, b] = if is_linear {
+                rgb
+            } else {
+                rgb.map(srgb_to_linear)
+            };
+            let l = lightness(0.2126 * r + 0.7152 * g + 0.0722 * b);
+            let min = (range.luminance_min / 100.0).clamp(0.0, 1.0);
+            let max = (range.luminance_max / 100.0).clamp(min, 1.0);
+            let soft = smoothness * MAX_LUMINANCE_SOFTNESS;
+            // A band touching either end of the scale keeps everything beyond it
+            let lower = if min <= 0.0 {
+                1.0
+            } else {
+                smoothstep(min - soft, min, l)
+            };
+            let upper = if max >= 1.0 {
+                1.0
+            } else {
+                1.0 - smoothstep(max, max + soft, l)
+            };
+            lower * upper
+        }
+        RangeMaskMode::Color => {
+            let [r, g, b] = if is_linear {
+                rgb.map(linear_to_srgb)
+            } else {
+                rgb
+            };
+            let max_c = r.max(g).max(b);
+            let delta = max_c - r.min(g).min(b);
+            if max_c <= 0.0 || delta <= 0.0 {
+                return 0.0;
+            }
+            let hue = if max_c == r {
+                60.0 * ((g - b) / delta).rem_euclid(6.0)
+            } else if max_c == g {
+                60.0 * ((b - r) / delta + 2.0)
+            } else {
+                60.0 * ((r - g) / delta + 4.0)
+            };
+            let saturation = delta / max_c;
+
+            let diff = (hue - range.hue.rem_euclid(360.0)).abs();
+            let distance = diff.min(360.0 - diff);
+            let hue_range = range.hue_range.clamp(0.0, 180.0);
+            let soft = smoothness * MAX_HUE_SOFTNESS;
+            // Near-grey pixels have no meaningful hue, so they fall out of any color band
+            (1.0 - smoothstep(hue_range, hue_range + soft, distance))
+                * smoothstep(0.05, 0.2, saturation)
+        }
+    }
+}
+
+// Samples the source where the mask is set instead of converting the whole image, which
+// would copy a full-resolution buffer for every range mask on every render
+fn apply_range_mask(mask: &mut GrayImage, range: &RangeMask, source: RangeSource) {
+    let is_linear = source.is_linear;
+    match source.image {
+        DynamicImage::ImageRgb32F(image) => {
+            refine_by_range(mask, range, is_linear, image.dimensions(), |x, y| {
+                image.get_pixel(x, y).0
+            })
+        }
+        DynamicImage::ImageRgba32F(image) => {
+            refine_by_range(mask, range, is_linear, image.dimensions(), |x, y| {
+                let [r, g, b, _] = image.get_pixel(x, y).0;
+                [r, g, b]
+            })
+        }
+        image => refine_by_range(mask, range, is_linear, image.dimensions(), |x, y| {
+            let [r, g, b, _] = image.get_pixel(x, y).0;
+            [r, g, b].map(|c| c as f32 / 255.0)
+        }),
+    }
+}
+
+fn refine_by_range(
+    mask: &mut GrayImage,
+    range: &RangeMask,
+    is_linear: bool,
+    (src_w, src_h): (u32, u32),
+    sample: impl Fn(u32, u32) -> [f32; 3],
+) {
+    let (width, height) = mask.dimensions();
+    if src_w == 0 || src_h == 0 {
+        return;
+    }
+
+    for (x, y, pixel) in mask.enumerate_pixels_mut() {
+        if pixel[0] == 0 {
+            continue;
+        }
+        let sx = ((x as u64 * src_w as u64) / width as u64) as u32;
+        let sy = ((y as u64 * src_h as u64) / height as u64) as u32;
+        let weight = range_weight(range, sample(sx, sy), is_linear);
+        pixel[0] = (pixel[0] as f32 * weight).round() as u8;
+    }
+}
+
 fn generate_sub_mask_bitmap(
     sub_mask: &SubMask,
     width: u32,
@@ -696,6 +879,7 @@ pub fn generate_mask_bitmap(
     height: u32,
     scale: f32,
     crop_offset: (f32, f32),
+    range_source: Option<RangeSource>,
 ) -> Option<GrayImage> {
     if !mask_def.visible || mask_def.sub_masks.is_empty() {
         return None;
@@ -743,6 +927,11 @@ pub fn generate_mask_bitmap(
         }
     }
 
+    // Refines after inverting, so an inverted mask is still limited to the chosen band
+    if let (Some(range), Some(source)) = (&mask_def.range_mask, range_source) {
+        apply_range_mask(&mut final_mask, range, source);
+    }
+
     let opacity_multiplier = (mask_def.opacity / 100.0).clamp(0.0, 1.0);
     if opacity_multiplier < 1.0 {
         for pixel in final_mask.pixels_mut() {
@@ -751,4 +940,83 @@ pub fn generate_mask_bitmap(
     }
 
     Some(final_mask)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn luminance_band(min: f32, max: f32) -> RangeMask {
+        RangeMask {
+            mode: RangeMaskMode::Luminance,
+            luminance_min: min,
+            luminance_max: max,
+            hue: 0.0,
+            hue_range: 30.0,
+            smoothness: 0.0,
+        }
+    }
+
+    fn hue_band(hue: f32, hue_range: f32) -> RangeMask {
+        RangeMask {
+            mode: RangeMaskMode::Color,
+            luminance_min: 0.0,
+            luminance_max: 100.0,
+            hue,
+            hue_range,
+            smoothness: 0.0,
+        }
+    }
+
+    #[test]
+    fn smoothstep_eases_between_edges() {
+        assert_eq!(smoothstep(0.2, 0.6, 0.1), 0.0);
+        assert_eq!(smoothstep(0.2, 0.6, 0.7), 1.0);
+        assert!((smoothstep(0.2, 0.6, 0.4) - 0.5).abs() < 1e-6);
+        assert!(smoothstep(0.2, 0.6, 0.3) < smoothstep(0.2, 0.6, 0.35));
+    }
+
+    #[test]
+    fn smoothstep_with_no_width_is_a_step() {
+        assert_eq!(smoothstep(0.5, 0.5, 0.49), 0.0);
+        assert_eq!(smoothstep(0.5, 0.5, 0.5), 1.0);
+        assert_eq!(smoothstep(0.5, 0.2, 0.4), 0.0);
+    }
+
+    #[test]
+    fn luminance_band_is_measured_in_lightness() {
+        // 18% grey is L* ~49.5 whether it arrives linear or sRGB-encoded
+        let midtones = luminance_band(40.0, 60.0);
+        assert_eq!(range_weight(&midtones, [0.18; 3], true), 1.0);
+        assert_eq!(range_weight(&midtones, [0.4613; 3], false), 1.0);
+        // The same number read as encoded is a much darker tone
+        assert_eq!(range_weight(&midtones, [0.18; 3], false), 0.0);
+    }
+
+    #[test]
+    fn luminance_band_keeps_values_beyond_the_scale_at_its_ends() {
+        let highlights = luminance_band(80.0, 100.0);
+        assert_eq!(range_weight(&highlights, [4.0; 3], true), 1.0);
+        let upper_midtones = luminance_band(50.0, 80.0);
+        assert_eq!(range_weight(&upper_midtones, [4.0; 3], true), 0.0);
+
+        let shadows = luminance_band(0.0, 20.0);
+        assert_eq!(range_weight(&shadows, [-0.01, 0.0, 0.0], true), 1.0);
+    }
+
+    #[test]
+    fn hue_band_is_measured_on_encoded_values() {
+        // Linear (1.0, 0.2, 0.0) is hue ~12 degrees as stored but ~29 degrees once encoded
+        let orange = hue_band(30.0, 5.0);
+        assert_eq!(range_weight(&orange, [1.0, 0.2, 0.0], true), 1.0);
+        assert_eq!(range_weight(&orange, [1.0, 0.2, 0.0], false), 0.0);
+    }
+
+    #[test]
+    fn hue_band_ignores_greys_and_wraps_around_red() {
+        let reds = hue_band(350.0, 15.0);
+        assert_eq!(range_weight(&reds, [0.8, 0.1, 0.1], false), 1.0);
+        assert_eq!(range_weight(&reds, [0.5; 3], false), 0.0);
+        assert_eq!(range_weight(&reds, [0.1, 0.8, 0.1], false), 0.0);
+    }
+}
@@ -0,0 +1,272 @@
+use image::{DynamicImage, imageops::FilterType};
+use rayon::prelude::*;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::file_management;
+use crate::gpu_processing;
+
+const PALETTE_SAMPLE_DIM: u32 = 128;
+const MAX_PALETTE_COLORS: usize = 16;
+const MAX_KMEANS_ITERATIONS: usize = 20;
+const CONVERGENCE_THRESHOLD: f32 = 1e-4;
+
+const THEME_CANDIDATE_COLORS: usize = 6;
+// Below this HSL saturation the photo is treated as monochrome and gets a neutral accent
+const MIN_THEME_SATURATION: f32 = 0.12;
+const MAX_THEME_SATURATION: f32 = 0.45;
+const THEME_LIGHTNESS_RANGE: (f32, f32) = (0.45, 0.65);
+const NEUTRAL_THEME_COLOR: [u8; 3] = [220, 220, 220];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteColor {
+    pub hex: String,
+    pub rgb: [u8; 3],
+    /// Share of the image's pixels closest to this color, in percent.
+    pub coverage: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveThemeColor {
+    pub hex: String,
+    pub rgb: [u8; 3],
+    /// True when the photo had no usable colour and the neutral fallback was returned.
+    pub is_neutral: bool,
+}
+
+fn to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+fn rgb_to_hsl(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    if max == min {
+        return (0.0, 0.0, l);
+    }
+
+    let d = max - min;
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [u8; 3] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r, g, b].map(|v| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+fn squared_distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn nearest_center(pixel: &[f32; 3], centers: &[[f32; 3]]) -> usize {
+    centers
+        .iter()
+        .enumerate()
+        .map(|(i, center)| (i, squared_distance(pixel, center)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Farthest-point seeding: start from the pixel closest to the mean colour, then keep
+/// adding the pixel farthest from every chosen centre. Deterministic, so the same image
+/// always yields the same palette, and small distinct accents still get a centre.
+fn seed_centers(pixels: &[[f32; 3]], k: usize) -> Vec<[f32; 3]> {
+    let count = pixels.len() as f32;
+    let mut mean = [0.0f32; 3];
+    for p in pixels {
+        for c in 0..3 {
+            mean[c] += p[c] / count;
+        }
+    }
+
+    let first = pixels[nearest_center(&mean, pixels)];
+    let mut centers = vec![first];
+    let mut distances: Vec<f32> = pixels.par_iter().map(|p| squared_distance(p, &first)).collect();
+
+    while centers.len() < k {
+        let Some((index, distance)) = distances
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+        else {
+            break;
+        };
+        if *distance <= 0.0 {
+            break;
+        }
+        let next = pixels[index];
+        centers.push(next);
+        distances
+            .par_iter_mut()
+            .zip(pixels.par_iter())
+            .for_each(|(d, p)| *d = d.min(squared_distance(p, &next)));
+    }
+    centers
+}
+
+/// Clusters the image's pixels with k-means and returns up to `k` dominant colours,
+/// most common first. Images with fewer distinct colours return fewer entries.
+pub fn dominant_colors(image: &DynamicImage, k: usize) -> Vec<PaletteColor> {
+    let k = k.clamp(1, MAX_PALETTE_COLORS);
+    let sample = image
+        .resize(PALETTE_SAMPLE_DIM, PALETTE_SAMPLE_DIM, FilterType::Triangle)
+        .to_rgb32f();
+    let pixels: Vec<[f32; 3]> = sample
+        .pixels()
+        .map(|p| [p[0].clamp(0.0, 1.0), p[1].clamp(0.0, 1.0), p[2].clamp(0.0, 1.0)])
+        .collect();
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+
+    let mut centers = seed_centers(&pixels, k);
+    let mut assignments = vec![0usize; pixels.len()];
+
+    for _ in 0..MAX_KMEANS_ITERATIONS {
+        assignments
+            .par_iter_mut()
+            .zip(pixels.par_iter())
+            .for_each(|(assignment, p)| *assignment = nearest_center(p, &centers));
+
+        let mut sums = vec![[0.0f32; 3]; centers.len()];
+        let mut counts = vec![0usize; centers.len()];
+        for (p, &cluster) in pixels.iter().zip(assignments.iter()) {
+            for c in 0..3 {
+                sums[cluster][c] += p[c];
+            }
+            counts[cluster] += 1;
+        }
+
+        let mut shift = 0.0f32;
+        for (i, center) in centers.iter_mut().enumerate() {
+            if counts[i] == 0 {
+                continue;
+            }
+            let updated = sums[i].map(|s| s / counts[i] as f32);
+            shift = shift.max(squared_distance(center, &updated));
+            *center = updated;
+        }
+        if shift < CONVERGENCE_THRESHOLD {
+            break;
+        }
+    }
+
+    let mut counts = vec![0usize; centers.len()];
+    for &cluster in &assignments {
+        counts[cluster] += 1;
+    }
+
+    let mut palette: Vec<PaletteColor> = centers
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(center, count)| {
+            let rgb = center.map(|c| (c * 255.0).round() as u8);
+            PaletteColor {
+                hex: to_hex(rgb),
+                rgb,
+                coverage: count as f32 / pixels.len() as f32 * 100.0,
+            }
+        })
+        .collect();
+    palette.sort_by(|a, b| b.coverage.total_cmp(&a.coverage));
+    palette
+}
+
+/// Picks the most colourful of the image's dominant colours, favouring ones that cover
+/// more of the frame, and tones it down to a muted accent that stays readable against
+/// the editor's surfaces.
+pub fn adaptive_theme_color(image: &DynamicImage) -> AdaptiveThemeColor {
+    let candidate = dominant_colors(image, THEME_CANDIDATE_COLORS)
+        .into_iter()
+        .map(|color| (rgb_to_hsl(color.rgb), color.coverage))
+        .filter(|((_, s, _), _)| *s >= MIN_THEME_SATURATION)
+        .max_by(|((_, sa, _), ca), ((_, sb, _), cb)| {
+            (sa * ca.sqrt()).total_cmp(&(sb * cb.sqrt()))
+        });
+
+    let Some(((h, s, l), _)) = candidate else {
+        return AdaptiveThemeColor {
+            hex: to_hex(NEUTRAL_THEME_COLOR),
+            rgb: NEUTRAL_THEME_COLOR,
+            is_neutral: true,
+        };
+    };
+
+    let rgb = hsl_to_rgb(
+        h,
+        s.min(MAX_THEME_SATURATION),
+        l.clamp(THEME_LIGHTNESS_RANGE.0, THEME_LIGHTNESS_RANGE.1),
+    );
+    AdaptiveThemeColor {
+        hex: to_hex(rgb),
+        rgb,
+        is_neutral: false,
+    }
+}
+
+/// Returns the `k` dominant colours of an image as it currently looks, sampled from its
+/// cached thumbnail so no full decode is needed.
+#[tauri::command]
+pub async fn extract_palette(
+    path: String,
+    k: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<PaletteColor>, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let gpu_context = gpu_processing::get_or_init_gpu_context(&state).ok();
+        let image = file_management::get_cached_or_generate_thumbnail_image(
+            &path,
+            &app_handle,
+            gpu_context.as_ref(),
+        )
+        .map_err(|e| format!("Failed to load preview for {}: {}", path, e))?;
+        Ok(dominant_colors(&image, k.unwrap_or(5)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Derives the editor accent colour from the last full-quality preview of the loaded
+/// image, so it follows the current edit without another render.
+#[tauri::command]
+pub async fn compute_adaptive_theme_color(
+    app_handle: AppHandle,
+) -> Result<AdaptiveThemeColor, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        let preview = state
+            .last_processed_preview
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or("No processed preview available for the adaptive theme")?;
+        Ok(adaptive_theme_color(&DynamicImage::ImageRgb8(preview)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
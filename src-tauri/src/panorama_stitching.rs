@@ -10,6 +10,7 @@ use tauri::{AppHandle, Emitter};
 use crate::formats::is_raw_file;
 use crate::image_processing::apply_cpu_default_raw_processing;
 use crate::panorama_utils::{processing, stitching};
+use crate::raw_processing::RawDevelopParams;
 
 pub const BRIEF_DESCRIPTOR_SIZE: usize = 256;
 pub type Descriptor = [u8; BRIEF_DESCRIPTOR_SIZE / 8];
@@ -81,9 +82,13 @@ pub fn stitch_images(image_paths: Vec<String>, app_handle: AppHandle) -> Result<
             let file_bytes = fs::read(filename)
                 .map_err(|e| format!("Failed to read image {}: {}", filename, e))?;
 
-            let mut dynamic_image =
-                crate::image_loader::load_base_image_from_bytes(&file_bytes, filename, false, 2.5)
-                    .map_err(|e| format!("Failed to load image {}: {}", filename, e))?;
+            let mut dynamic_image = crate::image_loader::load_base_image_from_bytes(
+                &file_bytes,
+                filename,
+                false,
+                RawDevelopParams::default(),
+            )
+            .map_err(|e| format!("Failed to load image {}: {}", filename, e))?;
 
             if is_raw_file(filename) {
                 apply_cpu_default_raw_processing(&mut dynamic_image);
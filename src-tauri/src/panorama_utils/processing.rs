@@ -257,6 +257,49 @@ pub fn find_homography_ransac(
     }
 }
 
+/// Finds the full-resolution homography mapping `reference` pixel coordinates into `frame`
+/// for two shots of the same scene (brackets, focus stacks). Returns `None` when the
+/// frames can't be matched reliably.
+pub fn estimate_alignment(
+    reference_gray: &GrayImage,
+    frame_gray: &GrayImage,
+    brief_pairs: &[(Point2<i32>, Point2<i32>)],
+) -> Option<Matrix3<f64>> {
+    let (w, h) = reference_gray.dimensions();
+    let (new_w, new_h, scale_factor) = calculate_downscale_dimensions(w, h);
+    let downscale = |gray: &GrayImage| {
+        image::imageops::resize(gray, new_w, new_h, image::imageops::FilterType::Triangle)
+    };
+
+    let reference_features = find_features(&downscale(reference_gray), brief_pairs);
+    let frame_features = find_features(&downscale(frame_gray), brief_pairs);
+    let matches = match_features(&reference_features, &frame_features);
+    if matches.len() < MIN_INLIERS_FOR_CONNECTION {
+        return None;
+    }
+
+    let keypoints1: Vec<KeyPoint> = reference_features.iter().map(|f| f.keypoint).collect();
+    let keypoints2: Vec<KeyPoint> = frame_features.iter().map(|f| f.keypoint).collect();
+    let (_, inliers) = find_homography_ransac(&matches, &keypoints1, &keypoints2)?;
+    let inlier_points: Vec<(Point2<f64>, Point2<f64>)> = inliers
+        .iter()
+        .map(|m| {
+            let p1 = keypoints1[m.index1];
+            let p2 = keypoints2[m.index2];
+            (
+                Point2::new(p1.x as f64, p1.y as f64),
+                Point2::new(p2.x as f64, p2.y as f64),
+            )
+        })
+        .collect();
+    let h_small = compute_homography(&inlier_points)?;
+
+    let s = scale_factor;
+    let scale_inv = Matrix3::new(1.0 / s, 0.0, 0.0, 0.0, 1.0 / s, 0.0, 0.0, 0.0, 1.0);
+    let scale = Matrix3::new(s, 0.0, 0.0, 0.0, s, 0.0, 0.0, 0.0, 1.0);
+    Some(scale * h_small * scale_inv)
+}
+
 fn are_points_collinear(p1: Point2<f64>, p2: Point2<f64>, p3: Point2<f64>) -> bool {
     let area = p1.x * (p2.y - p3.y) + p2.x * (p3.y - p1.y) + p3.x * (p1.y - p2.y);
     area.abs() < 1e-6
@@ -635,7 +635,7 @@ fn find_pairwise_seam_dp_horizontal(
     seam
 }
 
-fn get_interpolated_pixel(img: &Rgb32FImage, x: f64, y: f64) -> Rgb<f32> {
+pub fn get_interpolated_pixel(img: &Rgb32FImage, x: f64, y: f64) -> Rgb<f32> {
     let (width, height) = img.dimensions();
     let x_floor = x.floor() as u32;
     let y_floor = y.floor() as u32;
@@ -3,25 +3,110 @@ use anyhow::Result;
 use image::{DynamicImage, ImageBuffer, Rgba};
 use rawler::{
     decoders::{Orientation, RawDecodeParams},
+    formats::tiff::Rational,
     imgop::develop::{DemosaicAlgorithm, Intermediate, ProcessingStep, RawDevelop},
     rawimage::RawImage,
     rawsource::RawSource,
 };
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RawDemosaic {
+    #[default]
+    Quality,
+    Speed,
+}
+
+/// Knobs for developing a RAW file. The defaults reproduce the decoder's own behaviour:
+/// the black and white levels from the file, no extra exposure, full-quality demosaic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawDevelopParams {
+    pub highlight_compression: f32,
+    /// Replaces the black level reported by the camera, in raw sensor units.
+    pub black_level: Option<u32>,
+    /// Replaces the white (clipping) level reported by the camera, in raw sensor units.
+    pub white_level: Option<u32>,
+    /// Baseline exposure in stops, applied before highlight compression.
+    pub exposure_bias: f32,
+    pub demosaic: RawDemosaic,
+}
+
+// Float fields hash by bit pattern, so cache keys change with any setting change
+impl Hash for RawDevelopParams {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.highlight_compression.to_bits().hash(state);
+        self.black_level.hash(state);
+        self.white_level.hash(state);
+        self.exposure_bias.to_bits().hash(state);
+        self.demosaic.hash(state);
+    }
+}
+
+impl Default for RawDevelopParams {
+    fn default() -> Self {
+        Self {
+            highlight_compression: 2.5,
+            black_level: None,
+            white_level: None,
+            exposure_bias: 0.0,
+            demosaic: RawDemosaic::Quality,
+        }
+    }
+}
+
+/// The camera's "Make Model" (e.g. "FUJIFILM X-T5"), used to key per-camera settings.
+/// Models that already start with the make aren't prefixed twice.
+pub fn camera_model_key(file_bytes: &[u8]) -> Option<String> {
+    let source = RawSource::new_from_slice(file_bytes);
+    let decoder = rawler::get_decoder(&source).ok()?;
+    let metadata = decoder
+        .raw_metadata(&source, &RawDecodeParams::default())
+        .ok()?;
+    let make = metadata.make.trim();
+    let model = metadata.model.trim();
+    if model.is_empty() {
+        return None;
+    }
+    if make.is_empty() || model.to_lowercase().starts_with(&make.to_lowercase()) {
+        Some(model.to_string())
+    } else {
+        Some(format!("{} {}", make, model))
+    }
+}
 
 pub fn develop_raw_image(
     file_bytes: &[u8],
     fast_demosaic: bool,
-    highlight_compression: f32,
+    params: RawDevelopParams,
 ) -> Result<DynamicImage> {
-    let (developed_image, orientation) =
-        develop_internal(file_bytes, fast_demosaic, highlight_compression)?;
+    let (developed_image, orientation) = develop_internal(file_bytes, fast_demosaic, params)?;
     Ok(apply_orientation(developed_image, orientation))
 }
 
+/// The full-size JPEG the camera embedded in the RAW file, already rendered with the
+/// camera's tone curve and colour. Used when the sensor data itself can't be decoded.
+pub fn extract_embedded_preview(file_bytes: &[u8]) -> Result<DynamicImage> {
+    let source = RawSource::new_from_slice(file_bytes);
+    let decoder = rawler::get_decoder(&source)?;
+    let preview = decoder
+        .full_image(&source, &RawDecodeParams::default())?
+        .ok_or_else(|| anyhow::anyhow!("RAW file has no embedded preview"))?;
+
+    let orientation = decoder
+        .raw_metadata(&source, &RawDecodeParams::default())
+        .ok()
+        .and_then(|metadata| metadata.exif.orientation)
+        .map(Orientation::from_u16)
+        .unwrap_or(Orientation::Normal);
+    Ok(apply_orientation(preview, orientation))
+}
+
 fn develop_internal(
     file_bytes: &[u8],
     fast_demosaic: bool,
-    highlight_compression: f32,
+    params: RawDevelopParams,
 ) -> Result<(DynamicImage, Orientation)> {
     let source = RawSource::new_from_slice(file_bytes);
     let decoder = rawler::get_decoder(&source)?;
@@ -34,6 +119,8 @@ fn develop_internal(
         .map(Orientation::from_u16)
         .unwrap_or(Orientation::Normal);
 
+    apply_level_overrides(&mut raw_image, &params);
+
     let original_white_level = raw_image
         .whitelevel
         .0
@@ -53,7 +140,7 @@ fn develop_internal(
     }
 
     let mut developer = RawDevelop::default();
-    if fast_demosaic {
+    if fast_demosaic || params.demosaic == RawDemosaic::Speed {
         developer.demosaic_algorithm = DemosaicAlgorithm::Speed;
     }
     developer.steps.retain(|&step| step != ProcessingStep::SRgb);
@@ -61,9 +148,10 @@ fn develop_internal(
     let mut developed_intermediate = developer.develop_intermediate(&raw_image)?;
 
     let denominator = (original_white_level - original_black_level).max(1.0);
-    let rescale_factor = (headroom_white_level - original_black_level) / denominator;
+    let rescale_factor = (headroom_white_level - original_black_level) / denominator
+        * 2.0f32.powf(params.exposure_bias);
 
-    let safe_highlight_compression = highlight_compression.max(1.01);
+    let safe_highlight_compression = params.highlight_compression.max(1.01);
 
     match &mut developed_intermediate {
         Intermediate::Monochrome(pixels) => {
@@ -120,21 +208,76 @@ fn develop_internal(
         }
     }
 
-    let (width, height) = {
-        let dim = developed_intermediate.dim();
-        (dim.w as u32, dim.h as u32)
-    };
-    let dynamic_image = match developed_intermediate {
+    Ok((intermediate_to_image(developed_intermediate)?, orientation))
+}
+
+fn apply_level_overrides(raw_image: &mut RawImage, params: &RawDevelopParams) {
+    if let Some(black_level) = params.black_level {
+        for level in raw_image.blacklevel.levels.iter_mut() {
+            *level = Rational::new(black_level, 1);
+        }
+    }
+    if let Some(white_level) = params.white_level {
+        for level in raw_image.whitelevel.0.iter_mut() {
+            *level = white_level;
+        }
+    }
+}
+
+/// The sensor data after black/white level scaling and demosaicing only: still linear,
+/// in camera RGB with no white balance or colour matrix, so 1.0 is the clipping point
+/// of each channel. Only the black and white level overrides in `params` apply, as the
+/// rest act after clipping. The result is subsampled to at most `max_dim` pixels on the
+/// long edge, and orientation isn't applied since callers only look at pixel values.
+pub fn develop_linear_sensor_image(
+    file_bytes: &[u8],
+    params: &RawDevelopParams,
+    max_dim: u32,
+) -> Result<DynamicImage> {
+    let source = RawSource::new_from_slice(file_bytes);
+    let decoder = rawler::get_decoder(&source)?;
+    let mut raw_image: RawImage = decoder.raw_image(&source, &RawDecodeParams::default(), false)?;
+
+    apply_level_overrides(&mut raw_image, params);
+
+    let mut developer = RawDevelop::default();
+    developer.demosaic_algorithm = DemosaicAlgorithm::Speed;
+    developer.steps.retain(|&step| {
+        matches!(
+            step,
+            ProcessingStep::Rescale | ProcessingStep::Demosaic | ProcessingStep::CropActiveArea
+        )
+    });
+
+    let intermediate = developer.develop_intermediate(&raw_image)?;
+    let dim = intermediate.dim();
+    let step = (dim.w.max(dim.h) as u32).div_ceil(max_dim.max(1));
+    intermediate_to_image_sampled(intermediate, step)
+}
+
+fn intermediate_to_image(intermediate: Intermediate) -> Result<DynamicImage> {
+    intermediate_to_image_sampled(intermediate, 1)
+}
+
+/// Converts to an RGBA float image keeping every `step`th pixel in each direction, so
+/// analysis paths don't allocate a full-resolution buffer.
+fn intermediate_to_image_sampled(intermediate: Intermediate, step: u32) -> Result<DynamicImage> {
+    let step = step.max(1);
+    let dim = intermediate.dim();
+    let full_width = dim.w as u32;
+    let (width, height) = (full_width.div_ceil(step), (dim.h as u32).div_ceil(step));
+    let index = |x: u32, y: u32| (y * step * full_width + x * step) as usize;
+    let dynamic_image = match intermediate {
         Intermediate::ThreeColor(pixels) => {
             let buffer = ImageBuffer::<Rgba<f32>, _>::from_fn(width, height, |x, y| {
-                let p = pixels.data[(y * width + x) as usize];
+                let p = pixels.data[index(x, y)];
                 Rgba([p[0], p[1], p[2], 1.0])
             });
             DynamicImage::ImageRgba32F(buffer)
         }
         Intermediate::Monochrome(pixels) => {
             let buffer = ImageBuffer::<Rgba<f32>, _>::from_fn(width, height, |x, y| {
-                let p = pixels.data[(y * width + x) as usize];
+                let p = pixels.data[index(x, y)];
                 Rgba([p, p, p, 1.0])
             });
             DynamicImage::ImageRgba32F(buffer)
@@ -146,5 +289,5 @@ fn develop_internal(
         }
     };
 
-    Ok((dynamic_image, orientation))
+    Ok(dynamic_image)
 }
\ No newline at end of file
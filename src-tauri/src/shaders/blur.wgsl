@@ -2,7 +2,7 @@ struct BlurParams {
     radius: u32,
     tile_offset_x: u32,
     tile_offset_y: u32,
-    _pad: u32,
+    sigma: f32,
 }
 
 @group(0) @binding(0) var input_texture: texture_2d<f32>;
@@ -28,7 +28,7 @@ fn horizontal_blur(@builtin(global_invocation_id) id: vec3<u32>) {
     }
 
     let radius = i32(params.radius);
-    let sigma = f32(radius) / 2.0;
+    let sigma = params.sigma;
 
     let absolute_coord = vec2<u32>(id.x + params.tile_offset_x, id.y + params.tile_offset_y);
     let full_dims = vec2<i32>(textureDimensions(input_texture));
@@ -72,7 +72,7 @@ fn vertical_blur(@builtin(global_invocation_id) id: vec3<u32>) {
     }
 
     let radius = i32(params.radius);
-    let sigma = f32(radius) / 2.0;
+    let sigma = params.sigma;
 
     let local_coord = vec2<i32>(id.xy);
     let input_dims = vec2<i32>(textureDimensions(input_texture));
@@ -70,20 +70,19 @@ struct GlobalAdjustments {
     negative_red_balance: f32,
     negative_green_balance: f32,
     negative_blue_balance: f32,
-    _pad_neg1: f32,
-    _pad_neg2: f32,
+    grain_scale: f32,
+    grain_seed: u32,
 
     has_lut: u32,
     lut_intensity: f32,
     tonemapper_mode: u32,
-    _pad_lut2: f32,
-    _pad_lut3: f32,
-    _pad_lut4: f32,
-    _pad_lut5: f32,
-
-    _pad_agx1: f32,
-    _pad_agx2: f32,
-    _pad_agx3: f32,
+    lut_domain_min_r: f32,
+    lut_domain_min_g: f32,
+    lut_domain_min_b: f32,
+    lut_domain_max_r: f32,
+    lut_domain_max_g: f32,
+    lut_domain_max_b: f32,
+    lut_1d_size: u32,
     agx_pipe_to_rendering_matrix: mat3x3<f32>,
     agx_rendering_to_pipe_matrix: mat3x3<f32>,
 
@@ -110,10 +109,14 @@ struct GlobalAdjustments {
     red_curve_count: u32,
     green_curve_count: u32,
     blue_curve_count: u32,
-    _pad_end1: f32,
-    _pad_end2: f32,
-    _pad_end3: f32,
-    _pad_end4: f32,
+    output_linear: u32, // read on the host, which runs main_linear instead of main
+    sharpen_mode: u32,
+    sharpen_radius: f32,
+    sharpen_threshold: f32,
+    has_fill: u32,
+    fill_r: f32,
+    fill_g: f32,
+    fill_b: f32,
 }
 
 struct MaskAdjustments {
@@ -174,6 +177,10 @@ struct AllAdjustments {
     tile_offset_x: u32,
     tile_offset_y: u32,
     mask_atlas_cols: u32,
+    region_offset_x: u32,
+    region_offset_y: u32,
+    full_width: u32,
+    full_height: u32,
 }
 
 struct HslRange {
@@ -194,6 +201,7 @@ const HSL_RANGES: array<HslRange, 8> = array<HslRange, 8>(
 
 @group(0) @binding(0) var input_texture: texture_2d<f32>;
 @group(0) @binding(1) var output_texture: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(20) var linear_output_texture: texture_storage_2d<rgba16float, write>;
 @group(0) @binding(2) var<uniform> adjustments: AllAdjustments;
 
 @group(0) @binding(3) var mask0: texture_2d<f32>;
@@ -210,6 +218,7 @@ const HSL_RANGES: array<HslRange, 8> = array<HslRange, 8>(
 
 @group(0) @binding(14) var lut_texture: texture_3d<f32>;
 @group(0) @binding(15) var lut_sampler: sampler;
+@group(0) @binding(19) var lut_1d_texture: texture_2d<f32>;
 
 @group(0) @binding(16) var sharpness_blur_texture: texture_2d<f32>;
 @group(0) @binding(17) var clarity_blur_texture: texture_2d<f32>;
@@ -681,6 +690,92 @@ fn apply_local_contrast(
     return mix(processed_color_linear, final_color, midtone_mask);
 }
 
+const RATIO_SHARPEN_STRENGTH: f32 = 1.5;
+
+// Scales the colour by the ratio of its luminance to the blurred luminance, raised to the
+// amount, instead of adding the difference like an unsharp mask. Detail is boosted in
+// proportion to the local level, so hue is kept and dark edges don't clip to black, and
+// clamping the ratio to [0.5, 2] bounds the overshoot on hard edges.
+fn apply_ratio_sharpening(
+    processed_color_linear: vec3<f32>,
+    blurred_color_input_space: vec3<f32>,
+    amount: f32,
+    is_raw: u32
+) -> vec3<f32> {
+    let center_luma = get_luma(processed_color_linear);
+    let shadow_protection = smoothstep(0.0, 0.1, center_luma);
+    let highlight_protection = 1.0 - smoothstep(0.6, 1.0, center_luma);
+    let midtone_mask = shadow_protection * highlight_protection;
+    if (midtone_mask < 0.001) {
+        return processed_color_linear;
+    }
+
+    var blurred_color_linear: vec3<f32>;
+    if (is_raw == 1u) {
+        blurred_color_linear = blurred_color_input_space;
+    } else {
+        blurred_color_linear = srgb_to_linear(blurred_color_input_space);
+    }
+
+    let ratio = clamp(center_luma / max(get_luma(blurred_color_linear), 0.0001), 0.5, 2.0);
+    let final_color = processed_color_linear * pow(ratio, amount * RATIO_SHARPEN_STRENGTH);
+    return mix(processed_color_linear, final_color, midtone_mask);
+}
+
+fn apply_sharpening(
+    processed_color_linear: vec3<f32>,
+    blurred_color_input_space: vec3<f32>,
+    amount: f32,
+    g: GlobalAdjustments
+) -> vec3<f32> {
+    if (amount == 0.0) {
+        return processed_color_linear;
+    }
+
+    // Softening always uses the blur mix; the modes only differ in how they sharpen
+    if (amount > 0.0 && g.sharpen_mode == 1u) {
+        return apply_ratio_sharpening(processed_color_linear, blurred_color_input_space, amount, g.is_raw_image);
+    }
+
+    var effective_amount = amount;
+    if (amount > 0.0 && g.sharpen_threshold > 0.0) {
+        var blurred_color_linear = blurred_color_input_space;
+        if (g.is_raw_image == 0u) {
+            blurred_color_linear = srgb_to_linear(blurred_color_input_space);
+        }
+        let detail = abs(get_luma(processed_color_linear) - get_luma(blurred_color_linear));
+        effective_amount *= smoothstep(g.sharpen_threshold, g.sharpen_threshold * 2.0, detail);
+    }
+    return apply_local_contrast(processed_color_linear, blurred_color_input_space, effective_amount, g.is_raw_image);
+}
+
+// When rendering a region of a larger image, position-dependent effects
+// (vignette, centre, grain, CA) must be evaluated in full-image coordinates.
+fn full_image_dims() -> vec2<f32> {
+    if (adjustments.full_width > 0u && adjustments.full_height > 0u) {
+        return vec2<f32>(f32(adjustments.full_width), f32(adjustments.full_height));
+    }
+    return vec2<f32>(textureDimensions(input_texture));
+}
+
+fn full_image_coord(coords_i: vec2<i32>) -> vec2<f32> {
+    return vec2<f32>(coords_i) + vec2<f32>(f32(adjustments.region_offset_x), f32(adjustments.region_offset_y));
+}
+
+fn get_vignette_mask(coords_i: vec2<i32>) -> f32 {
+    let g = adjustments.global;
+    let full_dims_f = full_image_dims();
+    let coord_f = full_image_coord(coords_i);
+    let v_mid = g.vignette_midpoint;
+    let v_round = 1.0 - g.vignette_roundness;
+    let v_feather = g.vignette_feather * 0.5;
+    let aspect = full_dims_f.y / full_dims_f.x;
+    let uv_centered = (coord_f / full_dims_f - 0.5) * 2.0;
+    let uv_round = sign(uv_centered) * pow(abs(uv_centered), vec2<f32>(v_round, v_round));
+    let d = length(uv_round * vec2<f32>(1.0, aspect)) * 0.5;
+    return smoothstep(v_mid - v_feather, v_mid + v_feather, d);
+}
+
 fn apply_centre_local_contrast(
     color_in: vec3<f32>, 
     centre_amount: f32, 
@@ -691,8 +786,8 @@ fn apply_centre_local_contrast(
     if (centre_amount == 0.0) {
         return color_in;
     }
-    let full_dims_f = vec2<f32>(textureDimensions(input_texture));
-    let coord_f = vec2<f32>(coords_i);
+    let full_dims_f = full_image_dims();
+    let coord_f = full_image_coord(coords_i);
     let midpoint = 0.4;
     let feather = 0.375;
     let aspect = full_dims_f.y / full_dims_f.x;
@@ -720,8 +815,8 @@ fn apply_centre_tonal_and_color(
     if (centre_amount == 0.0) {
         return color_in;
     }
-    let full_dims_f = vec2<f32>(textureDimensions(input_texture));
-    let coord_f = vec2<f32>(coords_i);
+    let full_dims_f = full_image_dims();
+    let coord_f = full_image_coord(coords_i);
     let midpoint = 0.4;
     let feather = 0.375;
     let aspect = full_dims_f.y / full_dims_f.x;
@@ -803,7 +898,7 @@ fn apply_noise_reduction(color: vec3<f32>, coords_i: vec2<i32>, luma_amount: f32
 
 fn apply_ca_correction(coords: vec2<u32>, ca_rc: f32, ca_by: f32) -> vec3<f32> {
     let dims = vec2<f32>(textureDimensions(input_texture));
-    let center = dims / 2.0;
+    let center = full_image_dims() / 2.0 - vec2<f32>(f32(adjustments.region_offset_x), f32(adjustments.region_offset_y));
     let current_pos = vec2<f32>(coords);
 
     let to_center = current_pos - center;
@@ -1003,6 +1098,23 @@ fn get_mask_influence(mask_index: u32, coords: vec2<u32>) -> f32 {
     }
 }
 
+fn load_lut_1d(index: u32, row_width: u32) -> vec3<f32> {
+    return textureLoad(lut_1d_texture, vec2<i32>(i32(index % row_width), i32(index / row_width)), 0).rgb;
+}
+
+fn sample_lut_1d(uv: vec3<f32>) -> vec3<f32> {
+    let size = adjustments.global.lut_1d_size;
+    let row_width = textureDimensions(lut_1d_texture).x;
+    let scaled = clamp(uv, vec3<f32>(0.0), vec3<f32>(1.0)) * f32(size - 1u);
+    let i0 = vec3<u32>(floor(scaled));
+    let i1 = min(i0 + vec3<u32>(1u), vec3<u32>(size - 1u));
+    let f = scaled - floor(scaled);
+    let r = mix(load_lut_1d(i0.x, row_width).r, load_lut_1d(i1.x, row_width).r, f.x);
+    let g = mix(load_lut_1d(i0.y, row_width).g, load_lut_1d(i1.y, row_width).g, f.y);
+    let b = mix(load_lut_1d(i0.z, row_width).b, load_lut_1d(i1.z, row_width).b, f.z);
+    return vec3<f32>(r, g, b);
+}
+
 fn sample_lut_tetrahedral(uv: vec3<f32>) -> vec3<f32> {
     let dims = vec3<f32>(textureDimensions(lut_texture));
     let size = dims - vec3<f32>(1.0);
@@ -1073,17 +1185,24 @@ fn sample_lut_tetrahedral(uv: vec3<f32>) -> vec3<f32> {
     return res;
 }
 
-@compute @workgroup_size(8, 8, 1)
-fn main(@builtin(global_invocation_id) id: vec3<u32>) {
-    let out_dims = vec2<u32>(textureDimensions(output_texture));
-    if (id.x >= out_dims.x || id.y >= out_dims.y) { return; }
+const REFERENCE_DIMENSION: f32 = 1080.0;
+
+fn reference_scale() -> f32 {
+    let full_dims = full_image_dims();
+    return max(0.1, min(full_dims.x, full_dims.y) / REFERENCE_DIMENSION);
+}
 
-    const REFERENCE_DIMENSION: f32 = 1080.0;
-    let full_dims = vec2<f32>(textureDimensions(input_texture));
-    let current_ref_dim = min(full_dims.x, full_dims.y);
-    let scale = max(0.1, current_ref_dim / REFERENCE_DIMENSION);
+// Everything up to tone mapping: the edited colour, still linear and unclamped, with
+// the source alpha. `for_display` adds the RAW contrast emulation the display
+// rendering is tuned around.
+// The `fillColor` adjustment, display-referred sRGB
+fn fill_color() -> vec3<f32> {
+    return vec3<f32>(adjustments.global.fill_r, adjustments.global.fill_g, adjustments.global.fill_b);
+}
 
-    let absolute_coord = id.xy + vec2<u32>(adjustments.tile_offset_x, adjustments.tile_offset_y);
+fn render_scene_linear(tile_coord: vec2<u32>, for_display: bool) -> vec4<f32> {
+    let scale = reference_scale();
+    let absolute_coord = tile_coord + vec2<u32>(adjustments.tile_offset_x, adjustments.tile_offset_y);
     let absolute_coord_i = vec2<i32>(absolute_coord);
 
     let ca_rc = adjustments.global.chromatic_aberration_red_cyan;
@@ -1110,19 +1229,19 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         initial_linear_rgb = max(initial_linear_rgb, vec3<f32>(0.0));
     }
 
-    let sharpness_blurred = textureLoad(sharpness_blur_texture, id.xy, 0).rgb;
-    let clarity_blurred = textureLoad(clarity_blur_texture, id.xy, 0).rgb;
-    let structure_blurred = textureLoad(structure_blur_texture, id.xy, 0).rgb;
+    let sharpness_blurred = textureLoad(sharpness_blur_texture, tile_coord, 0).rgb;
+    let clarity_blurred = textureLoad(clarity_blur_texture, tile_coord, 0).rgb;
+    let structure_blurred = textureLoad(structure_blur_texture, tile_coord, 0).rgb;
     
     var locally_contrasted_rgb = initial_linear_rgb;
-    locally_contrasted_rgb = apply_local_contrast(locally_contrasted_rgb, sharpness_blurred, adjustments.global.sharpness, adjustments.global.is_raw_image);
+    locally_contrasted_rgb = apply_sharpening(locally_contrasted_rgb, sharpness_blurred, adjustments.global.sharpness, adjustments.global);
     locally_contrasted_rgb = apply_local_contrast(locally_contrasted_rgb, clarity_blurred, adjustments.global.clarity, adjustments.global.is_raw_image);
     locally_contrasted_rgb = apply_local_contrast(locally_contrasted_rgb, structure_blurred, adjustments.global.structure, adjustments.global.is_raw_image);
     locally_contrasted_rgb = apply_centre_local_contrast(locally_contrasted_rgb, adjustments.global.centre, absolute_coord_i, clarity_blurred, adjustments.global.is_raw_image);
 
     var processed_rgb = apply_linear_exposure(locally_contrasted_rgb, adjustments.global.exposure);
 
-    if (adjustments.global.is_raw_image == 1u && adjustments.global.tonemapper_mode != 1u) {
+    if (adjustments.global.is_raw_image == 1u && adjustments.global.tonemapper_mode != 1u && for_display) {
         var srgb_emulated = linear_to_srgb(processed_rgb);
         const BRIGHTNESS_GAMMA: f32 = 1.1;
         srgb_emulated = pow(srgb_emulated, vec3<f32>(1.0 / BRIGHTNESS_GAMMA));
@@ -1132,7 +1251,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         processed_rgb = srgb_to_linear(srgb_emulated);
     }
 
-    let globally_adjusted_linear = apply_all_adjustments(processed_rgb, adjustments.global, absolute_coord_i, id.xy, scale);
+    let globally_adjusted_linear = apply_all_adjustments(processed_rgb, adjustments.global, absolute_coord_i, tile_coord, scale);
     var composite_rgb_linear = globally_adjusted_linear;
     for (var i = 0u; i < adjustments.mask_count; i = i + 1u) {
         let influence = get_mask_influence(i, absolute_coord);
@@ -1140,15 +1259,42 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
             let mask_adj = adjustments.mask_adjustments[i];
 
             var mask_base_linear = composite_rgb_linear;
-            mask_base_linear = apply_local_contrast(mask_base_linear, sharpness_blurred, mask_adj.sharpness, adjustments.global.is_raw_image);
+            mask_base_linear = apply_sharpening(mask_base_linear, sharpness_blurred, mask_adj.sharpness, adjustments.global);
             mask_base_linear = apply_local_contrast(mask_base_linear, clarity_blurred, mask_adj.clarity, adjustments.global.is_raw_image);
             mask_base_linear = apply_local_contrast(mask_base_linear, structure_blurred, mask_adj.structure, adjustments.global.is_raw_image);
 
-            let mask_adjusted_linear = apply_all_mask_adjustments(mask_base_linear, mask_adj, absolute_coord_i, id.xy, scale, adjustments.global.is_raw_image, adjustments.global.tonemapper_mode);
+            let mask_adjusted_linear = apply_all_mask_adjustments(mask_base_linear, mask_adj, absolute_coord_i, tile_coord, scale, adjustments.global.is_raw_image, adjustments.global.tonemapper_mode);
             composite_rgb_linear = mix(composite_rgb_linear, mask_adjusted_linear, influence);
         }
     }
 
+    return vec4<f32>(composite_rgb_linear, original_alpha);
+}
+
+fn apply_vignette(color: vec3<f32>, coords_i: vec2<i32>) -> vec3<f32> {
+    let v_amount = adjustments.global.vignette_amount;
+    if (v_amount == 0.0) {
+        return color;
+    }
+    let vignette_mask = get_vignette_mask(coords_i);
+    if (v_amount < 0.0) {
+        return color * (1.0 + v_amount * vignette_mask);
+    }
+    return mix(color, vec3<f32>(1.0), v_amount * vignette_mask);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let out_dims = vec2<u32>(textureDimensions(output_texture));
+    if (id.x >= out_dims.x || id.y >= out_dims.y) { return; }
+
+    let absolute_coord = id.xy + vec2<u32>(adjustments.tile_offset_x, adjustments.tile_offset_y);
+    let absolute_coord_i = vec2<i32>(absolute_coord);
+
+    let scene = render_scene_linear(id.xy, true);
+    let composite_rgb_linear = scene.rgb;
+    let original_alpha = scene.a;
+
     var base_srgb: vec3<f32>;
     if (adjustments.global.tonemapper_mode == 1u) {
         base_srgb = agx_full_transform(composite_rgb_linear);
@@ -1176,43 +1322,44 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
-    if (adjustments.global.has_lut == 1u) {
-        let lut_color = sample_lut_tetrahedral(final_rgb);
+    if (adjustments.global.has_lut != 0u) {
+        let g = adjustments.global;
+        let domain_min = vec3<f32>(g.lut_domain_min_r, g.lut_domain_min_g, g.lut_domain_min_b);
+        let domain_max = vec3<f32>(g.lut_domain_max_r, g.lut_domain_max_g, g.lut_domain_max_b);
+        var lut_color = (final_rgb - domain_min) / max(domain_max - domain_min, vec3<f32>(1e-6));
+        if ((g.has_lut & 2u) != 0u && g.lut_1d_size > 1u) {
+            lut_color = sample_lut_1d(lut_color);
+        }
+        if ((g.has_lut & 1u) != 0u) {
+            lut_color = sample_lut_tetrahedral(lut_color);
+        }
         
         final_rgb = mix(final_rgb, lut_color, adjustments.global.lut_intensity);
     }
 
     if (adjustments.global.grain_amount > 0.0) {
         let g = adjustments.global;
-        let coord = vec2<f32>(absolute_coord_i);
+        // Grain is defined on the full-resolution image so downscaled previews
+        // sample the same pattern an export would produce.
+        let grain_scale = select(1.0, g.grain_scale, g.grain_scale > 0.0);
+        let coord = full_image_coord(absolute_coord_i) / grain_scale;
+        let full_res_dims = full_image_dims() / grain_scale;
+        let full_res_scale = max(0.1, min(full_res_dims.x, full_res_dims.y) / REFERENCE_DIMENSION);
         let amount = g.grain_amount * 0.5;
-        let grain_frequency = (1.0 / max(g.grain_size, 0.1)) / scale;
+        let grain_frequency = (1.0 / max(g.grain_size, 0.1)) / full_res_scale;
         let roughness = g.grain_roughness;
         let luma = max(0.0, get_luma(final_rgb));
         let luma_mask = smoothstep(0.0, 0.15, luma) * (1.0 - smoothstep(0.6, 1.0, luma));
-        let base_coord = coord * grain_frequency;
-        let rough_coord = coord * grain_frequency * 0.6;
+        let seed_offset = vec2<f32>(f32(g.grain_seed & 0xFFFu), f32((g.grain_seed >> 12u) & 0xFFFu));
+        let base_coord = coord * grain_frequency + seed_offset;
+        let rough_coord = coord * grain_frequency * 0.6 + seed_offset;
         let noise_base = gradient_noise(base_coord);
         let noise_rough = gradient_noise(rough_coord + vec2<f32>(5.2, 1.3)); 
         let noise_val = mix(noise_base, noise_rough, roughness);
         final_rgb += vec3<f32>(noise_val) * amount * luma_mask;
     }
 
-    let g = adjustments.global;
-    if (g.vignette_amount != 0.0) {
-        let full_dims_f = vec2<f32>(textureDimensions(input_texture));
-        let coord_f = vec2<f32>(absolute_coord);
-        let v_amount = g.vignette_amount;
-        let v_mid = g.vignette_midpoint;
-        let v_round = 1.0 - g.vignette_roundness;
-        let v_feather = g.vignette_feather * 0.5;
-        let aspect = full_dims_f.y / full_dims_f.x;
-        let uv_centered = (coord_f / full_dims_f - 0.5) * 2.0;
-        let uv_round = sign(uv_centered) * pow(abs(uv_centered), vec2<f32>(v_round, v_round));
-        let d = length(uv_round * vec2<f32>(1.0, aspect)) * 0.5;
-        let vignette_mask = smoothstep(v_mid - v_feather, v_mid + v_feather, d);
-        if (v_amount < 0.0) { final_rgb *= (1.0 + v_amount * vignette_mask); } else { final_rgb = mix(final_rgb, vec3<f32>(1.0), v_amount * vignette_mask); }
-    }
+    final_rgb = apply_vignette(final_rgb, absolute_coord_i);
 
     if (adjustments.global.show_clipping == 1u) {
         let HIGHLIGHT_WARNING_COLOR = vec3<f32>(1.0, 0.0, 0.0);
@@ -1228,6 +1375,32 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     let dither_amount = 1.0 / 255.0;
     final_rgb += dither(id.xy) * dither_amount;
+    final_rgb = clamp(final_rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    var output_alpha = original_alpha;
+    if (adjustments.global.has_fill != 0u) {
+        // Background for the area rotation left uncovered, laid under the finished image
+        final_rgb = mix(fill_color(), final_rgb, original_alpha);
+        output_alpha = 1.0;
+    }
+
+    textureStore(output_texture, id.xy, vec4<f32>(final_rgb, output_alpha));
+}
 
-    textureStore(output_texture, id.xy, vec4<f32>(clamp(final_rgb, vec3<f32>(0.0), vec3<f32>(1.0)), original_alpha));
+// Scene-referred output for compositing: stops before tone mapping and the
+// display-space stages (curves, LUT, grain), leaving values linear and unclamped.
+@compute @workgroup_size(8, 8, 1)
+fn main_linear(@builtin(global_invocation_id) id: vec3<u32>) {
+    let out_dims = vec2<u32>(textureDimensions(linear_output_texture));
+    if (id.x >= out_dims.x || id.y >= out_dims.y) { return; }
+
+    let absolute_coord = id.xy + vec2<u32>(adjustments.tile_offset_x, adjustments.tile_offset_y);
+    let scene = render_scene_linear(id.xy, false);
+    var linear_rgb = apply_vignette(max(scene.rgb, vec3<f32>(0.0)), vec2<i32>(absolute_coord));
+    var output_alpha = scene.a;
+    if (adjustments.global.has_fill != 0u) {
+        linear_rgb = mix(srgb_to_linear(fill_color()), linear_rgb, scene.a);
+        output_alpha = 1.0;
+    }
+    textureStore(linear_output_texture, id.xy, vec4<f32>(linear_rgb, output_alpha));
 }
\ No newline at end of file
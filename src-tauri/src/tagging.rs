@@ -5,11 +5,13 @@ use ndarray::{Array, Axis};
 use ort::session::Session;
 use ort::value::Tensor; 
 use rayon::prelude::*;
+use serde::Serialize;
 use serde_json;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tokenizers::Tokenizer;
 use tokio::task::JoinHandle;
@@ -24,6 +26,38 @@ use crate::image_processing::ImageMetadata;
 
 pub const COLOR_TAG_PREFIX: &str = "color:";
 pub const USER_TAG_PREFIX: &str = "user:";
+pub const DEFAULT_TAGGING_CONFIDENCE_THRESHOLD: f32 = 0.005;
+pub const DEFAULT_TAGGING_MAX_TAGS: u32 = 10;
+const DEFAULT_TAG_SCAN_BUDGET_MS: u64 = 2000;
+
+// Adobe's standard xmp:Label strings for the color labels Lightroom/Bridge know about
+const XMP_COLOR_LABELS: &[(&str, &str)] = &[
+    ("red", "Red"),
+    ("yellow", "Yellow"),
+    ("green", "Green"),
+    ("blue", "Blue"),
+    ("purple", "Purple"),
+];
+
+/// Maps one of our color label names to its `xmp:Label` value. Labels Adobe doesn't
+/// define are passed through unchanged as custom labels.
+pub fn color_label_to_xmp_label(color: &str) -> String {
+    XMP_COLOR_LABELS
+        .iter()
+        .find(|(ours, _)| ours.eq_ignore_ascii_case(color))
+        .map(|(_, adobe)| adobe.to_string())
+        .unwrap_or_else(|| color.to_string())
+}
+
+/// Maps an `xmp:Label` value back to our color label name, keeping custom labels as-is.
+pub fn xmp_label_to_color_label(label: &str) -> String {
+    let label = label.trim();
+    XMP_COLOR_LABELS
+        .iter()
+        .find(|(_, adobe)| adobe.eq_ignore_ascii_case(label))
+        .map(|(ours, _)| ours.to_string())
+        .unwrap_or_else(|| label.to_string())
+}
 
 fn preprocess_clip_image(image: &DynamicImage) -> Array<f32, ndarray::Dim<[usize; 4]>> {
     let input_size = 224;
@@ -144,10 +178,30 @@ pub fn extract_color_tags(image: &DynamicImage) -> Vec<String> {
     }
 }
 
+pub fn select_top_tags(
+    scored_candidates: impl IntoIterator<Item = (String, f32)>,
+    confidence_threshold: f32,
+    max_tags: usize,
+) -> Vec<String> {
+    let mut scored_tags: Vec<(String, f32)> = scored_candidates
+        .into_iter()
+        .filter(|(_, prob)| *prob > confidence_threshold)
+        .collect();
+
+    scored_tags.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored_tags
+        .into_iter()
+        .take(max_tags)
+        .map(|(tag, _)| tag)
+        .collect()
+}
+
 pub fn generate_tags_with_clip(
     image: &DynamicImage,
     clip_session_mutex: &Mutex<Session>,
     tokenizer: &Tokenizer,
+    confidence_threshold: f32,
+    max_tags: usize,
 ) -> Result<Vec<String>> {
     let image_input = preprocess_clip_image(image);
 
@@ -203,22 +257,12 @@ pub fn generate_tags_with_clip(
     let logits = logits_dyn.into_dimensionality::<ndarray::Dim<[usize; 2]>>()?;
     let probs = softmax(&logits);
 
-    let confidence_threshold = 0.005;
-    let mut scored_tags: Vec<(String, f32)> = Vec::new();
-
     let prob_row = probs.row(0);
-    for (i, &prob) in prob_row.iter().enumerate() {
-        if prob > confidence_threshold {
-            scored_tags.push((TAG_CANDIDATES[i].to_string(), prob));
-        }
-    }
-
-    scored_tags.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    let initial_tags: Vec<String> = scored_tags
-        .into_iter()
-        .take(10)
-        .map(|(tag, _)| tag)
-        .collect();
+    let scored_candidates = prob_row
+        .iter()
+        .enumerate()
+        .map(|(i, &prob)| (TAG_CANDIDATES[i].to_string(), prob));
+    let initial_tags = select_top_tags(scored_candidates, confidence_threshold, max_tags);
 
     let mut final_tags_set: HashSet<String> = initial_tags.iter().cloned().collect();
 
@@ -257,6 +301,11 @@ pub async fn start_background_indexing(
     }
 
     let max_concurrent_tasks = settings.tagging_thread_count.unwrap_or(3).max(1) as usize;
+    let confidence_threshold = settings
+        .tagging_confidence_threshold
+        .unwrap_or(DEFAULT_TAGGING_CONFIDENCE_THRESHOLD)
+        .clamp(0.0, 1.0);
+    let max_tags = settings.tagging_max_tags.unwrap_or(DEFAULT_TAGGING_MAX_TAGS) as usize;
 
     let models = crate::ai_processing::get_or_init_ai_models(
         &app_handle,
@@ -317,7 +366,7 @@ pub async fn start_background_indexing(
                     let path_str = path.to_string_lossy().to_string();
                     let (_, sidecar_path) = parse_virtual_path(&path_str);
 
-                    let mut metadata: ImageMetadata = if sidecar_path.exists() {
+                    let metadata: ImageMetadata = if sidecar_path.exists() {
                         fs::read_to_string(&sidecar_path)
                             .ok()
                             .and_then(|c| serde_json::from_str(&c).ok())
@@ -345,11 +394,18 @@ pub async fn start_background_indexing(
                                 if let (Some(clip_model), Some(clip_tokenizer)) =
                                     (&models_inner.clip_model, &models_inner.clip_tokenizer)
                                 {
-                                    if let Ok(ai_tags) =
-                                        generate_tags_with_clip(&image, clip_model, clip_tokenizer)
-                                    {
+                                    if let Ok(ai_tags) = generate_tags_with_clip(
+                                        &image,
+                                        clip_model,
+                                        clip_tokenizer,
+                                        confidence_threshold,
+                                        max_tags,
+                                    ) {
                                         println!("Found AI tags for {}: {:?}", path_str, ai_tags);
 
+                                        // Tagging takes a while, so merge into the sidecar as it is now
+                                        let _sidecar_lock = file_management::lock_sidecar(&sidecar_path);
+                                        let mut metadata = file_management::read_sidecar_metadata(&sidecar_path);
                                         let mut existing_tags: HashSet<String> = metadata
                                             .tags
                                             .unwrap_or_default()
@@ -396,6 +452,7 @@ pub async fn start_background_indexing(
             .await;
 
         println!("Background indexing finished for: {}", folder_path);
+        invalidate_tag_usage_cache(&app_handle_clone);
         let _ = app_handle_clone.emit("indexing-finished", ());
 
         *app_handle_clone
@@ -413,14 +470,8 @@ pub async fn start_background_indexing(
 fn modify_tags_for_path(path_str: &str, modify_fn: impl Fn(&mut Vec<String>)) -> Result<(), String> {
     let (_, sidecar_path) = parse_virtual_path(path_str);
 
-    let mut metadata: ImageMetadata = if sidecar_path.exists() {
-        fs::read_to_string(&sidecar_path)
-            .ok()
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_default()
-    } else {
-        ImageMetadata::default()
-    };
+    let _sidecar_lock = file_management::lock_sidecar(&sidecar_path);
+    let mut metadata = file_management::read_sidecar_metadata(&sidecar_path);
 
     let mut tags = metadata.tags.unwrap_or_else(Vec::new);
     modify_fn(&mut tags);
@@ -439,7 +490,7 @@ fn modify_tags_for_path(path_str: &str, modify_fn: impl Fn(&mut Vec<String>)) ->
 }
 
 #[tauri::command]
-pub fn add_tag_for_paths(paths: Vec<String>, tag: String) -> Result<(), String> {
+pub fn add_tag_for_paths(paths: Vec<String>, tag: String, app_handle: AppHandle) -> Result<(), String> {
     paths.par_iter().for_each(|path| {
         let tag_clone = tag.clone();
         if let Err(e) = modify_tags_for_path(path, |tags| {
@@ -450,11 +501,16 @@ pub fn add_tag_for_paths(paths: Vec<String>, tag: String) -> Result<(), String>
             eprintln!("Failed to add tag to {}: {}", path, e);
         }
     });
+    invalidate_tag_usage_cache(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
-pub fn remove_tag_for_paths(paths: Vec<String>, tag: String) -> Result<(), String> {
+pub fn remove_tag_for_paths(
+    paths: Vec<String>,
+    tag: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     paths.par_iter().for_each(|path| {
         let tag_clone = tag.clone();
         if let Err(e) = modify_tags_for_path(path, |tags| {
@@ -463,11 +519,16 @@ pub fn remove_tag_for_paths(paths: Vec<String>, tag: String) -> Result<(), Strin
             eprintln!("Failed to remove tag from {}: {}", path, e);
         }
     });
+    invalidate_tag_usage_cache(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
-pub fn clear_ai_tags(root_path: String) -> Result<usize, String> {
+pub fn remove_tag_in_folder(
+    root_path: String,
+    tag: String,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
     if !Path::new(&root_path).exists() {
         return Err(format!("Root path does not exist: {}", root_path));
     }
@@ -478,6 +539,46 @@ pub fn clear_ai_tags(root_path: String) -> Result<usize, String> {
     for entry in walker.filter_map(|e| e.ok()) {
         let path = entry.path();
         if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rrdata") {
+            let _sidecar_lock = file_management::lock_sidecar(path);
+            if let Ok(content) = fs::read_to_string(path) {
+                if let Ok(mut metadata) = serde_json::from_str::<ImageMetadata>(&content) {
+                    if let Some(tags) = &mut metadata.tags {
+                        let original_len = tags.len();
+                        // Color labels are managed separately and never removed here
+                        tags.retain(|t| t != &tag || t.starts_with(COLOR_TAG_PREFIX));
+
+                        if tags.len() < original_len {
+                            if tags.is_empty() {
+                                metadata.tags = None;
+                            }
+                            if let Ok(json_string) = serde_json::to_string_pretty(&metadata) {
+                                if fs::write(path, json_string).is_ok() {
+                                    updated_count += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    invalidate_tag_usage_cache(&app_handle);
+    Ok(updated_count)
+}
+
+#[tauri::command]
+pub fn clear_ai_tags(root_path: String, app_handle: AppHandle) -> Result<usize, String> {
+    if !Path::new(&root_path).exists() {
+        return Err(format!("Root path does not exist: {}", root_path));
+    }
+
+    let mut updated_count = 0;
+    let walker = WalkDir::new(root_path).into_iter();
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rrdata") {
+            let _sidecar_lock = file_management::lock_sidecar(path);
             if let Ok(content) = fs::read_to_string(path) {
                 if let Ok(mut metadata) = serde_json::from_str::<ImageMetadata>(&content) {
                     if let Some(tags) = &mut metadata.tags {
@@ -502,11 +603,12 @@ pub fn clear_ai_tags(root_path: String) -> Result<usize, String> {
             }
         }
     }
+    invalidate_tag_usage_cache(&app_handle);
     Ok(updated_count)
 }
 
 #[tauri::command]
-pub fn clear_all_tags(root_path: String) -> Result<usize, String> {
+pub fn clear_all_tags(root_path: String, app_handle: AppHandle) -> Result<usize, String> {
     if !Path::new(&root_path).exists() {
         return Err(format!("Root path does not exist: {}", root_path));
     }
@@ -517,6 +619,7 @@ pub fn clear_all_tags(root_path: String) -> Result<usize, String> {
     for entry in walker.filter_map(|e| e.ok()) {
         let path = entry.path();
         if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rrdata") {
+            let _sidecar_lock = file_management::lock_sidecar(path);
             if let Ok(content) = fs::read_to_string(path) {
                 if let Ok(mut metadata) = serde_json::from_str::<ImageMetadata>(&content) {
                     if let Some(tags) = &mut metadata.tags {
@@ -539,5 +642,160 @@ pub fn clear_all_tags(root_path: String) -> Result<usize, String> {
             }
         }
     }
+    invalidate_tag_usage_cache(&app_handle);
     Ok(updated_count)
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TagUsage {
+    pub tag: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingTags {
+    pub tags: Vec<TagUsage>,
+    pub color_labels: Vec<TagUsage>,
+    /// False when the scan hit its time budget and only covers part of the library.
+    pub complete: bool,
+}
+
+fn sorted_usage(counts: HashMap<String, usize>) -> Vec<TagUsage> {
+    let mut usage: Vec<TagUsage> = counts
+        .into_iter()
+        .map(|(tag, count)| TagUsage { tag, count })
+        .collect();
+    usage.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    usage
+}
+
+/// Drops every cached tag listing. Called by anything that rewrites sidecar tags.
+pub fn invalidate_tag_usage_cache(app_handle: &AppHandle) {
+    app_handle
+        .state::<AppState>()
+        .tag_usage_cache
+        .lock()
+        .unwrap()
+        .clear();
+}
+
+/// Collects the distinct tags used under `root_path` with how many images carry each,
+/// most used first, for tag autocomplete. Color labels are reported separately. The
+/// sidecar scan stops after `time_budget_ms` on very large libraries, in which case
+/// `complete` is false. Complete results are cached until tags change; partial ones are
+/// rescanned on the next call.
+#[tauri::command]
+pub async fn list_existing_tags(
+    root_path: String,
+    time_budget_ms: Option<u64>,
+    app_handle: AppHandle,
+) -> Result<ExistingTags, String> {
+    if let Some(cached) = app_handle
+        .state::<AppState>()
+        .tag_usage_cache
+        .lock()
+        .unwrap()
+        .get(&root_path)
+    {
+        return Ok(cached.clone());
+    }
+
+    let root = Path::new(&root_path);
+    if !root.exists() {
+        return Err(format!("Root path does not exist: {}", root_path));
+    }
+
+    let budget = Duration::from_millis(time_budget_ms.unwrap_or(DEFAULT_TAG_SCAN_BUDGET_MS));
+    let app_handle_clone = app_handle.clone();
+    let root_clone = root.to_path_buf();
+    let existing = tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let library_ignore = file_management::LibraryIgnore::load(&root_clone, &app_handle_clone);
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        let mut color_counts: HashMap<String, usize> = HashMap::new();
+        let mut complete = true;
+
+        for entry in WalkDir::new(&root_clone)
+            .into_iter()
+            .filter_entry(|e| !library_ignore.is_ignored(e.path(), e.file_type().is_dir()))
+            .filter_map(|e| e.ok())
+        {
+            if started.elapsed() > budget {
+                complete = false;
+                break;
+            }
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("rrdata") {
+                continue;
+            }
+            let Some(tags) = fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<ImageMetadata>(&content).ok())
+                .and_then(|metadata| metadata.tags)
+            else {
+                continue;
+            };
+            for tag in tags {
+                match tag.strip_prefix(COLOR_TAG_PREFIX) {
+                    Some(color) => *color_counts.entry(color.to_string()).or_insert(0) += 1,
+                    None => *tag_counts.entry(tag).or_insert(0) += 1,
+                }
+            }
+        }
+
+        ExistingTags {
+            tags: sorted_usage(tag_counts),
+            color_labels: sorted_usage(color_counts),
+            complete,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if existing.complete {
+        app_handle
+            .state::<AppState>()
+            .tag_usage_cache
+            .lock()
+            .unwrap()
+            .insert(root_path, existing.clone());
+    }
+    Ok(existing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_top_tags;
+
+    fn candidates() -> Vec<(String, f32)> {
+        [
+            ("beach", 0.62),
+            ("dog", 0.91),
+            ("sunset", 0.40),
+            ("portrait", 0.05),
+            ("ocean", 0.75),
+        ]
+        .into_iter()
+        .map(|(tag, prob)| (tag.to_string(), prob))
+        .collect()
+    }
+
+    #[test]
+    fn drops_candidates_at_or_below_threshold() {
+        let tags = select_top_tags(candidates(), 0.40, 10);
+        assert_eq!(tags, vec!["dog", "ocean", "beach"]);
+    }
+
+    #[test]
+    fn keeps_only_the_highest_scoring_tags() {
+        let tags = select_top_tags(candidates(), 0.0, 2);
+        assert_eq!(tags, vec!["dog", "ocean"]);
+    }
+
+    #[test]
+    fn returns_nothing_when_no_candidate_passes() {
+        assert!(select_top_tags(candidates(), 0.95, 5).is_empty());
+        assert!(select_top_tags(Vec::new(), 0.0, 5).is_empty());
+    }
+}
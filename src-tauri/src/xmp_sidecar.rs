@@ -0,0 +1,91 @@
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const XMP_NAMESPACES: &[(&str, &str)] = &[
+    ("exif", "http://ns.adobe.com/exif/1.0/"),
+    ("tiff", "http://ns.adobe.com/tiff/1.0/"),
+    ("xmp", "http://ns.adobe.com/xap/1.0/"),
+];
+
+pub fn xmp_sidecar_path(image_path: &Path) -> PathBuf {
+    image_path.with_extension("xmp")
+}
+
+pub fn escape_xml_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape_xml_attr(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Reads a simple `prefix:Name="value"` attribute from XMP content.
+pub fn read_xmp_attribute(xmp_content: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(r#"\s{}="([^"]*)""#, regex::escape(name))).ok()?;
+    re.captures(xmp_content)
+        .and_then(|caps| caps.get(1))
+        .map(|m| unescape_xml_attr(m.as_str()))
+}
+
+pub fn read_xmp_sidecar_attribute(image_path: &Path, name: &str) -> Option<String> {
+    let content = fs::read_to_string(xmp_sidecar_path(image_path)).ok()?;
+    read_xmp_attribute(&content, name)
+}
+
+fn new_xmp_document() -> String {
+    let namespaces: String = XMP_NAMESPACES
+        .iter()
+        .map(|(prefix, uri)| format!("\n    xmlns:{}=\"{}\"", prefix, uri))
+        .collect();
+    format!(
+        "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\" x:xmptk=\"RapidRAW\">\n <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n  <rdf:Description rdf:about=\"\"{}/>\n </rdf:RDF>\n</x:xmpmeta>\n",
+        namespaces
+    )
+}
+
+/// Sets simple attributes on the first `rdf:Description` of an image's XMP sidecar,
+/// creating the sidecar if needed and leaving all other content untouched.
+pub fn update_xmp_sidecar(image_path: &Path, attributes: &[(&str, String)]) -> Result<(), String> {
+    let sidecar_path = xmp_sidecar_path(image_path);
+    let mut content = if sidecar_path.exists() {
+        fs::read_to_string(&sidecar_path)
+            .map_err(|e| format!("Failed to read XMP sidecar: {}", e))?
+    } else {
+        new_xmp_document()
+    };
+
+    let description_start = content
+        .find("<rdf:Description")
+        .ok_or_else(|| "XMP sidecar has no rdf:Description element".to_string())?;
+    let insert_at = description_start + "<rdf:Description".len();
+
+    for (prefix, uri) in XMP_NAMESPACES {
+        let declaration = format!("xmlns:{}=", prefix);
+        if !content.contains(&declaration) {
+            content.insert_str(insert_at, &format!("\n    {}\"{}\"", declaration, uri));
+        }
+    }
+
+    for (name, value) in attributes {
+        let escaped = escape_xml_attr(value);
+        let re = Regex::new(&format!(r#"(\s){}="[^"]*""#, regex::escape(name)))
+            .map_err(|e| e.to_string())?;
+        if re.is_match(&content) {
+            let replacement = format!("${{1}}{}=\"{}\"", name, escaped.replace('$', "$$"));
+            content = re.replace(&content, replacement.as_str()).into_owned();
+        } else {
+            content.insert_str(insert_at, &format!("\n    {}=\"{}\"", name, escaped));
+        }
+    }
+
+    fs::write(&sidecar_path, content).map_err(|e| format!("Failed to write XMP sidecar: {}", e))
+}
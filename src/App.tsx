@@ -14,6 +14,7 @@ import {
   Check,
   ClipboardPaste,
   Copy,
+  Columns2,
   CopyPlus,
   Edit,
   FileEdit,
@@ -21,12 +22,15 @@ import {
   FolderInput,
   FolderPlus,
   Images,
+  Layers,
+  Focus,
   LayoutTemplate,
   Redo,
   RotateCcw,
   Star,
   Save,
   Palette,
+  Pipette,
   Tag,
   Trash2,
   Undo,
@@ -40,7 +44,7 @@ import {
 import TitleBar from './window/TitleBar';
 import CommunityPage from './components/panel/CommunityPage';
 import MainLibrary from './components/panel/MainLibrary';
-import FolderTree from './components/panel/FolderTree';
+import FolderTree, { mergeFolderNode } from './components/panel/FolderTree';
 import Editor from './components/panel/Editor';
 import Controls from './components/panel/right/ControlsPanel';
 import { useThumbnails } from './hooks/useThumbnails';
@@ -63,6 +67,7 @@ import ImportSettingsModal from './components/modals/ImportSettingsModal';
 import RenameFileModal from './components/modals/RenameFileModal';
 import PanoramaModal from './components/modals/PanoramaModal';
 import DenoiseModal from './components/modals/DenoiseModal';
+import CompareModal from './components/modals/CompareModal';
 import CollageModal from './components/modals/CollageModal';
 import CopyPasteSettingsModal from './components/modals/CopyPasteSettingsModal';
 import CullingModal from './components/modals/CullingModal';
@@ -81,7 +86,7 @@ import {
   PasteMode,
   CopyPasteSettings,
 } from './utils/adjustments';
-import { generatePaletteFromImage } from './utils/palette';
+import { AdaptiveThemeColor, paletteFromThemeColor } from './utils/palette';
 import { useKeyboardShortcuts } from './hooks/useKeyboardShortcuts';
 import { THEMES, DEFAULT_THEME_ID, ThemeProps } from './utils/themes';
 import { SubMask, ToolType } from './components/panel/right/Masks';
@@ -95,6 +100,8 @@ import {
 import {
   AppSettings,
   BrushSettings,
+  DisplayInfo,
+  FileHealth,
   FilterCriteria,
   Invokes,
   ImageFile,
@@ -164,6 +171,27 @@ interface PanoramaModalState {
   stitchingSourcePaths: Array<string>;
 }
 
+interface HdrModalState {
+  error: string | null;
+  finalImageBase64: string | null;
+  isOpen: boolean;
+  progressMessage: string | null;
+  sourcePaths: Array<string>;
+}
+
+interface FocusStackModalState {
+  error: string | null;
+  finalImageBase64: string | null;
+  isOpen: boolean;
+  progressMessage: string | null;
+  sourcePaths: Array<string>;
+}
+
+interface HdrBracketSet {
+  paths: Array<string>;
+  relativeExposures: Array<number>;
+}
+
 interface DenoiseModalState {
   isOpen: boolean;
   isProcessing: boolean;
@@ -183,6 +211,7 @@ interface CullingModalState {
 }
 
 interface LutData {
+  dimension: 'oneD' | 'threeD' | 'shaperThreeD';
   size: number;
 }
 
@@ -194,6 +223,8 @@ interface SearchCriteria {
 
 const DEBUG = false;
 const REVOCATION_DELAY = 5000;
+// Pinned folders can be whole drives; deeper levels load when a folder is selected or expanded
+const PINNED_FOLDER_TREE_MAX_DEPTH = 4;
 
 const useDelayedRevokeBlobUrl = (url: string | null | undefined) => {
   const previousUrlRef = useRef<string | null | undefined>(null);
@@ -229,6 +260,16 @@ const getParentDir = (filePath: string): string => {
   return filePath.substring(0, lastSeparatorIndex);
 };
 
+const differsOnlyInRating = (previous: Adjustments, next: Adjustments): boolean =>
+  previous !== next &&
+  Object.keys({ ...previous, ...next }).every(
+    (key) => key === 'rating' || previous[key as keyof Adjustments] === next[key as keyof Adjustments],
+  );
+
+// The tree root (current library root or pinned folder) whose `.rapidrawignore` applies to `path`.
+const getLibraryRoot = (path: string, roots: Array<string | null>): string =>
+  (roots.filter(Boolean) as string[]).find((root) => path.startsWith(root)) ?? path;
+
 const useAsyncThrottle = <T extends unknown[]>(
   fn: (...args: T) => Promise<void>,
   deps: any[] = []
@@ -320,6 +361,7 @@ function App() {
   });
   const [isSliderDragging, setIsSliderDragging] = useState(false);
   const dragIdleTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const lastAppliedAdjustmentsRef = useRef<{ path: string; adjustments: Adjustments } | null>(null);
   const [isFullScreen, setIsFullScreen] = useState(false);
   const [isFullScreenLoading, setIsFullScreenLoading] = useState(false);
   const [fullScreenUrl, setFullScreenUrl] = useState<string | null>(null);
@@ -362,6 +404,7 @@ function App() {
   }, []);
 
   const [initialFitScale, setInitialFitScale] = useState<number | null>(null);
+  const [previewDimension, setPreviewDimension] = useState<number | null>(null);
   const [renderedRightPanel, setRenderedRightPanel] = useState<Panel | null>(activeRightPanel);
   const [collapsibleSectionsState, setCollapsibleSectionsState] = useState<CollapsibleSectionsState>({
     basic: true,
@@ -382,6 +425,9 @@ function App() {
   const [copiedAdjustments, setCopiedAdjustments] = useState<Adjustments | null>(null);
   const [isStraightenActive, setIsStraightenActive] = useState(false);
   const [isWbPickerActive, setIsWbPickerActive] = useState(false);
+  const [grayCardReference, setGrayCardReference] = useState<{ path: string; point: { x: number; y: number } } | null>(
+    null,
+  );
   const [copiedFilePaths, setCopiedFilePaths] = useState<Array<string>>([]);
   const [aiModelDownloadStatus, setAiModelDownloadStatus] = useState<string | null>(null);
   const [copiedSectionAdjustments, setCopiedSectionAdjustments] = useState(null);
@@ -390,6 +436,7 @@ function App() {
   const [isPasted, setIsPasted] = useState(false);
   const [isIndexing, setIsIndexing] = useState(false);
   const [indexingProgress, setIndexingProgress] = useState<Progress>({ current: 0, total: 0 });
+  const [pregenerationProgress, setPregenerationProgress] = useState<Progress | null>(null);
   const [searchCriteria, setSearchCriteria] = useState<SearchCriteria>({
     tags: [],
     text: '',
@@ -417,6 +464,21 @@ function App() {
     progressMessage: '',
     stitchingSourcePaths: [],
   });
+  const [hdrModalState, setHdrModalState] = useState<HdrModalState>({
+    error: null,
+    finalImageBase64: null,
+    isOpen: false,
+    progressMessage: '',
+    sourcePaths: [],
+  });
+  const [focusStackModalState, setFocusStackModalState] = useState<FocusStackModalState>({
+    error: null,
+    finalImageBase64: null,
+    isOpen: false,
+    progressMessage: '',
+    sourcePaths: [],
+  });
+  const [comparePaths, setComparePaths] = useState<Array<string> | null>(null);
   const [denoiseModalState, setDenoiseModalState] = useState<DenoiseModalState>({
     isOpen: false,
     isProcessing: false,
@@ -445,11 +507,13 @@ function App() {
   const { showContextMenu } = useContextMenu();
   const imagePathList = useMemo(() => imageList.map((f: ImageFile) => f.path), [imageList]);
   const [thumbnails, setThumbnails] = useState<Record<string, string>>({});
+  const [thumbnailHealth, setThumbnailHealth] = useState<Record<string, FileHealth>>({});
   const { loading: isThumbnailsLoading } = useThumbnails(imageList, setThumbnails);
   const transformWrapperRef = useRef<any>(null);
   const isProgrammaticZoom = useRef(false);
   const isInitialMount = useRef(true);
   const currentFolderPathRef = useRef<string>(currentFolderPath);
+  const currentLibraryRootRef = useRef<string | null>(null);
 
   const [exportState, setExportState] = useState<ExportState>({
     errorMessage: '',
@@ -515,9 +579,15 @@ function App() {
     setIsWbPickerActive((prev) => !prev);
   }, []);
 
-  const handleWbPicked = useCallback(() => {
-    //setIsWbPickerActive(false); // lets keep it active
-  }, []);
+  const handleWbPicked = useCallback(
+    (point: { x: number; y: number }) => {
+      //setIsWbPickerActive(false); // lets keep it active
+      if (selectedImage) {
+        setGrayCardReference({ path: selectedImage.path, point });
+      }
+    },
+    [selectedImage?.path],
+  );
 
   useEffect(() => {
     setLiveAdjustments(historyAdjustments);
@@ -1260,6 +1330,28 @@ function App() {
     };
   }, []);
 
+  // The editor preview is sized to the monitor the window is on, so re-query it whenever
+  // the window moves to another display or its scale factor changes.
+  useEffect(() => {
+    const appWindow = getCurrentWindow();
+    const updateDisplayInfo = debounce(async () => {
+      try {
+        const info: DisplayInfo = await invoke(Invokes.GetDisplayInfo);
+        setPreviewDimension(info.previewDimension);
+      } catch (err) {
+        console.error('Failed to read display info:', err);
+      }
+    }, 250);
+    updateDisplayInfo();
+
+    const unlistenPromises = [appWindow.onMoved(updateDisplayInfo), appWindow.onScaleChanged(updateDisplayInfo)];
+
+    return () => {
+      updateDisplayInfo.cancel();
+      unlistenPromises.forEach((unlistenPromise) => unlistenPromise.then((unlisten: any) => unlisten()));
+    };
+  }, []);
+
   const handleLutSelect = useCallback(
     async (path: string) => {
       try {
@@ -1360,7 +1452,12 @@ function App() {
         }
         if (settings?.pinnedFolders && settings.pinnedFolders.length > 0) {
           try {
-            const trees = await invoke(Invokes.GetPinnedFolderTrees, { paths: settings.pinnedFolders });
+            const trees = await invoke(Invokes.GetPinnedFolderTrees, {
+              paths: settings.pinnedFolders,
+              countImages: settings.showFolderImageCounts ?? false,
+              hideEmpty: settings.hideEmptyFolders ?? false,
+              maxDepth: PINNED_FOLDER_TREE_MAX_DEPTH,
+            });
             setPinnedFolderTrees(trees);
           } catch (err) {
             console.error('Failed to load pinned folder trees:', err);
@@ -1434,8 +1531,8 @@ function App() {
 
   useEffect(() => {
     if (appSettings?.adaptiveEditorTheme && selectedImage && finalPreviewUrl) {
-      generatePaletteFromImage(finalPreviewUrl)
-        .then(setAdaptivePalette)
+      invoke(Invokes.ComputeAdaptiveThemeColor)
+        .then((color: any) => setAdaptivePalette(paletteFromThemeColor(color as AdaptiveThemeColor)))
         .catch((err) => {
           const darkTheme = THEMES.find((t) => t.id === Theme.Dark);
           setAdaptivePalette(darkTheme ? darkTheme.cssVariables : null);
@@ -1483,10 +1580,18 @@ function App() {
     return () => clearTimeout(timer);
   }, [theme]);
 
+  const folderTreeOptions = useMemo(
+    () => ({
+      countImages: appSettings?.showFolderImageCounts ?? false,
+      hideEmpty: appSettings?.hideEmptyFolders ?? false,
+    }),
+    [appSettings?.showFolderImageCounts, appSettings?.hideEmptyFolders],
+  );
+
   const refreshAllFolderTrees = useCallback(async () => {
     if (rootPath) {
       try {
-        const treeData = await invoke(Invokes.GetFolderTree, { path: rootPath });
+        const treeData = await invoke(Invokes.GetFolderTree, { path: rootPath, ...folderTreeOptions });
         setFolderTree(treeData);
       } catch (err) {
         console.error('Failed to refresh main folder tree:', err);
@@ -1497,16 +1602,62 @@ function App() {
     const currentPins = appSettings?.pinnedFolders || [];
     if (currentPins.length > 0) {
       try {
-        const trees = await invoke(Invokes.GetPinnedFolderTrees, { paths: currentPins });
+        const trees = await invoke(Invokes.GetPinnedFolderTrees, {
+          paths: currentPins,
+          maxDepth: PINNED_FOLDER_TREE_MAX_DEPTH,
+          ...folderTreeOptions,
+        });
         setPinnedFolderTrees(trees);
       } catch (err) {
         console.error('Failed to refresh pinned folder trees:', err);
       }
     }
-  }, [rootPath, appSettings?.pinnedFolders]);
+  }, [rootPath, appSettings?.pinnedFolders, folderTreeOptions]);
+
+  useEffect(() => {
+    if (isInitialMount.current) {
+      return;
+    }
+    refreshAllFolderTrees();
+  }, [folderTreeOptions.countImages, folderTreeOptions.hideEmpty]);
 
   const pinnedFolders = useMemo(() => appSettings?.pinnedFolders || [], [appSettings]);
 
+  useEffect(() => {
+    currentLibraryRootRef.current = currentFolderPath
+      ? getLibraryRoot(currentFolderPath, [rootPath, ...pinnedFolders])
+      : null;
+  }, [currentFolderPath, rootPath, pinnedFolders]);
+
+  const refreshFolderNode = useCallback(
+    async (path: string) => {
+      const { countImages, hideEmpty } = folderTreeOptions;
+      const treeRoots = ([rootPath, ...pinnedFolders].filter(Boolean) as string[]).filter((root) =>
+        path.startsWith(root),
+      );
+      for (const root of Array.from(new Set(treeRoots))) {
+        try {
+          const refreshed: any = await invoke(Invokes.RefreshFolderNode, {
+            path,
+            countImages: countImages || hideEmpty,
+            rootPath: root,
+          });
+          if (root === rootPath) {
+            setFolderTree((prev: any) => prev && mergeFolderNode(prev, refreshed, hideEmpty));
+          }
+          if (pinnedFolders.includes(root)) {
+            setPinnedFolderTrees((prev) =>
+              prev.map((tree) => (tree.path === root ? mergeFolderNode(tree, refreshed, hideEmpty) : tree)),
+            );
+          }
+        } catch (err) {
+          console.error('Failed to refresh folder node:', err);
+        }
+      }
+    },
+    [rootPath, pinnedFolders, folderTreeOptions],
+  );
+
   const handleTogglePinFolder = useCallback(async (path: string) => {
     if (!appSettings) return;
     const currentPins = appSettings.pinnedFolders || [];
@@ -1522,12 +1673,16 @@ function App() {
     handleSettingsChange({ ...appSettings, pinnedFolders: newPins });
 
     try {
-      const trees = await invoke(Invokes.GetPinnedFolderTrees, { paths: newPins });
+      const trees = await invoke(Invokes.GetPinnedFolderTrees, {
+        paths: newPins,
+        maxDepth: PINNED_FOLDER_TREE_MAX_DEPTH,
+        ...folderTreeOptions,
+      });
       setPinnedFolderTrees(trees);
     } catch (err) {
       console.error('Failed to refresh pinned folders:', err);
     }
-  }, [appSettings, handleSettingsChange]);
+  }, [appSettings, handleSettingsChange, folderTreeOptions]);
 
   const handleActiveTreeSectionChange = (section: string | null) => {
     setActiveTreeSection(section);
@@ -1574,6 +1729,7 @@ function App() {
             }
             return newSet;
           });
+          refreshFolderNode(path);
         }
 
         if (isNewRoot) {
@@ -1583,7 +1739,7 @@ function App() {
           setIsTreeLoading(true);
           handleSettingsChange({ ...appSettings, lastRootPath: path } as AppSettings);
           try {
-            const treeData = await invoke(Invokes.GetFolderTree, { path });
+            const treeData = await invoke(Invokes.GetFolderTree, { path, ...folderTreeOptions });
             setFolderTree(treeData);
           } catch (err) {
             console.error('Failed to load folder tree:', err);
@@ -1607,7 +1763,8 @@ function App() {
         const command =
           libraryViewMode === LibraryViewMode.Recursive ? Invokes.ListImagesRecursive : Invokes.ListImagesInDir;
 
-        const files: ImageFile[] = await invoke(command, { path });
+        const libraryRoot = isNewRoot ? path : getLibraryRoot(path, [rootPath, ...pinnedFolders]);
+        const files: ImageFile[] = await invoke(command, { path, rootPath: libraryRoot });
         const exifSortKeys = ['date_taken', 'iso', 'shutter_speed', 'aperture', 'focal_length'];
         const isExifSortActive = exifSortKeys.includes(sortCriteria.key);
         const shouldReadExif = appSettings?.enableExifReading ?? false;
@@ -1660,6 +1817,8 @@ function App() {
       sortCriteria.key,
       pinnedFolders,
       libraryViewMode,
+      folderTreeOptions,
+      refreshFolderNode,
     ],
   );
 
@@ -1673,7 +1832,10 @@ function App() {
       const command =
         libraryViewMode === LibraryViewMode.Recursive ? Invokes.ListImagesRecursive : Invokes.ListImagesInDir;
 
-      const files: ImageFile[] = await invoke(command, { path: currentFolderPath });
+      const files: ImageFile[] = await invoke(command, {
+        path: currentFolderPath,
+        rootPath: getLibraryRoot(currentFolderPath, [rootPath, ...pinnedFolders]),
+      });
       const exifSortKeys = ['date_taken', 'iso', 'shutter_speed', 'aperture', 'focal_length'];
       const isExifSortActive = exifSortKeys.includes(sortCriteria.key);
       const shouldReadExif = appSettings?.enableExifReading ?? false;
@@ -1723,9 +1885,12 @@ function App() {
       console.error('Failed to refresh image list:', err);
       setError('Failed to refresh image list.');
     }
-  }, [currentFolderPath, sortCriteria.key, appSettings?.enableExifReading, libraryViewMode]);
+  }, [currentFolderPath, sortCriteria.key, appSettings?.enableExifReading, libraryViewMode, rootPath, pinnedFolders]);
 
   const handleToggleFolder = useCallback((path: string) => {
+    if (!expandedFolders.has(path)) {
+      refreshFolderNode(path);
+    }
     setExpandedFolders((prev) => {
       const newSet = new Set(prev);
       if (newSet.has(path)) {
@@ -1735,7 +1900,7 @@ function App() {
       }
       return newSet;
     });
-  }, []);
+  }, [expandedFolders, refreshFolderNode]);
 
   useEffect(() => {
     if (isInitialMount.current || !appSettings || !rootPath) {
@@ -1969,7 +2134,10 @@ function App() {
         const blob = new Blob([imageData], { type: 'image/jpeg' });
         url = URL.createObjectURL(blob);
         setFullScreenUrl(url);
-      } catch (e) {
+      } catch (e: any) {
+        if (e?.code === 'cancelled') {
+          return;
+        }
         console.error('Failed to generate fullscreen preview:', e);
         setError('Failed to generate full screen preview.');
       } finally {
@@ -2088,6 +2256,10 @@ function App() {
       });
 
       if (selectedImage && pathsToRate.includes(selectedImage.path)) {
+        // A rating doesn't change the render: finish any pending edit first so it can't
+        // overwrite the new rating. The adjustments effect skips rating-only changes.
+        debouncedApplyAdjustments.flush();
+        debouncedSave.flush();
         setAdjustments((prev: Adjustments) => ({ ...prev, rating: finalRating }));
       }
 
@@ -2095,12 +2267,10 @@ function App() {
         setLibraryActiveAdjustments((prev) => ({ ...prev, rating: finalRating }));
       }
 
-      invoke(Invokes.ApplyAdjustmentsToPaths, { paths: pathsToRate, adjustments: { rating: finalRating } }).catch(
-        (err) => {
-          console.error('Failed to apply rating to paths:', err);
-          setError(`Failed to apply rating: ${err}`);
-        },
-      );
+      invoke(Invokes.SetRatingForPaths, { paths: pathsToRate, rating: finalRating }).catch((err) => {
+        console.error('Failed to apply rating to paths:', err);
+        setError(`Failed to apply rating: ${err}`);
+      });
     },
     [
       multiSelectedPaths,
@@ -2109,6 +2279,8 @@ function App() {
       adjustments.rating,
       libraryActiveAdjustments.rating,
       setAdjustments,
+      debouncedApplyAdjustments,
+      debouncedSave,
     ],
   );
 
@@ -2221,7 +2393,7 @@ function App() {
       const request = { cancelled: false };
       fullResRequestRef.current = request;
 
-      invoke(Invokes.GenerateFullscreenPreview, {
+      invoke(Invokes.GenerateFullResolutionPreview, {
         jsAdjustments: currentAdjustments,
       })
         .then((imageData: Uint8Array) => {
@@ -2235,7 +2407,7 @@ function App() {
           }
         })
         .catch((error: any) => {
-          if (!request.cancelled) {
+          if (!request.cancelled && error?.code !== 'cancelled') {
             console.error('Failed to generate full resolution preview:', error);
             setIsFullResolution(false);
             setFullResolutionUrl(null);
@@ -2480,12 +2652,34 @@ function App() {
           const { path, data, rating } = event.payload;
           if (data) {
             setThumbnails((prev) => ({ ...prev, [path]: data }));
+            setThumbnailHealth((prev) => {
+              if (!(path in prev)) {
+                return prev;
+              }
+              const { [path]: _, ...rest } = prev;
+              return rest;
+            });
           }
           if (rating !== undefined) {
             setImageRatings((prev) => ({ ...prev, [path]: rating }));
           }
         }
       }),
+      listen('thumbnail-failed', (event: any) => {
+        if (isEffectActive) {
+          const { path, status } = event.payload;
+          setThumbnailHealth((prev) => (prev[path] === status ? prev : { ...prev, [path]: status }));
+        }
+      }),
+      listen('image-edit-state-changed', (event: any) => {
+        if (isEffectActive) {
+          const { path, isEdited, rating } = event.payload;
+          setImageList((prevList: Array<ImageFile>) =>
+            prevList.map((image: ImageFile) => (image.path === path ? { ...image, is_edited: isEdited } : image)),
+          );
+          setImageRatings((prev) => ({ ...prev, [path]: rating }));
+        }
+      }),
       listen('ai-model-download-start', (event: any) => {
         if (isEffectActive) {
           setAiModelDownloadStatus(event.payload);
@@ -2514,7 +2708,10 @@ function App() {
           if (currentFolderPathRef.current) {
             const refreshImageList = async () => {
               try {
-                const list: ImageFile[] = await invoke(Invokes.ListImagesInDir, { path: currentFolderPathRef.current });
+                const list: ImageFile[] = await invoke(Invokes.ListImagesInDir, {
+                  path: currentFolderPathRef.current,
+                  rootPath: currentLibraryRootRef.current,
+                });
                 if (Array.isArray(list)) {
                   setImageList(list);
                 }
@@ -2526,6 +2723,19 @@ function App() {
           }
         }
       }),
+      listen('thumbnail-pregeneration-progress', (event: any) => {
+        if (isEffectActive) {
+          setPregenerationProgress(event.payload);
+        }
+      }),
+      listen('thumbnail-pregeneration-complete', (event: any) => {
+        if (isEffectActive) {
+          setPregenerationProgress(null);
+          if (event.payload?.budgetReached) {
+            setError('Thumbnail cache is full. Some thumbnails were not prepared.');
+          }
+        }
+      }),
       listen('batch-export-progress', (event: any) => {
         if (isEffectActive) {
           setExportState((prev: ExportState) => ({ ...prev, progress: event.payload }));
@@ -2545,6 +2755,14 @@ function App() {
           }));
         }
       }),
+      listen('export-warning', (event: any) => {
+        if (isEffectActive) {
+          setExportState((prev: ExportState) => ({
+            ...prev,
+            warnings: [...(prev.warnings || []), { path: event.payload.path, message: event.payload.message }],
+          }));
+        }
+      }),
       listen('export-cancelled', () => {
         if (isEffectActive) {
           setExportState((prev: ExportState) => ({ ...prev, status: Status.Cancelled }));
@@ -2625,14 +2843,15 @@ function App() {
 
   useEffect(() => {
     if ([Status.Success, Status.Error, Status.Cancelled].includes(exportState.status)) {
-      const timeoutDuration = exportState.status === Status.Success ? 5000 : 3000;
+      const hasWarnings = (exportState.warnings?.length ?? 0) > 0;
+      const timeoutDuration = hasWarnings ? 10000 : exportState.status === Status.Success ? 5000 : 3000;
 
       const timer = setTimeout(() => {
         setExportState({ status: Status.Idle, progress: { current: 0, total: 0 }, errorMessage: '' });
       }, timeoutDuration);
       return () => clearTimeout(timer);
     }
-  }, [exportState.status]);
+  }, [exportState.status, exportState.warnings?.length]);
 
   useEffect(() => {
     if ([Status.Success, Status.Error].includes(importState.status)) {
@@ -2710,6 +2929,86 @@ function App() {
     };
   }, []);
 
+  useEffect(() => {
+    let isEffectActive = true;
+
+    const unlistenProgress = listen('hdr-progress', (event: any) => {
+      if (isEffectActive) {
+        setHdrModalState((prev: HdrModalState) => ({ ...prev, progressMessage: event.payload }));
+      }
+    });
+
+    const unlistenComplete = listen('hdr-complete', (event: any) => {
+      if (isEffectActive) {
+        const { base64 } = event.payload;
+        setHdrModalState((prev: HdrModalState) => ({
+          ...prev,
+          error: null,
+          finalImageBase64: base64,
+          progressMessage: 'HDR Ready',
+        }));
+      }
+    });
+
+    const unlistenError = listen('hdr-error', (event: any) => {
+      if (isEffectActive) {
+        setHdrModalState((prev: HdrModalState) => ({
+          ...prev,
+          error: String(event.payload),
+          finalImageBase64: null,
+          progressMessage: 'An error occurred.',
+        }));
+      }
+    });
+
+    return () => {
+      isEffectActive = false;
+      unlistenProgress.then((f: any) => f());
+      unlistenComplete.then((f: any) => f());
+      unlistenError.then((f: any) => f());
+    };
+  }, []);
+
+  useEffect(() => {
+    let isEffectActive = true;
+
+    const unlistenProgress = listen('focus-stack-progress', (event: any) => {
+      if (isEffectActive) {
+        setFocusStackModalState((prev: FocusStackModalState) => ({ ...prev, progressMessage: event.payload }));
+      }
+    });
+
+    const unlistenComplete = listen('focus-stack-complete', (event: any) => {
+      if (isEffectActive) {
+        const { base64 } = event.payload;
+        setFocusStackModalState((prev: FocusStackModalState) => ({
+          ...prev,
+          error: null,
+          finalImageBase64: base64,
+          progressMessage: 'Focus Stack Ready',
+        }));
+      }
+    });
+
+    const unlistenError = listen('focus-stack-error', (event: any) => {
+      if (isEffectActive) {
+        setFocusStackModalState((prev: FocusStackModalState) => ({
+          ...prev,
+          error: String(event.payload),
+          finalImageBase64: null,
+          progressMessage: 'An error occurred.',
+        }));
+      }
+    });
+
+    return () => {
+      isEffectActive = false;
+      unlistenProgress.then((f: any) => f());
+      unlistenComplete.then((f: any) => f());
+      unlistenError.then((f: any) => f());
+    };
+  }, []);
+
   useEffect(() => {
     let isEffectActive = true;
 
@@ -2761,6 +3060,7 @@ function App() {
     try {
       const savedPath: string = await invoke(Invokes.SavePanorama, {
         firstPathStr: panoramaModalState.stitchingSourcePaths[0],
+        sourcePaths: panoramaModalState.stitchingSourcePaths,
       });
       await refreshImageList();
       return savedPath;
@@ -2771,6 +3071,57 @@ function App() {
     }
   };
 
+  const handleMergeHdr = async (paths: Array<string>) => {
+    setHdrModalState({
+      error: null,
+      finalImageBase64: null,
+      isOpen: true,
+      progressMessage: 'Detecting exposure brackets...',
+      sourcePaths: [],
+    });
+    try {
+      const brackets: Array<HdrBracketSet> = await invoke(Invokes.DetectHdrBrackets, { paths });
+      if (brackets.length === 0) {
+        throw 'The selected images do not look like an exposure bracket.';
+      }
+      const bracket = brackets.reduce((largest, set) => (set.paths.length > largest.paths.length ? set : largest));
+      setHdrModalState((prev: HdrModalState) => ({ ...prev, sourcePaths: bracket.paths }));
+      await invoke(Invokes.MergeHdr, { paths: bracket.paths });
+    } catch (err) {
+      setHdrModalState((prev: HdrModalState) => ({ ...prev, error: String(err), progressMessage: 'Failed to start.' }));
+    }
+  };
+
+  const handleSaveHdr = async (): Promise<string> => {
+    try {
+      const savedPath: string = await invoke(Invokes.SaveHdr, {
+        firstPathStr: hdrModalState.sourcePaths[0],
+        sourcePaths: hdrModalState.sourcePaths,
+      });
+      await refreshImageList();
+      return savedPath;
+    } catch (err) {
+      console.error('Failed to save HDR image:', err);
+      setHdrModalState((prev: HdrModalState) => ({ ...prev, error: String(err) }));
+      throw err;
+    }
+  };
+
+  const handleSaveFocusStack = async (): Promise<string> => {
+    try {
+      const savedPath: string = await invoke(Invokes.SaveFocusStack, {
+        firstPathStr: focusStackModalState.sourcePaths[0],
+        sourcePaths: focusStackModalState.sourcePaths,
+      });
+      await refreshImageList();
+      return savedPath;
+    } catch (err) {
+      console.error('Failed to save focus stack:', err);
+      setFocusStackModalState((prev: FocusStackModalState) => ({ ...prev, error: String(err) }));
+      throw err;
+    }
+  };
+
   const handleApplyDenoise = useCallback(async (intensity: number) => {
     if (!denoiseModalState.targetPath) return;
     
@@ -2838,7 +3189,17 @@ function App() {
   }, [throttledInteractiveUpdate]);
 
   useEffect(() => {
-    if (!selectedImage?.isReady) return;
+    if (!selectedImage?.isReady) {
+      lastAppliedAdjustmentsRef.current = null;
+      return;
+    }
+
+    // Ratings are saved on their own and don't change the render
+    const lastApplied = lastAppliedAdjustmentsRef.current;
+    lastAppliedAdjustmentsRef.current = { path: selectedImage.path, adjustments };
+    if (lastApplied?.path === selectedImage.path && differsOnlyInRating(lastApplied.adjustments, adjustments)) {
+      return;
+    }
 
     if (dragIdleTimer.current) {
       clearTimeout(dragIdleTimer.current);
@@ -2880,6 +3241,14 @@ function App() {
     appSettings?.enableLivePreviews
   ]);
 
+  const lastPreviewDimensionRef = useRef<number | null>(null);
+  useEffect(() => {
+    const previous = lastPreviewDimensionRef.current;
+    lastPreviewDimensionRef.current = previewDimension;
+    if (previous === null || previous === previewDimension || !selectedImage?.isReady) return;
+    debouncedApplyAdjustments(adjustments);
+  }, [previewDimension]);
+
   useEffect(() => {
     if (activeRightPanel === Panel.Crop && selectedImage?.isReady) {
       debouncedGenerateUncroppedPreview(adjustments);
@@ -2923,7 +3292,7 @@ function App() {
 
       setIsTreeLoading(true);
       try {
-        const treeData = await invoke(Invokes.GetFolderTree, { path: root });
+        const treeData = await invoke(Invokes.GetFolderTree, { path: root, ...folderTreeOptions });
         setFolderTree(treeData);
       } catch (err) {
         console.error('Failed to restore folder tree:', err);
@@ -3101,8 +3470,10 @@ function App() {
             if (currentSelected && currentSelected.path === selectedImage.path) {
             return {
                 ...currentSelected,
+                colorProfile: loadImageResult.source_color_profile,
                 exif: loadImageResult.exif,
                 height: loadImageResult.height,
+                isEmbeddedPreview: loadImageResult.is_embedded_preview,
                 isRaw: loadImageResult.is_raw,
                 isReady: true,
                 metadata: loadImageResult.metadata,
@@ -3127,10 +3498,17 @@ function App() {
         }
         setLiveAdjustments(initialAdjusts);
         resetAdjustmentsHistory(initialAdjusts);
-        } catch (err) {
+        } catch (err: any) {
         if (isEffectActive) {
             console.error('Failed to load image:', err);
-            setError(`Failed to load image: ${err}`);
+            const message = err?.message ?? String(err);
+            if (err?.code === 'fileLocked') {
+              setError(`${message}. Close any app using it and try again.`);
+            } else if (err?.code === 'notFound') {
+              setError(`${message}. It may have been moved or deleted.`);
+            } else {
+              setError(`Failed to load image: ${message}`);
+            }
             setSelectedImage(null);
         }
         } finally {
@@ -3353,6 +3731,7 @@ function App() {
               initialTags: commonTags,
               onTagsChanged: handleTagsChanged,
               appSettings,
+              rootPath,
             },
           },
         ],
@@ -3475,33 +3854,50 @@ function App() {
       }
     };
 
+    const reloadAdjustmentsForSelection = async () => {
+      if (selectedImage && finalSelection.includes(selectedImage.path)) {
+        const metadata: Metadata = await invoke(Invokes.LoadMetadata, { path: selectedImage.path });
+        if (metadata.adjustments && !metadata.adjustments.is_null) {
+          const normalized = normalizeLoadedAdjustments(metadata.adjustments);
+          setLiveAdjustments(normalized);
+          resetAdjustmentsHistory(normalized);
+        }
+      }
+      if (libraryActivePath && finalSelection.includes(libraryActivePath)) {
+        const metadata: Metadata = await invoke(Invokes.LoadMetadata, { path: libraryActivePath });
+        if (metadata.adjustments && !metadata.adjustments.is_null) {
+          const normalized = normalizeLoadedAdjustments(metadata.adjustments);
+          setLibraryActiveAdjustments(normalized);
+        }
+      }
+    };
+
     const handleApplyAutoAdjustmentsToSelection = () => {
       if (finalSelection.length === 0) return;
 
       invoke(Invokes.ApplyAutoAdjustmentsToPaths, { paths: finalSelection })
-        .then(async () => {
-          if (selectedImage && finalSelection.includes(selectedImage.path)) {
-            const metadata: Metadata = await invoke(Invokes.LoadMetadata, { path: selectedImage.path });
-            if (metadata.adjustments && !metadata.adjustments.is_null) {
-              const normalized = normalizeLoadedAdjustments(metadata.adjustments);
-              setLiveAdjustments(normalized);
-              resetAdjustmentsHistory(normalized);
-            }
-          }
-          if (libraryActivePath && finalSelection.includes(libraryActivePath)) {
-            const metadata: Metadata = await invoke(Invokes.LoadMetadata, { path: libraryActivePath });
-            if (metadata.adjustments && !metadata.adjustments.is_null) {
-              const normalized = normalizeLoadedAdjustments(metadata.adjustments);
-              setLibraryActiveAdjustments(normalized);
-            }
-          }
-        })
+        .then(reloadAdjustmentsForSelection)
         .catch((err) => {
           console.error('Failed to apply auto adjustments to paths:', err);
           setError(`Failed to apply auto adjustments: ${err}`);
         });
     };
 
+    const handleApplyGrayCardToSelection = () => {
+      if (!grayCardReference || finalSelection.length === 0) return;
+
+      invoke(Invokes.ApplyReferenceWhiteBalance, {
+        referencePath: grayCardReference.path,
+        referencePoint: [grayCardReference.point.x, grayCardReference.point.y],
+        targetPaths: finalSelection,
+      })
+        .then(reloadAdjustmentsForSelection)
+        .catch((err) => {
+          console.error('Failed to apply reference white balance:', err);
+          setError(`Failed to apply gray card white balance: ${err}`);
+        });
+    };
+
     const onExportClick = () => {
       if (selectedImage) {
         if (selectedImage.path !== path) {
@@ -3576,6 +3972,18 @@ function App() {
             icon: Aperture,
             onClick: handleApplyAutoAdjustmentsToSelection,
           },
+          {
+            label: 'Apply Gray Card White Balance',
+            icon: Pipette,
+            disabled: !grayCardReference,
+            onClick: handleApplyGrayCardToSelection,
+          },
+          {
+            label: 'Compare Side by Side',
+            icon: Columns2,
+            disabled: selectionCount !== 2,
+            onClick: () => setComparePaths(finalSelection),
+          },
           {
             disabled: !isSingleSelection,
             icon: CopyPlus,
@@ -3619,6 +4027,33 @@ function App() {
               });
             },
           },
+          {
+            disabled: selectionCount < 2 || selectionCount > 9,
+            icon: Layers,
+            label: 'Merge to HDR',
+            onClick: () => handleMergeHdr(finalSelection),
+          },
+          {
+            disabled: selectionCount < 2 || selectionCount > 50,
+            icon: Focus,
+            label: 'Focus Stack',
+            onClick: () => {
+              setFocusStackModalState({
+                error: null,
+                finalImageBase64: null,
+                isOpen: true,
+                progressMessage: 'Starting focus stacking...',
+                sourcePaths: finalSelection,
+              });
+              invoke(Invokes.FocusStack, { paths: finalSelection }).catch((err) => {
+                setFocusStackModalState((prev: FocusStackModalState) => ({
+                  ...prev,
+                  error: String(err),
+                  progressMessage: 'Failed to start.',
+                }));
+              });
+            },
+          },
           {
             icon: LayoutTemplate,
             label: collageLabel,
@@ -3702,6 +4137,7 @@ function App() {
               initialTags: commonTags,
               onTagsChanged: handleTagsChanged,
               appSettings,
+              rootPath,
             },
           },
         ],
@@ -3856,6 +4292,25 @@ function App() {
         ],
       },
       { icon: FolderInput, label: 'Import Images', onClick: () => handleImportClick(targetPath) },
+      pregenerationProgress
+        ? {
+            icon: X,
+            label: `Stop Preparing Thumbnails (${pregenerationProgress.current}/${pregenerationProgress.total})`,
+            onClick: () => invoke(Invokes.CancelThumbnailPregeneration),
+          }
+        : {
+            icon: Images,
+            label: 'Prepare Thumbnails',
+            onClick: async () => {
+              try {
+                setPregenerationProgress({ current: 0, total: 0 });
+                await invoke(Invokes.PregenerateThumbnails, { root: targetPath });
+              } catch (err) {
+                setPregenerationProgress(null);
+                setError(`Failed to prepare thumbnails: ${err}`);
+              }
+            },
+          },
       { type: OPTION_SEPARATOR },
       {
         icon: Folder,
@@ -4037,6 +4492,7 @@ function App() {
             sortCriteria={sortCriteria}
             theme={theme}
             thumbnailAspectRatio={thumbnailAspectRatio}
+            thumbnailHealth={thumbnailHealth}
             thumbnails={thumbnails}
             thumbnailSize={thumbnailSize}
             onNavigateToCommunity={() => setActiveView('community')}
@@ -4289,8 +4745,10 @@ function App() {
                       {renderedRightPanel === Panel.Export && (
                         <ExportPanel
                           adjustments={adjustments}
+                          appSettings={appSettings}
                           exportState={exportState}
                           multiSelectedPaths={multiSelectedPaths}
+                          onSettingsChange={handleSettingsChange}
                           selectedImage={selectedImage}
                           setExportState={setExportState}
                         />
@@ -4377,11 +4835,13 @@ function App() {
             style={{ width: isLibraryExportPanelVisible ? `${rightPanelWidth}px` : '0px' }}
           >
             <LibraryExportPanel
+              appSettings={appSettings}
               exportState={exportState}
               imageList={sortedImageList}
               isVisible={isLibraryExportPanelVisible}
               multiSelectedPaths={multiSelectedPaths}
               onClose={() => setIsLibraryExportPanelVisible(false)}
+              onSettingsChange={handleSettingsChange}
               setExportState={setExportState}
             />
           </div>
@@ -4412,6 +4872,51 @@ function App() {
         onSave={handleSavePanorama}
         progressMessage={panoramaModalState.progressMessage}
       />
+      <PanoramaModal
+        error={hdrModalState.error}
+        finalImageBase64={hdrModalState.finalImageBase64}
+        isOpen={hdrModalState.isOpen}
+        kind="hdr"
+        onClose={() =>
+          setHdrModalState({
+            isOpen: false,
+            progressMessage: '',
+            finalImageBase64: null,
+            error: null,
+            sourcePaths: [],
+          })
+        }
+        onOpenFile={(path: string) => {
+          handleImageSelect(path);
+        }}
+        onSave={handleSaveHdr}
+        progressMessage={hdrModalState.progressMessage}
+      />
+      <PanoramaModal
+        error={focusStackModalState.error}
+        finalImageBase64={focusStackModalState.finalImageBase64}
+        isOpen={focusStackModalState.isOpen}
+        kind="focusStack"
+        onClose={() =>
+          setFocusStackModalState({
+            isOpen: false,
+            progressMessage: '',
+            finalImageBase64: null,
+            error: null,
+            sourcePaths: [],
+          })
+        }
+        onOpenFile={(path: string) => {
+          handleImageSelect(path);
+        }}
+        onSave={handleSaveFocusStack}
+        progressMessage={focusStackModalState.progressMessage}
+      />
+      <CompareModal
+        isOpen={comparePaths !== null}
+        onClose={() => setComparePaths(null)}
+        paths={comparePaths || []}
+      />
       <DenoiseModal 
         isOpen={denoiseModalState.isOpen}
         onClose={() => setDenoiseModalState(prev => ({ ...prev, isOpen: false }))}
@@ -1,7 +1,13 @@
+import Dropdown from '../ui/Dropdown';
 import Slider from '../ui/Slider';
 import { Adjustments, DetailsAdjustment, Effect } from '../../utils/adjustments';
 import { AppSettings } from '../ui/AppProperties';
 
+const sharpeningModeOptions = [
+  { label: 'Unsharp Mask', value: 'unsharp' },
+  { label: 'Ratio', value: 'ratio' },
+];
+
 interface DetailsPanelProps {
   adjustments: Adjustments;
   setAdjustments(adjustments: Partial<Adjustments>): any;
@@ -22,6 +28,14 @@ export default function DetailsPanel({
     setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [key]: numericValue }));
   };
 
+  const handleSharpeningRadiusChange = (value: string) => {
+    setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, sharpeningRadius: parseFloat(value) }));
+  };
+
+  const handleSharpeningModeChange = (mode: 'unsharp' | 'ratio') => {
+    setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, sharpeningMode: mode }));
+  };
+
   const adjustmentVisibility = appSettings?.adjustmentVisibility || {};
 
   return (
@@ -38,6 +52,39 @@ export default function DetailsPanel({
             value={adjustments.sharpness}
             onDragStateChange={onDragStateChange}
           />
+          {!isForMask && (
+            <>
+              <Slider
+                label="Radius"
+                max={3}
+                min={0.5}
+                onChange={(e: any) => handleSharpeningRadiusChange(e.target.value)}
+                step={0.1}
+                value={adjustments.sharpeningRadius ?? 2}
+                onDragStateChange={onDragStateChange}
+              />
+              {(adjustments.sharpeningMode ?? 'unsharp') === 'unsharp' && (
+                <Slider
+                  label="Threshold"
+                  max={100}
+                  min={0}
+                  onChange={(e: any) => handleAdjustmentChange(DetailsAdjustment.SharpeningThreshold, e.target.value)}
+                  step={1}
+                  value={adjustments.sharpeningThreshold ?? 0}
+                  onDragStateChange={onDragStateChange}
+                />
+              )}
+              <div className="flex items-center justify-between mt-2">
+                <span className="text-sm font-medium text-text-primary">Method</span>
+                <Dropdown
+                  className="w-40"
+                  onChange={handleSharpeningModeChange}
+                  options={sharpeningModeOptions}
+                  value={adjustments.sharpeningMode ?? 'unsharp'}
+                />
+              </div>
+            </>
+          )}
         </div>
       )}
 
@@ -34,6 +34,13 @@ export default function EffectsPanel({
     setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [key]: value }));
   };
 
+  const handleGrainReshuffle = () => {
+    setAdjustments((prev: Partial<Adjustments>) => ({
+      ...prev,
+      grainSeed: Math.floor(Math.random() * 0xffffffff),
+    }));
+  };
+
   const handleLutIntensityChange = (intensity: number) => {
     setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, lutIntensity: intensity }));
   };
@@ -199,6 +206,15 @@ export default function EffectsPanel({
                 value={adjustments.grainRoughness}
                 onDragStateChange={onDragStateChange}
               />
+              <div className="flex justify-end mt-1">
+                <button
+                  className="text-xs text-text-secondary hover:text-text-primary transition-colors"
+                  onClick={handleGrainReshuffle}
+                  title="Generate a new grain pattern"
+                >
+                  Reshuffle
+                </button>
+              </div>
             </div>
           )}
         </>
@@ -0,0 +1,142 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Loader2, X } from 'lucide-react';
+import { Invokes } from '../ui/AppProperties';
+
+interface CompareModalProps {
+  isOpen: boolean;
+  onClose(): void;
+  paths: Array<string>;
+}
+
+const COMPARISON_SIZE = 2048;
+
+const loadAdjustments = async (path: string) => {
+  const metadata: any = await invoke(Invokes.LoadMetadata, { path });
+  return metadata.adjustments && !metadata.adjustments.is_null ? metadata.adjustments : {};
+};
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+export default function CompareModal({ isOpen, onClose, paths }: CompareModalProps) {
+  const [isMounted, setIsMounted] = useState(false);
+  const [show, setShow] = useState(false);
+  const [imageUrl, setImageUrl] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setIsMounted(true);
+      const timer = setTimeout(() => {
+        setShow(true);
+      }, 10);
+      return () => clearTimeout(timer);
+    } else {
+      setShow(false);
+      const timer = setTimeout(() => {
+        setIsMounted(false);
+      }, 300);
+      return () => clearTimeout(timer);
+    }
+  }, [isOpen]);
+
+  const pathsKey = paths.join('\n');
+
+  useEffect(() => {
+    if (!isOpen || paths.length !== 2) {
+      return;
+    }
+
+    let isCancelled = false;
+    let url: string | null = null;
+
+    const generate = async () => {
+      setImageUrl(null);
+      setError(null);
+      try {
+        const [adjustmentsA, adjustmentsB] = await Promise.all(paths.map(loadAdjustments));
+        const imageData: Uint8Array = await invoke(Invokes.GenerateComparison, {
+          pathA: paths[0],
+          adjustmentsA,
+          pathB: paths[1],
+          adjustmentsB,
+          size: COMPARISON_SIZE,
+        });
+        if (isCancelled) {
+          return;
+        }
+        url = URL.createObjectURL(new Blob([imageData], { type: 'image/jpeg' }));
+        setImageUrl(url);
+      } catch (err) {
+        console.error('Failed to generate comparison:', err);
+        if (!isCancelled) {
+          setError(String(err));
+        }
+      }
+    };
+
+    generate();
+
+    return () => {
+      isCancelled = true;
+      if (url) {
+        URL.revokeObjectURL(url);
+      }
+    };
+  }, [isOpen, pathsKey]);
+
+  if (!isMounted) {
+    return null;
+  }
+
+  return (
+    <div
+      aria-modal="true"
+      className={`
+        fixed inset-0 flex items-center justify-center z-50
+        bg-black/60 backdrop-blur-sm
+        transition-opacity duration-300 ease-in-out
+        ${show ? 'opacity-100' : 'opacity-0'}
+      `}
+      onClick={onClose}
+      onKeyDown={(e) => e.key === 'Escape' && onClose()}
+      role="dialog"
+      tabIndex={-1}
+    >
+      <div
+        className={`
+          bg-surface rounded-lg shadow-xl p-4 w-[90vw] h-[85vh] flex flex-col
+          transform transition-all duration-300 ease-out
+          ${show ? 'scale-100 opacity-100 translate-y-0' : 'scale-95 opacity-0 -translate-y-4'}
+        `}
+        onClick={(e: any) => e.stopPropagation()}
+      >
+        <div className="flex items-center justify-between mb-3">
+          <div className="grid grid-cols-2 flex-1 text-sm text-text-secondary">
+            {paths.map((path) => (
+              <span className="truncate text-center" key={path}>
+                {fileName(path)}
+              </span>
+            ))}
+          </div>
+          <button
+            className="p-1.5 rounded-full text-text-secondary hover:text-text-primary hover:bg-bg-primary"
+            onClick={onClose}
+            title="Close"
+          >
+            <X size={18} />
+          </button>
+        </div>
+        <div className="flex-1 min-h-0 flex items-center justify-center bg-bg-primary rounded-md">
+          {imageUrl ? (
+            <img alt="Comparison" className="max-w-full max-h-full object-contain" src={imageUrl} />
+          ) : error ? (
+            <p className="text-sm text-red-400">{error}</p>
+          ) : (
+            <Loader2 className="h-8 w-8 animate-spin text-text-secondary" />
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -2,10 +2,35 @@ import { useState, useEffect, useCallback } from 'react';
 import { CheckCircle, XCircle, Loader2, Save } from 'lucide-react';
 import Button from '../ui/Button';
 
+const MODAL_TEXT = {
+  panorama: {
+    failed: 'Panorama Failed',
+    saved: 'Panorama Saved!',
+    alt: 'Stitched Panorama',
+    processing: 'Stitching Panorama',
+    save: 'Save Panorama',
+  },
+  hdr: {
+    failed: 'HDR Merge Failed',
+    saved: 'HDR Image Saved!',
+    alt: 'Merged HDR',
+    processing: 'Merging HDR',
+    save: 'Save HDR',
+  },
+  focusStack: {
+    failed: 'Focus Stacking Failed',
+    saved: 'Focus Stack Saved!',
+    alt: 'Focus Stack',
+    processing: 'Stacking Images',
+    save: 'Save Focus Stack',
+  },
+};
+
 interface PanoramaModalProps {
   error: string | null;
   finalImageBase64: string | null;
   isOpen: boolean;
+  kind?: keyof typeof MODAL_TEXT;
   onClose(): void;
   onOpenFile(path: string): void;
   onSave(): Promise<string>;
@@ -16,11 +41,13 @@ export default function PanoramaModal({
   error,
   finalImageBase64,
   isOpen,
+  kind = 'panorama',
   onClose,
   onOpenFile,
   onSave,
   progressMessage,
 }: PanoramaModalProps) {
+  const text = MODAL_TEXT[kind];
   const [isSaving, setIsSaving] = useState(false);
   const [savedPath, setSavedPath] = useState<string | null>(null);
   const [isMounted, setIsMounted] = useState(false);
@@ -82,7 +109,7 @@ export default function PanoramaModal({
       return (
         <>
           <XCircle className="w-16 h-16 text-red-500 mx-auto mb-4" />
-          <h3 className="text-lg font-semibold text-text-primary mb-2 text-center">Panorama Failed</h3>
+          <h3 className="text-lg font-semibold text-text-primary mb-2 text-center">{text.failed}</h3>
           <p className="text-sm text-text-secondary text-center p-2 rounded-md max-h-40 overflow-y-auto">
             {String(error)}
           </p>
@@ -96,11 +123,11 @@ export default function PanoramaModal({
           {savedPath && (
             <>
               <CheckCircle className="w-16 h-16 mx-auto mb-4" />
-              <h3 className="text-lg font-semibold text-text-primary mb-4 text-center">Panorama Saved!</h3>
+              <h3 className="text-lg font-semibold text-text-primary mb-4 text-center">{text.saved}</h3>
             </>
           )}
           <div className="w-full bg-bg-primary rounded-md overflow-hidden border border-surface">
-            <img src={finalImageBase64} alt="Stitched Panorama" className="w-full h-full object-contain" />
+            <img src={finalImageBase64} alt={text.alt} className="w-full h-full object-contain" />
           </div>
         </>
       );
@@ -111,7 +138,7 @@ export default function PanoramaModal({
         <div className="w-16 h-16 mx-auto mb-4">
           <Loader2 className="w-16 h-16 text-accent animate-spin" />
         </div>
-        <h3 className="text-lg font-semibold text-text-primary mb-2 text-center">Stitching Panorama</h3>
+        <h3 className="text-lg font-semibold text-text-primary mb-2 text-center">{text.processing}</h3>
         <p className="text-sm text-text-secondary text-center min-h-[1.25rem]">{progressMessage}</p>
       </>
     );
@@ -149,7 +176,7 @@ export default function PanoramaModal({
           </button>
           <Button onClick={handleSave} disabled={isSaving}>
             {isSaving ? <Loader2 className="animate-spin mr-2" /> : <Save size={16} className="mr-2" />}
-            {isSaving ? 'Saving...' : 'Save Panorama'}
+            {isSaving ? 'Saving...' : text.save}
           </Button>
         </>
       );
@@ -1,5 +1,6 @@
 import React, { useState, useEffect, useCallback, useRef, useMemo } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import {
   ArrowLeft,
   CheckCircle2,
@@ -23,6 +24,11 @@ interface CommunityPreset {
   adjustments: Record<string, any>;
 }
 
+interface CommunityPresetsResult {
+  presets: CommunityPreset[];
+  isCached: boolean;
+}
+
 const containerVariants = {
   hidden: { opacity: 1 },
   visible: {
@@ -62,11 +68,13 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
   const [presets, setPresets] = useState<CommunityPreset[]>([]);
   const [previews, setPreviews] = useState<Record<string, string | null>>({});
   const [isLoading, setIsLoading] = useState(true);
+  const [isShowingCachedPresets, setIsShowingCachedPresets] = useState(false);
   const [previewImagePaths, setPreviewImagePaths] = useState<string[]>([]);
   const [searchTerm, setSearchTerm] = useState('');
   const [sortBy, setSortBy] = useState('name');
   const [downloadStatus, setDownloadStatus] = useState<Record<string, 'idle' | 'downloading' | 'success'>>({});
   const [allPreviewsLoaded, setAllPreviewsLoaded] = useState(false);
+  const [previewProgress, setPreviewProgress] = useState<{ current: number; total: number } | null>(null);
 
   const previewsRef = useRef(previews);
   previewsRef.current = previews;
@@ -87,8 +95,9 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
     const fetchPresets = async () => {
       setIsLoading(true);
       try {
-        const communityPresets: CommunityPreset[] = await invoke(Invokes.FetchCommunityPresets);
-        setPresets(communityPresets);
+        const result: CommunityPresetsResult = await invoke(Invokes.FetchCommunityPresets);
+        setPresets(result.presets);
+        setIsShowingCachedPresets(result.isCached);
       } catch (error) {
         console.error("Failed to fetch community presets:", error);
       } finally {
@@ -138,6 +147,8 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
       return;
     }
 
+    let isCancelled = false;
+
     const generateAllPreviews = async () => {
       setAllPreviewsLoaded(false);
       try {
@@ -148,6 +159,9 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
             adjustments: { ...INITIAL_ADJUSTMENTS, ...p.adjustments }
           })),
         });
+        if (isCancelled) {
+          return;
+        }
 
         const newPreviews: Record<string, string | null> = {};
         for (const [presetName, imageData] of Object.entries(previewDataMap)) {
@@ -163,14 +177,34 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
       } catch (error) {
         console.error(`Failed to generate previews:`, error);
       } finally {
-        setAllPreviewsLoaded(true);
+        if (!isCancelled) {
+          setAllPreviewsLoaded(true);
+          setPreviewProgress(null);
+        }
       }
     };
 
     generateAllPreviews();
 
+    return () => {
+      isCancelled = true;
+    };
   }, [presets, previewImagePaths]);
 
+  useEffect(() => {
+    const unlistenProgress = listen('community-preview-progress', (event: any) => {
+      setPreviewProgress({ current: event.payload.current, total: event.payload.total });
+    });
+
+    return () => {
+      unlistenProgress.then((unlisten) => unlisten());
+      // A new generation run replaces the previous one on its own, so only leaving the page cancels.
+      invoke(Invokes.CancelCommunityPreviewGeneration).catch((err) =>
+        console.error('Failed to cancel community preview generation:', err),
+      );
+    };
+  }, []);
+
   const handleDownloadPreset = async (preset: CommunityPreset) => {
     setDownloadStatus(prev => ({ ...prev, [preset.name]: 'downloading' }));
     try {
@@ -217,6 +251,14 @@ const CommunityPage = ({ onBackToLibrary, imageList, currentFolderPath }: Commun
               <Users /> Community Presets
             </h1>
             <p className="text-sm text-text-secondary">Discover presets created by the community.</p>
+            {isShowingCachedPresets && (
+              <p className="text-xs text-text-tertiary">Offline – showing the last downloaded presets.</p>
+            )}
+            {previewProgress && !allPreviewsLoaded && (
+              <p className="text-xs text-text-tertiary">
+                Rendering previews ({previewProgress.current}/{previewProgress.total})...
+              </p>
+            )}
           </div>
         </div>
       </header>
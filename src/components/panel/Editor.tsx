@@ -65,7 +65,7 @@ interface EditorProps {
   fullResolutionUrl?: string | null;
   isLoadingFullRes?: boolean;
   isWbPickerActive?: boolean;
-  onWbPicked?: () => void;
+  onWbPicked?: (point: { x: number; y: number }) => void;
 }
 
 export default function Editor({
@@ -130,6 +130,8 @@ export default function Editor({
   const isInitialMount = useRef(true);
   const transformStateRef = useRef<TransformState>(transformState);
   transformStateRef.current = transformState;
+  const adjustmentsRef = useRef<Adjustments>(adjustments);
+  adjustmentsRef.current = adjustments;
 
   const isAnimating = useRef(false);
   const animationTimeoutRef = useRef<number | null>(null);
@@ -256,6 +258,12 @@ export default function Editor({
     }
   }, [imageRenderSize.scale, onInitialFitScale]);
 
+  // Range masks are measured on the rendered preview, so their overlay follows each new render
+  const activeMaskHasRange =
+    activeRightPanel === Panel.Masks &&
+    !!adjustments.masks.find((c: MaskContainer) => c.id === activeMaskContainerId)?.rangeMask;
+  const rangeMaskPreviewUrl = activeMaskHasRange ? finalPreviewUrl : null;
+
   const debouncedGenerateMaskOverlay = useCallback(
     debounce(async (maskDef, renderSize) => {
       if (!maskDef || !maskDef.visible || renderSize.width === 0) {
@@ -265,6 +273,7 @@ export default function Editor({
       try {
         const cropOffset = [adjustments.crop?.x || 0, adjustments.crop?.y || 0];
         const dataUrl: string = await invoke(Invokes.GenerateMaskOverlay, {
+          adjustments: adjustmentsRef.current,
           cropOffset,
           height: Math.round(renderSize.height),
           maskDef,
@@ -311,6 +320,7 @@ export default function Editor({
     adjustments.aiPatches,
     imageRenderSize,
     debouncedGenerateMaskOverlay,
+    rangeMaskPreviewUrl,
   ]);
 
   useEffect(() => {
@@ -5,9 +5,11 @@ import { useState, useMemo, useEffect } from 'react';
 
 export interface FolderTree {
   children: any;
+  image_count?: number;
   is_dir: boolean;
   name: string;
   path: string;
+  total_image_count?: number;
 }
 
 interface FolderTreeProps {
@@ -67,6 +69,32 @@ const filterTree = (node: FolderTree | null, query: string): FolderTree | null =
   return null;
 };
 
+// Merges a single-level `refresh_folder_node` result into a cached tree. Sub-folders that
+// were already loaded keep their children; ones that no longer exist on disk are dropped.
+// The refresh has no subtree totals, so `hideEmpty` can only prune new folders without images.
+export const mergeFolderNode = (node: FolderTree, refreshed: FolderTree, hideEmpty: boolean): FolderTree => {
+  if (node.path === refreshed.path) {
+    const cachedChildren = new Map<string, FolderTree>(
+      (node.children || []).map((child: FolderTree) => [child.path, child]),
+    );
+    const children = refreshed.children
+      .filter((child: FolderTree) => !hideEmpty || cachedChildren.has(child.path) || child.image_count !== 0)
+      .map((child: FolderTree) => {
+        const cached = cachedChildren.get(child.path);
+        return cached ? { ...cached, image_count: child.image_count } : child;
+      });
+    return { ...node, image_count: refreshed.image_count, children };
+  }
+
+  if (!node.children || !refreshed.path.startsWith(node.path)) {
+    return node;
+  }
+  return {
+    ...node,
+    children: node.children.map((child: FolderTree) => mergeFolderNode(child, refreshed, hideEmpty)),
+  };
+};
+
 const getAutoExpandedPaths = (node: FolderTree, paths: Set<string>) => {
   if (node.children && node.children.length > 0) {
     paths.add(node.path);
@@ -180,6 +208,12 @@ function TreeNode({
           {node.name}
         </span>
 
+        {node.image_count !== undefined && (
+          <span className="text-xs text-text-secondary tabular-nums flex-shrink-0 select-none">
+            {node.image_count}
+          </span>
+        )}
+
         {hasChildren && (
           <div className="p-0.5 rounded hover:bg-surface/50 cursor-pointer" onClick={handleFolderIconClick}>
             {isExpanded ? (
@@ -5,11 +5,13 @@ import { open } from '@tauri-apps/plugin-shell';
 import {
   AlertTriangle,
   Check,
+  FileWarning,
   Folder,
   FolderInput,
   Home,
   Image as ImageIcon,
   Loader2,
+  Lock,
   FolderOpen,
   RefreshCw,
   Settings,
@@ -27,6 +29,7 @@ import SettingsPanel from './SettingsPanel';
 import { ThemeProps, THEMES, DEFAULT_THEME_ID } from '../../utils/themes';
 import {
   AppSettings,
+  FileHealth,
   FilterCriteria,
   ImageFile,
   Invokes,
@@ -105,6 +108,7 @@ interface MainLibraryProps {
   sortCriteria: SortCriteria;
   theme: string;
   thumbnailAspectRatio: ThumbnailAspectRatio;
+  thumbnailHealth: Record<string, FileHealth>;
   thumbnails: Record<string, string>;
   thumbnailSize: ThumbnailSize;
   onNavigateToCommunity(): void;
@@ -131,6 +135,7 @@ interface ImageLayer {
 
 interface ThumbnailProps {
   data: string | undefined;
+  health?: FileHealth;
   isActive: boolean;
   isSelected: boolean;
   onContextMenu(e: any): void;
@@ -844,6 +849,7 @@ function ViewOptionsDropdown({
 
 function Thumbnail({
   data,
+  health,
   isActive,
   isSelected,
   onContextMenu,
@@ -971,13 +977,25 @@ function Thumbnail({
       <AnimatePresence>
         {layers.length === 0 && showPlaceholder && (
           <motion.div
-            className="absolute inset-0 w-full h-full flex items-center justify-center bg-surface"
+            className="absolute inset-0 w-full h-full flex flex-col items-center justify-center gap-1 bg-surface"
             initial={{ opacity: 0 }}
             animate={{ opacity: 1 }}
             exit={{ opacity: 0 }}
             transition={{ duration: 0.3, ease: 'easeInOut' }}
           >
-            <ImageIcon className="text-text-secondary animate-pulse" />
+            {health === FileHealth.Corrupt ? (
+              <>
+                <FileWarning className="text-red-400" />
+                <span className="text-xs text-text-secondary">Corrupt file</span>
+              </>
+            ) : health === FileHealth.Locked ? (
+              <>
+                <Lock className="text-text-secondary" />
+                <span className="text-xs text-text-secondary">File in use</span>
+              </>
+            ) : (
+              <ImageIcon className="text-text-secondary animate-pulse" />
+            )}
           </motion.div>
         )}
       </AnimatePresence>
@@ -1022,6 +1040,7 @@ const Row = ({ index, style, data }: any) => {
     onContextMenu,
     onImageClick,
     onImageDoubleClick,
+    thumbnailHealth,
     thumbnails,
     thumbnailAspectRatio,
     loadedThumbnails,
@@ -1090,6 +1109,7 @@ const Row = ({ index, style, data }: any) => {
         >
           <Thumbnail
             data={thumbnails[imageFile.path]}
+            health={thumbnailHealth[imageFile.path]}
             isActive={activePath === imageFile.path}
             isSelected={multiSelectedPaths.includes(imageFile.path)}
             onContextMenu={(e: any) => onContextMenu(e, imageFile.path)}
@@ -1146,6 +1166,7 @@ export default function MainLibrary({
   sortCriteria,
   theme,
   thumbnailAspectRatio,
+  thumbnailHealth,
   thumbnails,
   thumbnailSize,
   onNavigateToCommunity,
@@ -1664,6 +1685,7 @@ export default function MainLibrary({
                     onContextMenu,
                     onImageClick,
                     onImageDoubleClick,
+                    thumbnailHealth,
                     thumbnails,
                     thumbnailAspectRatio,
                     loadedThumbnails: loadedThumbnailsRef.current,
@@ -27,7 +27,7 @@ import Switch from '../ui/Switch';
 import Input from '../ui/Input';
 import Slider from '../ui/Slider';
 import { ThemeProps, THEMES, DEFAULT_THEME_ID } from '../../utils/themes';
-import { Invokes } from '../ui/AppProperties';
+import { CameraRawOverrides, Invokes } from '../ui/AppProperties';
 
 interface ConfirmModalState {
   confirmText: string;
@@ -103,6 +103,21 @@ const backendOptions: OptionItem[] = [
   { value: 'gl', label: 'OpenGL' },
 ];
 
+const rawDemosaicOptions: OptionItem[] = [
+  { value: 'quality', label: 'Quality' },
+  { value: 'speed', label: 'Speed' },
+];
+
+const exportThreadOptions: OptionItem[] = [
+  { value: 0, label: 'Auto' },
+  ...[1, 2, 4, 6, 8, 12, 16, 24, 32].map((count) => ({ value: count, label: String(count) })),
+];
+
+const fileLockRetryOptions: OptionItem[] = [
+  { value: 0, label: 'Off' },
+  ...[1, 2, 3, 5, 8].map((count) => ({ value: count, label: String(count) })),
+];
+
 const settingCategories = [
   { id: 'general', label: 'General', icon: SlidersHorizontal },
   { id: 'processing', label: 'Processing', icon: Cpu },
@@ -233,6 +248,8 @@ export default function SettingsPanel({
   const [clearMessage, setClearMessage] = useState('');
   const [isClearingCache, setIsClearingCache] = useState(false);
   const [cacheClearMessage, setCacheClearMessage] = useState('');
+  const [isClearingPreviewCache, setIsClearingPreviewCache] = useState(false);
+  const [previewCacheClearMessage, setPreviewCacheClearMessage] = useState('');
   const [isClearingAiTags, setIsClearingAiTags] = useState(false);
   const [aiTagsClearMessage, setAiTagsClearMessage] = useState('');
   const [isClearingTags, setIsClearingTags] = useState(false);
@@ -251,10 +268,14 @@ export default function SettingsPanel({
   const [aiProvider, setAiProvider] = useState(appSettings?.aiProvider || 'cpu');
   const [aiConnectorAddress, setAiConnectorAddress] = useState<string>(appSettings?.aiConnectorAddress || '');
   const [newShortcut, setNewShortcut] = useState('');
+  const [newCameraModel, setNewCameraModel] = useState('');
+  const [newCameraCompression, setNewCameraCompression] = useState('2.5');
+  const [newRawOverride, setNewRawOverride] = useState({ model: '', blackLevel: '', whiteLevel: '', exposureBias: '' });
 
   const [processingSettings, setProcessingSettings] = useState({
     editorPreviewResolution: appSettings?.editorPreviewResolution || 1920,
     rawHighlightCompression: appSettings?.rawHighlightCompression ?? 2.5,
+    rawExposureBias: appSettings?.rawExposureBias ?? 0,
     processingBackend: appSettings?.processingBackend || 'auto',
     linuxGpuOptimization: appSettings?.linuxGpuOptimization ?? false,
   });
@@ -272,6 +293,7 @@ export default function SettingsPanel({
     setProcessingSettings({
       editorPreviewResolution: appSettings?.editorPreviewResolution || 1920,
       rawHighlightCompression: appSettings?.rawHighlightCompression ?? 2.5,
+      rawExposureBias: appSettings?.rawExposureBias ?? 0,
       processingBackend: appSettings?.processingBackend || 'auto',
       linuxGpuOptimization: appSettings?.linuxGpuOptimization ?? false,
     });
@@ -459,6 +481,35 @@ export default function SettingsPanel({
     });
   };
 
+  const executeClearPreviewCache = async () => {
+    setIsClearingPreviewCache(true);
+    setPreviewCacheClearMessage('Clearing preview cache...');
+    try {
+      await invoke(Invokes.ClearPreviewCache);
+      setPreviewCacheClearMessage('Preview cache cleared successfully.');
+    } catch (err: any) {
+      console.error('Failed to clear preview cache:', err);
+      setPreviewCacheClearMessage(`Error: ${err}`);
+    } finally {
+      setTimeout(() => {
+        setIsClearingPreviewCache(false);
+        setPreviewCacheClearMessage('');
+      }, EXECUTE_TIMEOUT);
+    }
+  };
+
+  const handleClearPreviewCache = () => {
+    setConfirmModalState({
+      confirmText: 'Clear Cache',
+      confirmVariant: 'destructive',
+      isOpen: true,
+      message:
+        'Are you sure you want to clear the preview cache?\n\nFullscreen previews will be rendered again the next time they are opened.',
+      onConfirm: executeClearPreviewCache,
+      title: 'Confirm Cache Deletion',
+    });
+  };
+
   const handleTestConnection = async () => {
     if (!aiConnectorAddress) {
       return;
@@ -495,6 +546,62 @@ export default function SettingsPanel({
     onSettingsChange({ ...appSettings, taggingShortcuts: newShortcuts });
   };
 
+  const handleAddCameraOverride = () => {
+    const model = newCameraModel.trim();
+    const amount = parseFloat(newCameraCompression);
+    if (!model || isNaN(amount)) {
+      return;
+    }
+    const overrides = { ...(appSettings?.cameraHighlightCompression || {}) };
+    overrides[model] = Math.min(10, Math.max(1, amount));
+    onSettingsChange({ ...appSettings, cameraHighlightCompression: overrides });
+    setNewCameraModel('');
+  };
+
+  const handleRemoveCameraOverride = (model: string) => {
+    const overrides = { ...(appSettings?.cameraHighlightCompression || {}) };
+    delete overrides[model];
+    onSettingsChange({ ...appSettings, cameraHighlightCompression: overrides });
+  };
+
+  const handleAddRawOverride = () => {
+    const model = newRawOverride.model.trim();
+    if (!model) {
+      return;
+    }
+    const parseLevel = (value: string) => {
+      const level = parseInt(value, 10);
+      return isNaN(level) || level < 0 ? null : level;
+    };
+    const exposureBias = parseFloat(newRawOverride.exposureBias);
+    const override: CameraRawOverrides = {
+      blackLevel: parseLevel(newRawOverride.blackLevel),
+      whiteLevel: parseLevel(newRawOverride.whiteLevel),
+      exposureBias: isNaN(exposureBias) ? null : exposureBias,
+    };
+    if (override.blackLevel === null && override.whiteLevel === null && override.exposureBias === null) {
+      return;
+    }
+    const overrides = { ...(appSettings?.cameraRawOverrides || {}), [model]: override };
+    onSettingsChange({ ...appSettings, cameraRawOverrides: overrides });
+    setNewRawOverride({ model: '', blackLevel: '', whiteLevel: '', exposureBias: '' });
+  };
+
+  const handleRemoveRawOverride = (model: string) => {
+    const overrides = { ...(appSettings?.cameraRawOverrides || {}) };
+    delete overrides[model];
+    onSettingsChange({ ...appSettings, cameraRawOverrides: overrides });
+  };
+
+  const formatRawOverride = (override: CameraRawOverrides) =>
+    [
+      override.blackLevel != null && `Black ${override.blackLevel}`,
+      override.whiteLevel != null && `White ${override.whiteLevel}`,
+      override.exposureBias != null && `${override.exposureBias > 0 ? '+' : ''}${override.exposureBias} EV`,
+    ]
+      .filter(Boolean)
+      .join(', ');
+
   const handleInputKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
     if (e.key === 'Enter') {
       e.preventDefault();
@@ -596,6 +703,30 @@ export default function SettingsPanel({
                       />
                     </SettingItem>
 
+                    <SettingItem
+                      label="Folder Image Counts"
+                      description="Show how many images each folder in the sidebar contains. Counting adds time to scanning very large folder trees."
+                    >
+                      <Switch
+                        checked={appSettings?.showFolderImageCounts ?? false}
+                        id="folder-image-counts-toggle"
+                        label="Show Image Counts"
+                        onChange={(checked) => onSettingsChange({ ...appSettings, showFolderImageCounts: checked })}
+                      />
+                    </SettingItem>
+
+                    <SettingItem
+                      label="Empty Folders"
+                      description="Hide folders in the sidebar that contain no images, including in any of their sub-folders."
+                    >
+                      <Switch
+                        checked={appSettings?.hideEmptyFolders ?? false}
+                        id="hide-empty-folders-toggle"
+                        label="Hide Empty Folders"
+                        onChange={(checked) => onSettingsChange({ ...appSettings, hideEmptyFolders: checked })}
+                      />
+                    </SettingItem>
+
                     <SettingItem
                       description="Enables or disables transparency effects for the application window. Relaunch required."
                       label="Window Effects"
@@ -810,6 +941,18 @@ export default function SettingsPanel({
                       />
                     </SettingItem>
 
+                    <SettingItem
+                      label="Cache Fullscreen Previews"
+                      description="Keep rendered fullscreen previews on disk so reopening an image with the same edits is instant. Uses extra disk space."
+                    >
+                      <Switch
+                        checked={appSettings?.enablePreviewDiskCache ?? false}
+                        id="preview-disk-cache-toggle"
+                        label="Enable Preview Cache"
+                        onChange={(checked) => onSettingsChange({ ...appSettings, enablePreviewDiskCache: checked })}
+                      />
+                    </SettingItem>
+
                     <div className="space-y-4">
                       <SettingItem
                         label="Live Interactive Previews"
@@ -872,6 +1015,177 @@ export default function SettingsPanel({
                       />
                     </SettingItem>
 
+                    <SettingItem
+                      label="Per-Camera Highlight Recovery"
+                      description="Overrides the amount above for RAW files from specific cameras. Use the make and model as reported by the camera, e.g. 'Sony ILCE-7M3'."
+                    >
+                      <div className="space-y-2">
+                        {Object.entries(appSettings?.cameraHighlightCompression || {})
+                          .sort(([a], [b]) => a.localeCompare(b))
+                          .map(([model, amount]) => (
+                            <div
+                              key={model}
+                              className="flex items-center justify-between bg-surface rounded-md px-3 py-1.5 text-sm"
+                            >
+                              <span className="text-text-primary truncate">{model}</span>
+                              <div className="flex items-center gap-3 flex-shrink-0">
+                                <span className="text-text-secondary">{Number(amount).toFixed(1)}</span>
+                                <button
+                                  onClick={() => handleRemoveCameraOverride(model)}
+                                  className="p-0.5 rounded-full text-text-secondary hover:text-text-primary hover:bg-black/20"
+                                  title="Remove override"
+                                >
+                                  <X size={14} />
+                                </button>
+                              </div>
+                            </div>
+                          ))}
+                        <div className="flex items-center gap-2">
+                          <Input
+                            type="text"
+                            value={newCameraModel}
+                            onChange={(e) => setNewCameraModel(e.target.value)}
+                            onKeyDown={(e: React.KeyboardEvent<HTMLInputElement>) => {
+                              if (e.key === 'Enter') {
+                                e.preventDefault();
+                                handleAddCameraOverride();
+                              }
+                            }}
+                            placeholder="Camera make and model..."
+                            className="flex-grow"
+                          />
+                          <Input
+                            type="number"
+                            value={newCameraCompression}
+                            onChange={(e) => setNewCameraCompression(e.target.value)}
+                            className="w-20"
+                          />
+                          <button
+                            onClick={handleAddCameraOverride}
+                            className="p-1.5 rounded-full text-text-secondary hover:text-text-primary hover:bg-surface"
+                            title="Add override"
+                          >
+                            <Plus size={18} />
+                          </button>
+                        </div>
+                      </div>
+                    </SettingItem>
+
+                    <SettingItem
+                      label="RAW Baseline Exposure"
+                      description="Brightens or darkens every RAW file as it is decoded, before highlight recovery. Useful if your camera's files consistently open too dark."
+                    >
+                      <Slider
+                        label="Stops"
+                        min={-2}
+                        max={2}
+                        step={0.05}
+                        value={processingSettings.rawExposureBias}
+                        defaultValue={0}
+                        onChange={(e: any) => handleProcessingSettingChange('rawExposureBias', parseFloat(e.target.value))}
+                      />
+                    </SettingItem>
+
+                    <SettingItem
+                      label="RAW Demosaicing"
+                      description="'Quality' is recommended. 'Speed' opens RAW files faster at the cost of fine detail."
+                    >
+                      <Dropdown
+                        onChange={(value: any) => onSettingsChange({ ...appSettings, rawDemosaic: value })}
+                        options={rawDemosaicOptions}
+                        value={appSettings?.rawDemosaic || 'quality'}
+                      />
+                    </SettingItem>
+
+                    <SettingItem
+                      label="Per-Camera RAW Levels"
+                      description="For cameras whose files open with muddy shadows or clip too early: override the black and white levels (in raw sensor units) or the baseline exposure. Leave a field empty to keep the camera's value."
+                    >
+                      <div className="space-y-2">
+                        {Object.entries(appSettings?.cameraRawOverrides || {})
+                          .sort(([a], [b]) => a.localeCompare(b))
+                          .map(([model, override]) => (
+                            <div
+                              key={model}
+                              className="flex items-center justify-between bg-surface rounded-md px-3 py-1.5 text-sm"
+                            >
+                              <span className="text-text-primary truncate">{model}</span>
+                              <div className="flex items-center gap-3 flex-shrink-0">
+                                <span className="text-text-secondary">
+                                  {formatRawOverride(override as CameraRawOverrides)}
+                                </span>
+                                <button
+                                  onClick={() => handleRemoveRawOverride(model)}
+                                  className="p-0.5 rounded-full text-text-secondary hover:text-text-primary hover:bg-black/20"
+                                  title="Remove override"
+                                >
+                                  <X size={14} />
+                                </button>
+                              </div>
+                            </div>
+                          ))}
+                        <div className="flex items-center gap-2">
+                          <Input
+                            type="text"
+                            value={newRawOverride.model}
+                            onChange={(e) => setNewRawOverride((prev) => ({ ...prev, model: e.target.value }))}
+                            placeholder="Camera make and model..."
+                            className="flex-grow"
+                          />
+                          <Input
+                            type="number"
+                            value={newRawOverride.blackLevel}
+                            onChange={(e) => setNewRawOverride((prev) => ({ ...prev, blackLevel: e.target.value }))}
+                            placeholder="Black"
+                            className="w-20"
+                          />
+                          <Input
+                            type="number"
+                            value={newRawOverride.whiteLevel}
+                            onChange={(e) => setNewRawOverride((prev) => ({ ...prev, whiteLevel: e.target.value }))}
+                            placeholder="White"
+                            className="w-20"
+                          />
+                          <Input
+                            type="number"
+                            value={newRawOverride.exposureBias}
+                            onChange={(e) => setNewRawOverride((prev) => ({ ...prev, exposureBias: e.target.value }))}
+                            placeholder="EV"
+                            className="w-16"
+                          />
+                          <button
+                            onClick={handleAddRawOverride}
+                            className="p-1.5 rounded-full text-text-secondary hover:text-text-primary hover:bg-surface"
+                            title="Add override"
+                          >
+                            <Plus size={18} />
+                          </button>
+                        </div>
+                      </div>
+                    </SettingItem>
+
+                    <SettingItem
+                      label="Export Threads"
+                      description="How many images are exported in parallel. 'Auto' uses half of your CPU cores, up to 4. Lower it on laptops that run hot."
+                    >
+                      <Dropdown
+                        onChange={(value: any) => onSettingsChange({ ...appSettings, exportThreadCount: value })}
+                        options={exportThreadOptions}
+                        value={appSettings?.exportThreadCount ?? 0}
+                      />
+                    </SettingItem>
+
+                    <SettingItem
+                      label="Locked File Retries"
+                      description="How often to retry opening a file another program is still writing, such as a tethered capture or a syncing cloud folder. Each retry waits twice as long as the last, starting at 50 ms."
+                    >
+                      <Dropdown
+                        onChange={(value: any) => onSettingsChange({ ...appSettings, fileLockRetries: value })}
+                        options={fileLockRetryOptions}
+                        value={appSettings?.fileLockRetries ?? 3}
+                      />
+                    </SettingItem>
+
                     <SettingItem
                       label="Processing Backend"
                       description="Select the graphics API. 'Auto' is recommended. May fix crashes on some systems."
@@ -1066,6 +1380,16 @@ export default function SettingsPanel({
                       title="Clear Thumbnail Cache"
                     />
 
+                    <DataActionItem
+                      buttonAction={handleClearPreviewCache}
+                      buttonText="Clear Preview Cache"
+                      description="This will delete all cached fullscreen previews. They will be rendered again when needed."
+                      icon={<Trash2 size={16} className="mr-2" />}
+                      isProcessing={isClearingPreviewCache}
+                      message={previewCacheClearMessage}
+                      title="Clear Preview Cache"
+                    />
+
                     <DataActionItem
                       buttonAction={async () => {
                         if (logPath && !logPath.startsWith('Could not')) {
@@ -55,7 +55,7 @@ interface ImageCanvasProps {
   isFullResolution?: boolean;
   isLoadingFullRes?: boolean;
   isWbPickerActive?: boolean;
-  onWbPicked?: () => void;
+  onWbPicked?: (point: { x: number; y: number }) => void;
   setAdjustments(fn: (prev: Adjustments) => Adjustments): void;
 }
 
@@ -742,7 +742,7 @@ const ImageCanvas = memo(
           tint: Math.max(-100, Math.min(100, (prev.tint || 0) + deltaTint)),
         }));
 
-        onWbPicked();
+        onWbPicked({ x: x / imgLogicalWidth, y: y / imgLogicalHeight });
       };
     }, [isWbPickerActive, finalPreviewUrl, imageRenderSize, onWbPicked, setAdjustments]);
 
@@ -29,6 +29,11 @@ interface CropPreset {
   value: number | null;
 }
 
+interface FillPreset {
+  name: string;
+  value: string | null;
+}
+
 const PRESETS: Array<CropPreset> = [
   { name: 'Free', value: null },
   { name: 'Original', value: ORIGINAL_RATIO },
@@ -41,6 +46,12 @@ const PRESETS: Array<CropPreset> = [
   { name: '65:24', value: 65 / 24 },
 ];
 
+const FILL_PRESETS: Array<FillPreset> = [
+  { name: 'None', value: null },
+  { name: 'White', value: '#ffffff' },
+  { name: 'Black', value: '#000000' },
+];
+
 export default function CropPanel({
   adjustments,
   isStraightenActive,
@@ -51,7 +62,15 @@ export default function CropPanel({
   const [customW, setCustomW] = useState('');
   const [customH, setCustomH] = useState('');
 
-  const { aspectRatio, rotation = 0, flipHorizontal = false, flipVertical = false, orientationSteps = 0 } = adjustments;
+  const {
+    aspectRatio,
+    fillColor = null,
+    rotation = 0,
+    flipHorizontal = false,
+    flipVertical = false,
+    orientationSteps = 0,
+  } = adjustments;
+  const isCustomFill = fillColor !== null && !FILL_PRESETS.some((preset: FillPreset) => preset.value === fillColor);
 
   const getEffectiveOriginalRatio = useCallback(() => {
     if (!selectedImage?.width || !selectedImage?.height) {
@@ -356,6 +375,36 @@ export default function CropPanel({
                 type="range"
                 value={fineRotation}
               />
+              <div className="flex justify-between items-center">
+                <span className="text-sm text-text-secondary">Background</span>
+                <div className="flex items-center gap-2">
+                  {FILL_PRESETS.map((preset: FillPreset) => (
+                    <button
+                      className={clsx(
+                        'px-2 py-1 text-sm rounded-md transition-colors',
+                        fillColor === preset.value ? 'bg-accent text-button-text' : 'bg-surface hover:bg-card-active',
+                      )}
+                      key={preset.name}
+                      onClick={() => setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, fillColor: preset.value }))}
+                    >
+                      {preset.name}
+                    </button>
+                  ))}
+                  <input
+                    className={clsx(
+                      'p-0 h-7 w-9 border-none rounded-md cursor-pointer bg-bg-secondary',
+                      isCustomFill && 'ring-2 ring-accent',
+                    )}
+                    id="fillColor"
+                    onChange={(e: any) =>
+                      setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, fillColor: e.target.value }))
+                    }
+                    title="Custom Background Color"
+                    type="color"
+                    value={fillColor ?? '#ffffff'}
+                  />
+                </div>
+              </div>
             </div>
 
             <div className="space-y-4">
@@ -8,12 +8,14 @@ export enum FileFormats {
   Jpeg = 'jpeg',
   Png = 'png',
   Tiff = 'tiff',
+  Exr = 'exr',
 }
 
 export const FILE_FORMATS: Array<FileFormat> = [
   { id: FileFormats.Jpeg, name: 'JPEG', extensions: ['jpg', 'jpeg'] },
   { id: FileFormats.Png, name: 'PNG', extensions: ['png'] },
   { id: FileFormats.Tiff, name: 'TIFF', extensions: ['tiff'] },
+  { id: FileFormats.Exr, name: 'EXR', extensions: ['exr'] },
 ];
 
 export const FILENAME_VARIABLES: Array<string> = [
@@ -35,6 +37,7 @@ export interface ExportSettings {
     value: number;
     dontEnlarge: boolean;
   } | null;
+  sceneLinear: boolean;
   stripGps: boolean;
   watermark: WatermarkSettings | null;
 }
@@ -59,10 +62,16 @@ export interface WatermarkSettings {
   opacity: number;
 }
 
+export interface ExportWarning {
+  message: string;
+  path: string;
+}
+
 export interface ExportState {
   errorMessage: string;
   progress: Progress;
   status: Status;
+  warnings?: Array<ExportWarning>;
 }
 
 export interface FileFormat {
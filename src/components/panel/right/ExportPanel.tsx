@@ -1,12 +1,13 @@
 import { useState, useEffect, useRef, useMemo } from 'react';
 import { save, open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
-import { Save, CheckCircle, XCircle, Loader, Ban } from 'lucide-react';
+import { AlertTriangle, Save, CheckCircle, XCircle, Loader, Ban } from 'lucide-react';
 import debounce from 'lodash.debounce';
 import Switch from '../../ui/Switch';
 import Dropdown from '../../ui/Dropdown';
 import Slider from '../../ui/Slider';
 import ImagePicker from '../../ui/ImagePicker';
+import { useCapabilities } from '../../../hooks/useCapabilities';
 import { Adjustments } from '../../../utils/adjustments';
 import {
   ExportSettings,
@@ -18,12 +19,14 @@ import {
   FileFormats,
   WatermarkAnchor,
 } from './ExportImportProperties';
-import { Invokes, SelectedImage } from '../../ui/AppProperties';
+import { AppSettings, Invokes, SelectedImage } from '../../ui/AppProperties';
 
 interface ExportPanelProps {
   adjustments: Adjustments;
+  appSettings: AppSettings | null;
   exportState: ExportState;
   multiSelectedPaths: Array<string>;
+  onSettingsChange(settings: AppSettings): void;
   selectedImage: SelectedImage;
   setExportState(state: any): void;
 }
@@ -158,8 +161,10 @@ const resizeModeOptions = [
 
 export default function ExportPanel({
   adjustments,
+  appSettings,
   exportState,
   multiSelectedPaths,
+  onSettingsChange,
   selectedImage,
   setExportState,
 }: ExportPanelProps) {
@@ -171,6 +176,7 @@ export default function ExportPanel({
   const [dontEnlarge, setDontEnlarge] = useState<boolean>(true);
   const [keepMetadata, setKeepMetadata] = useState<boolean>(true);
   const [stripGps, setStripGps] = useState<boolean>(true);
+  const [sceneLinear, setSceneLinear] = useState<boolean>(false);
   const [filenameTemplate, setFilenameTemplate] = useState<string>('{original_filename}_edited');
   const [estimatedSize, setEstimatedSize] = useState<number | null>(null);
   const [isEstimating, setIsEstimating] = useState<boolean>(false);
@@ -183,8 +189,17 @@ export default function ExportPanel({
   const [watermarkImageAspectRatio, setWatermarkImageAspectRatio] = useState(1);
   const filenameInputRef = useRef<HTMLInputElement>(null);
 
-  const { status, progress, errorMessage } = exportState;
+  const { status, progress, errorMessage, warnings } = exportState;
+  const degradedImageCount = new Set((warnings ?? []).map((warning) => warning.path)).size;
   const isExporting = status === Status.Exporting;
+  const supportsSceneLinear = fileFormat === FileFormats.Exr || fileFormat === FileFormats.Tiff;
+  // EXR is scene-linear by definition; TIFF is only when asked
+  const exportsSceneLinear = fileFormat === FileFormats.Exr || (sceneLinear && supportsSceneLinear);
+  const capabilities = useCapabilities();
+  const availableFormats = useMemo(
+    () => FILE_FORMATS.filter((f: FileFormat) => !capabilities || capabilities.exportFormats.includes(f.id)),
+    [capabilities],
+  );
 
   const isEditorContext = !!selectedImage;
   const pathsToExport = isEditorContext
@@ -270,6 +285,7 @@ export default function ExportPanel({
       jpegQuality,
       keepMetadata,
       resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
+      sceneLinear: exportsSceneLinear,
       stripGps,
       watermark:
         enableWatermark && watermarkPath
@@ -295,6 +311,7 @@ export default function ExportPanel({
     resizeValue,
     dontEnlarge,
     keepMetadata,
+    sceneLinear,
     stripGps,
     filenameTemplate,
     enableWatermark,
@@ -326,12 +343,27 @@ export default function ExportPanel({
     }, 0);
   };
 
+  useEffect(() => {
+    setJpegQuality(appSettings?.exportQualityByFormat?.[fileFormat] ?? 90);
+  }, [fileFormat, appSettings?.exportQualityByFormat?.[fileFormat]]);
+
+  const rememberQualityForFormat = () => {
+    if (!appSettings || appSettings.exportQualityByFormat?.[fileFormat] === jpegQuality) {
+      return;
+    }
+    onSettingsChange({
+      ...appSettings,
+      exportQualityByFormat: { ...appSettings.exportQualityByFormat, [fileFormat]: jpegQuality },
+    });
+  };
+
   const handleExport = async () => {
     if (numImages === 0 || isExporting) {
       return;
     }
 
     setExportState({ status: Status.Exporting, progress: { current: 0, total: numImages }, errorMessage: '' });
+    rememberQualityForFormat();
 
     let finalFilenameTemplate = filenameTemplate;
     if (isBatchMode && !filenameTemplate.includes('{sequence}') && !filenameTemplate.includes('{original_filename}')) {
@@ -344,6 +376,7 @@ export default function ExportPanel({
       jpegQuality: jpegQuality,
       keepMetadata,
       resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
+      sceneLinear: exportsSceneLinear,
       stripGps,
       watermark:
         enableWatermark && watermarkPath
@@ -379,7 +412,7 @@ export default function ExportPanel({
         const filePath = await save({
           title: 'Save Edited Image',
           defaultPath: `${name}_edited.${selectedFormat.extensions[0]}`,
-          filters: FILE_FORMATS.map((f: FileFormat) => ({ name: f.name, extensions: f.extensions })),
+          filters: availableFormats.map((f: FileFormat) => ({ name: f.name, extensions: f.extensions })),
         });
         if (filePath) {
           await invoke(Invokes.ExportImage, {
@@ -395,7 +428,7 @@ export default function ExportPanel({
     } catch (error) {
       console.error('Failed to start export:', error);
       setExportState({
-        errorMessage: typeof error === 'string' ? error : 'Failed to start export.',
+        errorMessage: typeof error === 'string' ? error : (error as any)?.message ?? 'Failed to start export.',
         progress,
         status: Status.Error,
       });
@@ -424,7 +457,7 @@ export default function ExportPanel({
           <>
             <Section title="File Settings">
               <div className="grid grid-cols-3 gap-2">
-                {FILE_FORMATS.map((format: FileFormat) => (
+                {availableFormats.map((format: FileFormat) => (
                   <button
                     className={`px-2 py-1.5 text-sm rounded-md transition-colors ${
                       fileFormat === format.id ? 'bg-accent text-button-text' : 'bg-surface hover:bg-card-active'
@@ -450,6 +483,14 @@ export default function ExportPanel({
                   />
                 </div>
               )}
+              {supportsSceneLinear && (
+                <Switch
+                  checked={exportsSceneLinear}
+                  disabled={isExporting || fileFormat === FileFormats.Exr}
+                  label="Scene-Linear (No Tone Mapping)"
+                  onChange={setSceneLinear}
+                />
+              )}
             </Section>
 
             {isBatchMode && (
@@ -640,6 +681,22 @@ export default function ExportPanel({
             <span>Export cancelled.</span>
           </div>
         )}
+        {warnings && warnings.length > 0 && status !== Status.Idle && (
+          <div className="mt-3 text-xs text-yellow-400 space-y-1">
+            <div className="flex items-center gap-2 justify-center">
+              <AlertTriangle size={14} />
+              <span>
+                {degradedImageCount === 1 ? '1 image' : `${degradedImageCount} images`} may be degraded:
+              </span>
+            </div>
+            {warnings.slice(0, 3).map((warning, index) => (
+              <p key={`${warning.path}-${index}`} className="truncate text-center" title={warning.message}>
+                {warning.path.split(/[\\/]/).pop()}: {warning.message}
+              </p>
+            ))}
+            {warnings.length > 3 && <p className="text-center">...and {warnings.length - 3} more (see the log file)</p>}
+          </div>
+        )}
       </div>
     </div>
   );
@@ -1,12 +1,13 @@
 import { useState, useEffect, useRef, useMemo } from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
-import { Save, CheckCircle, XCircle, Loader, X, Ban } from 'lucide-react';
+import { AlertTriangle, Save, CheckCircle, XCircle, Loader, X, Ban } from 'lucide-react';
 import debounce from 'lodash.debounce';
 import Switch from '../../ui/Switch';
 import Dropdown from '../../ui/Dropdown';
 import Slider from '../../ui/Slider';
 import ImagePicker from '../../ui/ImagePicker';
+import { useCapabilities } from '../../../hooks/useCapabilities';
 import {
   FileFormat,
   FILE_FORMATS,
@@ -17,13 +18,15 @@ import {
   FileFormats,
   WatermarkAnchor,
 } from './ExportImportProperties';
-import { Invokes, ImageFile } from '../../ui/AppProperties';
+import { AppSettings, Invokes, ImageFile } from '../../ui/AppProperties';
 
 interface LibraryExportPanelProps {
+  appSettings: AppSettings | null;
   exportState: ExportState;
   isVisible: boolean;
   multiSelectedPaths: Array<string>;
   onClose(): void;
+  onSettingsChange(settings: AppSettings): void;
   setExportState(state: any): void;
   imageList: ImageFile[];
 }
@@ -157,10 +160,12 @@ const resizeModeOptions = [
 ];
 
 export default function LibraryExportPanel({
+  appSettings,
   exportState,
   isVisible,
   multiSelectedPaths,
   onClose,
+  onSettingsChange,
   setExportState,
   imageList,
 }: LibraryExportPanelProps) {
@@ -172,6 +177,7 @@ export default function LibraryExportPanel({
   const [dontEnlarge, setDontEnlarge] = useState(true);
   const [keepMetadata, setKeepMetadata] = useState(true);
   const [stripGps, setStripGps] = useState(true);
+  const [sceneLinear, setSceneLinear] = useState(false);
   const [filenameTemplate, setFilenameTemplate] = useState('{original_filename}_edited');
   const [estimatedSize, setEstimatedSize] = useState<number | null>(null);
   const [isEstimating, setIsEstimating] = useState<boolean>(false);
@@ -184,8 +190,17 @@ export default function LibraryExportPanel({
   const [watermarkImageAspectRatio, setWatermarkImageAspectRatio] = useState(1);
   const filenameInputRef = useRef<HTMLInputElement>(null);
 
-  const { status, progress, errorMessage } = exportState;
+  const { status, progress, errorMessage, warnings } = exportState;
+  const degradedImageCount = new Set((warnings ?? []).map((warning) => warning.path)).size;
   const isExporting = status === Status.Exporting;
+  const supportsSceneLinear = fileFormat === FileFormats.Exr || fileFormat === FileFormats.Tiff;
+  // EXR is scene-linear by definition; TIFF is only when asked
+  const exportsSceneLinear = fileFormat === FileFormats.Exr || (sceneLinear && supportsSceneLinear);
+  const capabilities = useCapabilities();
+  const availableFormats = useMemo(
+    () => FILE_FORMATS.filter((f: FileFormat) => !capabilities || capabilities.exportFormats.includes(f.id)),
+    [capabilities],
+  );
 
   const numImages = multiSelectedPaths.length;
   const [imageAspectRatio, setImageAspectRatio] = useState(3 / 2);
@@ -278,6 +293,7 @@ export default function LibraryExportPanel({
       jpegQuality,
       keepMetadata,
       resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
+      sceneLinear: exportsSceneLinear,
       stripGps,
       watermark:
         enableWatermark && watermarkPath
@@ -304,6 +320,7 @@ export default function LibraryExportPanel({
     resizeValue,
     dontEnlarge,
     keepMetadata,
+    sceneLinear,
     stripGps,
     filenameTemplate,
     enableWatermark,
@@ -335,12 +352,27 @@ export default function LibraryExportPanel({
     }, 0);
   };
 
+  useEffect(() => {
+    setJpegQuality(appSettings?.exportQualityByFormat?.[fileFormat] ?? 90);
+  }, [fileFormat, appSettings?.exportQualityByFormat?.[fileFormat]]);
+
+  const rememberQualityForFormat = () => {
+    if (!appSettings || appSettings.exportQualityByFormat?.[fileFormat] === jpegQuality) {
+      return;
+    }
+    onSettingsChange({
+      ...appSettings,
+      exportQualityByFormat: { ...appSettings.exportQualityByFormat, [fileFormat]: jpegQuality },
+    });
+  };
+
   const handleExport = async () => {
     if (numImages === 0 || isExporting) {
       return;
     }
 
     setExportState({ status: Status.Exporting, progress: { current: 0, total: numImages }, errorMessage: '' });
+    rememberQualityForFormat();
 
     let finalFilenameTemplate = filenameTemplate;
     if (numImages > 1 && !filenameTemplate.includes('{sequence}') && !filenameTemplate.includes('{original_filename}')) {
@@ -353,6 +385,7 @@ export default function LibraryExportPanel({
       jpegQuality: jpegQuality,
       keepMetadata,
       resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
+      sceneLinear: exportsSceneLinear,
       stripGps,
       watermark:
         enableWatermark && watermarkPath
@@ -420,7 +453,7 @@ export default function LibraryExportPanel({
           <>
             <Section title="File Settings">
               <div className="grid grid-cols-3 gap-2">
-                {FILE_FORMATS.map((format: FileFormat) => (
+                {availableFormats.map((format: FileFormat) => (
                   <button
                     className={`px-2 py-1.5 text-sm rounded-md transition-colors ${
                       fileFormat === format.id ? 'bg-accent text-button-text' : 'bg-surface hover:bg-card-active'
@@ -446,6 +479,14 @@ export default function LibraryExportPanel({
                   />
                 </div>
               )}
+              {supportsSceneLinear && (
+                <Switch
+                  checked={exportsSceneLinear}
+                  disabled={isExporting || fileFormat === FileFormats.Exr}
+                  label="Scene-Linear (No Tone Mapping)"
+                  onChange={setSceneLinear}
+                />
+              )}
             </Section>
 
             <Section title="File Naming">
@@ -637,6 +678,22 @@ export default function LibraryExportPanel({
             <span>Export cancelled.</span>
           </div>
         )}
+        {warnings && warnings.length > 0 && status !== Status.Idle && (
+          <div className="mt-3 text-xs text-yellow-400 space-y-1">
+            <div className="flex items-center gap-2 justify-center">
+              <AlertTriangle size={14} />
+              <span>
+                {degradedImageCount === 1 ? '1 image' : `${degradedImageCount} images`} may be degraded:
+              </span>
+            </div>
+            {warnings.slice(0, 3).map((warning, index) => (
+              <p key={`${warning.path}-${index}`} className="truncate text-center" title={warning.message}>
+                {warning.path.split(/[\\/]/).pop()}: {warning.message}
+              </p>
+            ))}
+            {warnings.length > 3 && <p className="text-center">...and {warnings.length - 3} more (see the log file)</p>}
+          </div>
+        )}
       </div>
     </div>
   );
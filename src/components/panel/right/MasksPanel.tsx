@@ -34,6 +34,7 @@ import {
 import CollapsibleSection from '../../ui/CollapsibleSection';
 import Switch from '../../ui/Switch';
 import Slider from '../../ui/Slider';
+import Dropdown from '../../ui/Dropdown';
 import BasicAdjustments from '../../adjustments/Basic';
 import CurveGraph from '../../adjustments/Curves';
 import ColorPanel from '../../adjustments/Color';
@@ -54,7 +55,10 @@ import {
   Adjustments,
   INITIAL_MASK_ADJUSTMENTS,
   INITIAL_MASK_CONTAINER,
+  INITIAL_RANGE_MASK,
   MaskContainer,
+  RangeMask,
+  RangeMaskMode,
   ADJUSTMENT_SECTIONS,
 } from '../../../utils/adjustments';
 import { useContextMenu } from '../../../context/ContextMenuContext';
@@ -113,6 +117,12 @@ const SUB_MASK_CONFIG: Record<Mask, any> = {
   [Mask.QuickEraser]: { parameters: [] },
 };
 
+const rangeMaskModeOptions = [
+  { label: 'None', value: 'none' },
+  { label: 'Luminance', value: RangeMaskMode.Luminance },
+  { label: 'Color', value: RangeMaskMode.Color },
+];
+
 const BrushTools = ({ settings, onSettingsChange }: { settings: any; onSettingsChange: any }) => (
   <div className="space-y-4 border-t border-surface">
     <Slider defaultValue={100} label="Brush Size" max={200} min={1} onChange={(e: any) => onSettingsChange((s: any) => ({ ...s, size: Number(e.target.value) }))} step={1} value={settings.size} />
@@ -988,6 +998,19 @@ function SettingsPanel({ container, activeSubMask, aiModelDownloadStatus, brushS
     updateContainer(container.id, { [key]: value });
   };
   
+  const handleRangeMaskModeChange = (mode: RangeMaskMode | 'none') => {
+    if (mode === 'none') {
+      handleMaskPropertyChange('rangeMask', null);
+      return;
+    }
+    handleMaskPropertyChange('rangeMask', { ...INITIAL_RANGE_MASK, ...displayContainer.rangeMask, mode });
+  };
+
+  const handleRangeMaskChange = (key: keyof RangeMask, value: number) => {
+    if (!displayContainer.rangeMask) return;
+    handleMaskPropertyChange('rangeMask', { ...displayContainer.rangeMask, [key]: value });
+  };
+
   const handleSubMaskParameterChange = (key: string, value: number) => { 
     if (!isActive || !activeSubMask) return;
     updateSubMask(activeSubMask.id, { parameters: { ...activeSubMask.parameters, [key]: value } }); 
@@ -1125,6 +1148,50 @@ function SettingsPanel({ container, activeSubMask, aiModelDownloadStatus, brushS
                     step={1} 
                  />
 
+                 {!isComponentMode && (
+                    <>
+                        <div className="flex justify-between items-center">
+                            <span className="text-sm font-medium text-text-secondary select-none">Range Mask</span>
+                            <Dropdown
+                                className="w-40"
+                                onChange={handleRangeMaskModeChange}
+                                options={rangeMaskModeOptions}
+                                value={displayContainer.rangeMask?.mode ?? 'none'}
+                            />
+                        </div>
+                        {displayContainer.rangeMask?.mode === RangeMaskMode.Luminance && (
+                            <>
+                                <Slider defaultValue={0} label="Luminance Min" max={100} min={0} step={1}
+                                    value={displayContainer.rangeMask.luminanceMin}
+                                    onChange={(e: any) => handleRangeMaskChange('luminanceMin', Number(e.target.value))}
+                                />
+                                <Slider defaultValue={100} label="Luminance Max" max={100} min={0} step={1}
+                                    value={displayContainer.rangeMask.luminanceMax}
+                                    onChange={(e: any) => handleRangeMaskChange('luminanceMax', Number(e.target.value))}
+                                />
+                            </>
+                        )}
+                        {displayContainer.rangeMask?.mode === RangeMaskMode.Color && (
+                            <>
+                                <Slider defaultValue={INITIAL_RANGE_MASK.hue} label="Hue" max={360} min={0} step={1}
+                                    value={displayContainer.rangeMask.hue}
+                                    onChange={(e: any) => handleRangeMaskChange('hue', Number(e.target.value))}
+                                />
+                                <Slider defaultValue={INITIAL_RANGE_MASK.hueRange} label="Hue Range" max={180} min={0} step={1}
+                                    value={displayContainer.rangeMask.hueRange}
+                                    onChange={(e: any) => handleRangeMaskChange('hueRange', Number(e.target.value))}
+                                />
+                            </>
+                        )}
+                        {displayContainer.rangeMask && (
+                            <Slider defaultValue={INITIAL_RANGE_MASK.smoothness} label="Smoothness" max={100} min={0} step={1}
+                                value={displayContainer.rangeMask.smoothness}
+                                onChange={(e: any) => handleRangeMaskChange('smoothness', Number(e.target.value))}
+                            />
+                        )}
+                    </>
+                 )}
+
                  {isComponentMode && (
                     <>
                         {isAiMask && aiModelDownloadStatus && <div className="text-xs text-accent text-center bg-accent/10 p-1 rounded">Downloading Model: {aiModelDownloadStatus}</div>}
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback, useMemo, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { open as openDialog, save as saveDialog } from '@tauri-apps/plugin-dialog';
+import { ask, open as openDialog, save as saveDialog } from '@tauri-apps/plugin-dialog';
 import {
   DndContext,
   DragOverlay,
@@ -645,8 +645,8 @@ export default function PresetsPanel({
     try {
       const selectedPath = await openDialog({
         filters: [
-          { name: 'All Preset Files', extensions: ['rrpreset', 'xmp', 'lrtemplate'] },
-          { name: 'RapidRAW Preset', extensions: ['rrpreset'] },
+          { name: 'All Preset Files', extensions: ['rrpreset', 'rrpack', 'xmp', 'lrtemplate'] },
+          { name: 'RapidRAW Preset', extensions: ['rrpreset', 'rrpack'] },
           { name: 'Legacy Preset', extensions: ['xmp', 'lrtemplate'] },
         ],
         multiple: false,
@@ -659,7 +659,16 @@ export default function PresetsPanel({
         if (isLegacy) {
           await importLegacyPresetsFromFile(selectedPath);
         } else {
-          await importPresetsFromFile(selectedPath);
+          const addedCount = await importPresetsFromFile(selectedPath);
+          if (addedCount === 0) {
+            const importAnyway = await ask(
+              'Every preset in this file is already installed. Import them again as copies?',
+              { title: 'Presets Already Installed', kind: 'info', okLabel: 'Import Copies', cancelLabel: 'Skip' },
+            );
+            if (importAnyway) {
+              await importPresetsFromFile(selectedPath, true);
+            }
+          }
         }
 
         setFolderPreviewsGenerated(new Set<string>());
@@ -670,6 +679,23 @@ export default function PresetsPanel({
     }
   };
 
+  const referencesLut = (items: Array<UserPreset>) =>
+    items.some((item) => {
+      const children = item.folder ? item.folder.children : item.preset ? [item.preset] : [];
+      return children.some((preset: any) => !!preset?.adjustments?.lutPath);
+    });
+
+  // Presets using a LUT default to a pack so the LUT travels with them.
+  const exportDialogOptions = (baseName: string, items: Array<UserPreset>) => {
+    const packFilter = { name: 'Preset Pack (with LUTs)', extensions: ['rrpack'] };
+    const presetFilter = { name: 'Preset File', extensions: ['rrpreset'] };
+    const bundleLuts = referencesLut(items);
+    return {
+      defaultPath: `${baseName}.${bundleLuts ? 'rrpack' : 'rrpreset'}`.replace(/[<>:"/\\|?*]/g, '_'),
+      filters: bundleLuts ? [packFilter, presetFilter] : [presetFilter, packFilter],
+    };
+  };
+
   const handleExport = async (item: UserPreset) => {
     const isFolder = !!item.folder;
     const name = isFolder ? item.folder?.name : item.preset?.name;
@@ -677,8 +703,7 @@ export default function PresetsPanel({
 
     try {
       const filePath = await saveDialog({
-        defaultPath: `${name}.rrpreset`.replace(/[<>:"/\\|?*]/g, '_'),
-        filters: [{ name: 'Preset File', extensions: ['rrpreset'] }],
+        ...exportDialogOptions(name ?? 'preset', itemsToExport),
         title: `Export ${isFolder ? 'Folder' : 'Preset'}`,
       });
 
@@ -696,8 +721,7 @@ export default function PresetsPanel({
     }
     try {
       const filePath = await saveDialog({
-        defaultPath: 'all_presets.rrpreset',
-        filters: [{ name: 'Preset File', extensions: ['rrpreset'] }],
+        ...exportDialogOptions('all_presets', presets),
         title: 'Export All Presets',
       });
 
@@ -831,7 +855,7 @@ export default function PresetsPanel({
               className="p-2 rounded-full hover:bg-surface transition-colors"
               disabled={isLoading}
               onClick={handleImportPresets}
-              title="Import presets from .rrpreset file"
+              title="Import presets from .rrpreset or .rrpack file"
             >
               <FileUp size={18} />
             </button>
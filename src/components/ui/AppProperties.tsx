@@ -11,34 +11,51 @@ export enum Invokes {
   ApplyAdjustmentsToPaths = 'apply_adjustments_to_paths',
   ApplyAutoAdjustmentsToPaths = 'apply_auto_adjustments_to_paths',
   ApplyDenoising = 'apply_denoising',
+  ApplyReferenceWhiteBalance = 'apply_reference_white_balance',
+  BatchExportFilteredImages = 'batch_export_filtered_images',
   BatchExportImages = 'batch_export_images',
   CalculateAutoAdjustments = 'calculate_auto_adjustments',
+  CancelCommunityPreviewGeneration = 'cancel_community_preview_generation',
   CancelExport = 'cancel_export',
+  CancelThumbnailPregeneration = 'cancel_thumbnail_pregeneration',
   CheckAIConnectorStatus = 'check_ai_connector_status',
   ClearAllSidecars = 'clear_all_sidecars',
   ClearAiTags = 'clear_ai_tags',
   ClearAllTags = 'clear_all_tags',
+  ClearPreviewCache = 'clear_preview_cache',
   ClearThumbnailCache = 'clear_thumbnail_cache',
+  ComputeAdaptiveThemeColor = 'compute_adaptive_theme_color',
   CopyFiles = 'copy_files',
   CreateFolder = 'create_folder',
   CreateVirtualCopy = 'create_virtual_copy',
   CullImages = 'cull_images',
   DeleteFolder = 'delete_folder',
+  DetectHdrBrackets = 'detect_hdr_brackets',
   DuplicateFile = 'duplicate_file',
   EstimateBatchExportSize = 'estimate_batch_export_size',
   EstimateExportSize = 'estimate_export_size',
   ExportImage = 'export_image',
+  ExtractPalette = 'extract_palette',
+  FlattenAndSave = 'flatten_and_save',
+  FocusStack = 'focus_stack',
   GenerateAiForegroundMask = 'generate_ai_foreground_mask',
   GenerateAiSkyMask = 'generate_ai_sky_mask',
   GenerateAiSubjectMask = 'generate_ai_subject_mask',
+  GenerateComparison = 'generate_comparison',
+  GenerateCropOverlay = 'generate_crop_overlay',
+  GenerateFullResolutionPreview = 'generate_full_resolution_preview',
   GenerateFullscreenPreview = 'generate_fullscreen_preview',
   GeneratePreviewForPath = 'generate_preview_for_path',
+  GenerateRegionPreview = 'generate_region_preview',
   GenerateHistogram = 'generate_histogram',
+  GenerateRawHistogram = 'generate_raw_histogram',
   GenerateMaskOverlay = 'generate_mask_overlay',
   GeneratePresetPreview = 'generate_preset_preview',
   GenerateThumbnailsProgressive = 'generate_thumbnails_progressive',
   GenerateUncroppedPreview = 'generate_uncropped_preview',
   GenerateWaveform = 'image_processing::generate_waveform',
+  GetCapabilities = 'get_capabilities',
+  GetDisplayInfo = 'get_display_info',
   GetFolderTree = 'get_folder_tree',
   GetLogFilePath = 'get_log_file_path',
   GetPinnedFolderTrees = 'get_pinned_folder_trees',
@@ -49,14 +66,19 @@ export enum Invokes {
   ImportFiles = 'import_files',
   InvokeGenerativeReplace = 'invoke_generative_replace',
   InvokeGenerativeReplaseWithMaskDef = 'invoke_generative_replace_with_mask_def',
+  ListExistingTags = 'list_existing_tags',
   ListImagesInDir = 'list_images_in_dir',
   ListImagesRecursive = 'list_images_recursive',
   LoadImage = 'load_image',
   LoadMetadata = 'load_metadata',
   LoadPresets = 'load_presets',
   LoadSettings = 'load_settings',
+  MergeHdr = 'merge_hdr',
   MoveFiles = 'move_files',
+  PregenerateThumbnails = 'pregenerate_thumbnails',
   ReadExifForPaths = 'read_exif_for_paths',
+  RefreshFolderNode = 'refresh_folder_node',
+  RelinkOriginals = 'relink_originals',
   RemoveTagForPaths = 'remove_tag_for_paths',
   RenameFiles = 'rename_files',
   RenameFolder = 'rename_folder',
@@ -64,15 +86,19 @@ export enum Invokes {
   SaveMetadataAndUpdateThumbnail = 'save_metadata_and_update_thumbnail',
   SaveCollage = 'save_collage',
   SaveDenoisedImage = 'save_denoised_image',
+  SaveFocusStack = 'save_focus_stack',
+  SaveHdr = 'save_hdr',
   SavePanorama = 'save_panorama',
   SavePresets = 'save_presets',
   SaveSettings = 'save_settings',
   SetColorLabelForPaths = 'set_color_label_for_paths',
+  SetRatingForPaths = 'set_rating_for_paths',
   ShowInFinder = 'show_in_finder',
   StartBackgroundIndexing = 'start_background_indexing',
   StitchPanorama = 'stitch_panorama',
   TestAIConnectorConnection = 'test_ai_connector_connection',
   UpdateWindowEffect = 'update_window_effect',
+  ValidateBatchExport = 'validate_batch_export',
   FetchCommunityPresets = 'fetch_community_presets',
   GenerateAllCommunityPreviews = 'generate_all_community_previews',
   SaveCommunityPreset = 'save_community_preset',
@@ -117,6 +143,12 @@ export enum ThumbnailAspectRatio {
   Contain = 'contain',
 }
 
+export interface CameraRawOverrides {
+  blackLevel?: number | null;
+  whiteLevel?: number | null;
+  exposureBias?: number | null;
+}
+
 export interface AppSettings {
   adaptiveEditorTheme?: Theme;
   aiConnectorAddress?: string;
@@ -127,7 +159,12 @@ export interface AppSettings {
   enableHighQualityLivePreviews?: boolean;
   enableAiTagging?: boolean;
   enableExifReading?: boolean;
+  enablePreviewDiskCache?: boolean;
+  exportQualityByFormat?: { [format: string]: number };
+  exportThreadCount?: number;
+  fileLockRetries?: number;
   filterCriteria?: FilterCriteria;
+  hideEmptyFolders?: boolean;
   lastFolderState?: any;
   pinnedFolders?: any;
   lastRootPath: string | null;
@@ -139,8 +176,24 @@ export interface AppSettings {
   adjustmentVisibility?: { [key: string]: boolean };
   activeTreeSection?: string | null;
   rawHighlightCompression?: number;
+  cameraHighlightCompression?: Record<string, number>;
+  rawExposureBias?: number;
+  rawDemosaic?: 'quality' | 'speed';
+  showFolderImageCounts?: boolean;
+  cameraRawOverrides?: Record<string, CameraRawOverrides>;
   processingBackend?: string;
   linuxGpuOptimization?: boolean;
+  windowBounds?: WindowBounds | null;
+}
+
+// Saved by the backend when the main window closes, in physical pixels
+export interface WindowBounds {
+  height: number;
+  maximized: boolean;
+  monitor?: string | null;
+  width: number;
+  x: number;
+  y: number;
 }
 
 export interface BrushSettings {
@@ -204,9 +257,65 @@ export interface Progress {
   total: number;
 }
 
+// Linear sensor-data histogram from generate_raw_histogram, independent of any adjustments
+export interface RawHistogram {
+  blue: Array<number>;
+  clipped: [number, number, number];
+  green: Array<number>;
+  red: Array<number>;
+}
+
+export interface AiModelStatus {
+  id: string;
+  installed: boolean;
+  name: string;
+}
+
+export interface GpuCapabilities {
+  adapterName: string;
+  backend: string;
+  deviceType: string;
+  driver: string;
+  maxTextureDimension: number;
+}
+
+export interface Capabilities {
+  aiModels: Array<AiModelStatus>;
+  appVersion: string;
+  exportFormats: Array<string>;
+  gpu: GpuCapabilities | null;
+  gpuError: string | null;
+  onnxRuntimeAvailable: boolean;
+  platform: string;
+}
+
+export interface DisplayInfo {
+  physicalHeight: number;
+  physicalWidth: number;
+  previewDimension: number;
+  scaleFactor: number;
+}
+
+export enum CropGridType {
+  Diagonals = 'diagonals',
+  GoldenRatio = 'goldenRatio',
+  GoldenSpiralBottomLeft = 'goldenSpiralBottomLeft',
+  GoldenSpiralBottomRight = 'goldenSpiralBottomRight',
+  GoldenSpiralTopLeft = 'goldenSpiralTopLeft',
+  GoldenSpiralTopRight = 'goldenSpiralTopRight',
+  Thirds = 'thirds',
+}
+
+export enum FileHealth {
+  Corrupt = 'corrupt',
+  Locked = 'locked',
+}
+
 export interface SelectedImage {
+  colorProfile?: string | null;
   exif: any;
   height: number;
+  isEmbeddedPreview?: boolean;
   isRaw: boolean;
   isReady: boolean;
   metadata?: any;
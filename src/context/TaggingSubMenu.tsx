@@ -1,4 +1,4 @@
-import { useState, useEffect, useRef } from 'react';
+import { useState, useEffect, useMemo, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { X, Plus } from 'lucide-react';
 import { motion, AnimatePresence } from 'framer-motion';
@@ -10,9 +10,22 @@ interface TaggingSubMenuProps {
   onTagsChanged: (paths: string[], newTags: { tag: string; isUser: boolean }[]) => void;
   appSettings: any;
   hideContextMenu: () => void;
+  rootPath: string | null;
+}
+
+interface TagUsage {
+  tag: string;
+  count: number;
+}
+
+interface ExistingTags {
+  tags: Array<TagUsage>;
+  colorLabels: Array<TagUsage>;
+  complete: boolean;
 }
 
 const USER_TAG_PREFIX = 'user:';
+const MAX_SUGGESTIONS = 6;
 
 const tagVariants = {
   visible: { opacity: 1, scale: 1, transition: { type: 'spring', stiffness: 500, damping: 30 } },
@@ -25,11 +38,42 @@ export default function TaggingSubMenu({
   onTagsChanged,
   appSettings,
   hideContextMenu,
+  rootPath,
 }: TaggingSubMenuProps) {
   const [tags, setTags] = useState<{ tag: string; isUser: boolean }[]>(initialTags);
   const [inputValue, setInputValue] = useState('');
+  const [existingTags, setExistingTags] = useState<Array<TagUsage>>([]);
   const inputRef = useRef<HTMLInputElement>(null);
 
+  useEffect(() => {
+    if (!rootPath) {
+      return;
+    }
+    invoke(Invokes.ListExistingTags, { rootPath })
+      .then((result: any) => setExistingTags((result as ExistingTags).tags))
+      .catch((err) => console.error(`Failed to load existing tags: ${err}`));
+  }, [rootPath]);
+
+  const suggestions = useMemo(() => {
+    const query = inputValue.trim().toLowerCase();
+    if (!query) {
+      return [];
+    }
+    const seen = new Set(tags.map((t) => t.tag));
+    const result: Array<string> = [];
+    for (const { tag } of existingTags) {
+      const name = tag.startsWith(USER_TAG_PREFIX) ? tag.slice(USER_TAG_PREFIX.length) : tag;
+      if (name.startsWith(query) && name !== query && !seen.has(name)) {
+        seen.add(name);
+        result.push(name);
+        if (result.length >= MAX_SUGGESTIONS) {
+          break;
+        }
+      }
+    }
+    return result;
+  }, [existingTags, inputValue, tags]);
+
   useEffect(() => {
     setTags(initialTags);
   }, [initialTags]);
@@ -71,6 +115,10 @@ export default function TaggingSubMenu({
       e.preventDefault();
       handleAddTag(inputValue);
     }
+    if (e.key === 'Tab' && suggestions.length > 0) {
+      e.preventDefault();
+      setInputValue(suggestions[0]);
+    }
     if (e.key === 'Escape') {
       hideContextMenu();
     }
@@ -137,6 +185,20 @@ export default function TaggingSubMenu({
         </button>
       </div>
 
+      {suggestions.length > 0 && (
+        <div className="flex flex-wrap gap-1 mb-2">
+          {suggestions.map((suggestion: string) => (
+            <button
+              key={suggestion}
+              onClick={() => handleAddTag(suggestion)}
+              className="bg-surface text-text-secondary hover:bg-card-active hover:text-text-primary text-xs font-medium px-2 py-1 rounded"
+            >
+              {suggestion}
+            </button>
+          ))}
+        </div>
+      )}
+
       {shortcuts.length > 0 && (
         <div>
           <p className="text-xs font-semibold text-text-primary px-1 py-1">SHORTCUTS</p>
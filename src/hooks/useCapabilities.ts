@@ -0,0 +1,32 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Capabilities, Invokes } from '../components/ui/AppProperties';
+
+let capabilitiesRequest: Promise<Capabilities> | null = null;
+
+// Capabilities don't change while the app runs, so every caller shares one request.
+export const useCapabilities = () => {
+  const [capabilities, setCapabilities] = useState<Capabilities | null>(null);
+
+  useEffect(() => {
+    if (!capabilitiesRequest) {
+      capabilitiesRequest = invoke<Capabilities>(Invokes.GetCapabilities);
+    }
+    let isCancelled = false;
+    capabilitiesRequest
+      .then((result) => {
+        if (!isCancelled) {
+          setCapabilities(result);
+        }
+      })
+      .catch((err) => {
+        console.error('Failed to load capabilities:', err);
+        capabilitiesRequest = null;
+      });
+    return () => {
+      isCancelled = true;
+    };
+  }, []);
+
+  return capabilities;
+};
@@ -389,11 +389,18 @@ export function usePresets(currentAdjustments: Adjustments) {
   }, [savePresetsToBackend]);
 
   const importPresetsFromFile = useCallback(
-    async (filePath: string) => {
+    async (filePath: string, forceAdd = false): Promise<number> => {
       setIsLoading(true);
+      const countPresets = (list: Array<any>) =>
+        list.reduce((count, item) => count + (item.folder ? item.folder.children.length : 1), 0);
       try {
-        const updatedPresetList: Array<any> = await invoke(Invokes.HandleImportPresetsFromFile, { filePath });
+        const countBefore = countPresets(presets);
+        const updatedPresetList: Array<any> = await invoke(Invokes.HandleImportPresetsFromFile, {
+          filePath,
+          forceAdd,
+        });
         setPresets(updatedPresetList);
+        return countPresets(updatedPresetList) - countBefore;
       } catch (error) {
         console.error('Failed to import presets from file:', error);
         throw error;
@@ -401,7 +408,7 @@ export function usePresets(currentAdjustments: Adjustments) {
         setIsLoading(false);
       }
     },
-    [setPresets],
+    [presets, setPresets],
   );
 
   const importLegacyPresetsFromFile = useCallback(
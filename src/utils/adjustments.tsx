@@ -64,6 +64,9 @@ export enum DetailsAdjustment {
   ColorNoiseReduction = 'colorNoiseReduction',
   LumaNoiseReduction = 'lumaNoiseReduction',
   Sharpness = 'sharpness',
+  SharpeningMode = 'sharpeningMode',
+  SharpeningRadius = 'sharpeningRadius',
+  SharpeningThreshold = 'sharpeningThreshold',
   ChromaticAberrationRedCyan = 'chromaticAberrationRedCyan',
   ChromaticAberrationBlueYellow = 'chromaticAberrationBlueYellow',
 }
@@ -73,6 +76,7 @@ export enum Effect {
   FilmBaseColor = 'filmBaseColor',
   GrainAmount = 'grainAmount',
   GrainRoughness = 'grainRoughness',
+  GrainSeed = 'grainSeed',
   GrainSize = 'grainSize',
   LutIntensity = 'lutIntensity',
   NegativeBlueBalance = 'negativeBlueBalance',
@@ -114,10 +118,12 @@ export interface Adjustments {
   enableNegativeConversion: boolean;
   exposure: number;
   filmBaseColor: string;
+  fillColor: string | null;
   flipHorizontal: boolean;
   flipVertical: boolean;
   grainAmount: number;
   grainRoughness: number;
+  grainSeed: number | null;
   grainSize: number;
   highlights: number;
   hsl: Hsl;
@@ -137,6 +143,9 @@ export interface Adjustments {
   saturation: number;
   sectionVisibility: SectionVisibility;
   shadows: number;
+  sharpeningMode: 'unsharp' | 'ratio';
+  sharpeningRadius: number;
+  sharpeningThreshold: number;
   sharpness: number;
   showClipping: boolean;
   structure: number;
@@ -232,12 +241,35 @@ export interface MaskAdjustments {
   whites: number;
 }
 
+export enum RangeMaskMode {
+  Color = 'color',
+  Luminance = 'luminance',
+}
+
+export interface RangeMask {
+  hue: number;
+  hueRange: number;
+  luminanceMax: number;
+  luminanceMin: number;
+  mode: RangeMaskMode;
+  smoothness: number;
+}
+
+export const INITIAL_RANGE_MASK: Omit<RangeMask, 'mode'> = {
+  hue: 210,
+  hueRange: 30,
+  luminanceMax: 100,
+  luminanceMin: 0,
+  smoothness: 50,
+};
+
 export interface MaskContainer {
   adjustments: MaskAdjustments;
   id?: any;
   invert: boolean;
   name: string;
   opacity: number;
+  rangeMask?: RangeMask | null;
   subMasks: Array<SubMask>;
   visible: boolean;
 }
@@ -387,10 +419,12 @@ export const INITIAL_ADJUSTMENTS: Adjustments = {
   enableNegativeConversion: false,
   exposure: 0,
   filmBaseColor: '#ff8800',
+  fillColor: null,
   flipHorizontal: false,
   flipVertical: false,
   grainAmount: 0,
   grainRoughness: 50,
+  grainSeed: null,
   grainSize: 25,
   highlights: 0,
   hsl: {
@@ -425,6 +459,9 @@ export const INITIAL_ADJUSTMENTS: Adjustments = {
     effects: true,
   },
   shadows: 0,
+  sharpeningMode: 'unsharp',
+  sharpeningRadius: 2,
+  sharpeningThreshold: 0,
   sharpness: 0,
   showClipping: false,
   structure: 0,
@@ -517,6 +554,7 @@ export const COPYABLE_ADJUSTMENT_KEYS: Array<string> = [
   Effect.FilmBaseColor,
   Effect.GrainAmount,
   Effect.GrainRoughness,
+  Effect.GrainSeed,
   Effect.GrainSize,
   BasicAdjustment.Highlights,
   ColorAdjustment.Hsl,
@@ -531,6 +569,9 @@ export const COPYABLE_ADJUSTMENT_KEYS: Array<string> = [
   ColorAdjustment.Saturation,
   'sectionVisibility',
   BasicAdjustment.Shadows,
+  DetailsAdjustment.SharpeningMode,
+  DetailsAdjustment.SharpeningRadius,
+  DetailsAdjustment.SharpeningThreshold,
   DetailsAdjustment.Sharpness,
   'showClipping',
   DetailsAdjustment.Structure,
@@ -572,6 +613,9 @@ export const ADJUSTMENT_SECTIONS: Sections = {
     DetailsAdjustment.Structure,
     DetailsAdjustment.Centré,
     DetailsAdjustment.Sharpness,
+    DetailsAdjustment.SharpeningMode,
+    DetailsAdjustment.SharpeningRadius,
+    DetailsAdjustment.SharpeningThreshold,
     DetailsAdjustment.LumaNoiseReduction,
     DetailsAdjustment.ColorNoiseReduction,
     DetailsAdjustment.ChromaticAberrationRedCyan,
@@ -582,6 +626,7 @@ export const ADJUSTMENT_SECTIONS: Sections = {
     Effect.FilmBaseColor,
     Effect.GrainAmount,
     Effect.GrainRoughness,
+    Effect.GrainSeed,
     Effect.GrainSize,
     Effect.LutIntensity,
     Effect.NegativeBlueBalance,